@@ -0,0 +1,323 @@
+use crate::types::{
+    Column, ColumnSuggestion, ColumnType, ContextMenu, ContextMenuKind, OperationType,
+    SecretDisplay, Setting, SettingOperations, Surface, UiOptions,
+};
+use crate::Error;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A builder for a `Column`
+///
+/// Columns default to non-nullable, non-secret, not ignored for any operation and without suggestions:
+///
+/// ```ignore
+/// let column = ColumnBuilder::new("id", "ID", ColumnType::new_scalar(InnerColumnType::Integer { .. }))
+///     .description("The ID of the entry")
+///     .primary_key(true)
+///     .build();
+/// ```
+pub struct ColumnBuilder {
+    column: Column,
+}
+
+impl ColumnBuilder {
+    /// Creates a new builder for a column with the given id, friendly name and type
+    pub fn new(id: impl Into<String>, name: impl Into<String>, column_type: ColumnType) -> Self {
+        Self {
+            column: Column {
+                id: id.into(),
+                name: name.into(),
+                description: String::new(),
+                column_type,
+                primary_key: false,
+                alternate_key: false,
+                nullable: false,
+                suggestions: ColumnSuggestion::None {},
+                secret: false,
+                secret_display: SecretDisplay::Masked,
+                ignored_for: vec![],
+                surfaces: vec![],
+                filter_only: false,
+                deprecated: None,
+                default: None,
+                indexed: false,
+                name_localizations: HashMap::new(),
+                description_localizations: HashMap::new(),
+                group: None,
+            },
+        }
+    }
+
+    /// Sets the description of the column
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.column.description = description.into();
+        self
+    }
+
+    /// Sets whether the column is a primary key
+    pub fn primary_key(mut self, primary_key: bool) -> Self {
+        self.column.primary_key = primary_key;
+        self
+    }
+
+    /// Sets whether the column is part of the alternate key
+    pub fn alternate_key(mut self, alternate_key: bool) -> Self {
+        self.column.alternate_key = alternate_key;
+        self
+    }
+
+    /// Sets whether the column is nullable
+    pub fn nullable(mut self, nullable: bool) -> Self {
+        self.column.nullable = nullable;
+        self
+    }
+
+    /// Sets the group the column is shown under
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.column.group = Some(group.into());
+        self
+    }
+
+    /// Sets static suggestions for the column
+    pub fn suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.column.suggestions = ColumnSuggestion::Static { suggestions };
+        self
+    }
+
+    /// Offers suggestions fetched from the autocompleter of the setting for the column
+    pub fn dynamic_suggestions(mut self) -> Self {
+        self.column.suggestions = ColumnSuggestion::Dynamic {};
+        self
+    }
+
+    /// Sets whether the column is secret
+    pub fn secret(mut self, secret: bool) -> Self {
+        self.column.secret = secret;
+        self
+    }
+
+    /// Sets how the value of the (secret) column is displayed in views
+    pub fn secret_display(mut self, secret_display: SecretDisplay) -> Self {
+        self.column.secret_display = secret_display;
+        self
+    }
+
+    /// Sets the operations the column is ignored for
+    pub fn ignored_for(mut self, ignored_for: Vec<OperationType>) -> Self {
+        self.column.ignored_for = ignored_for;
+        self
+    }
+
+    /// Sets the surfaces the column is exposed on
+    pub fn surfaces(mut self, surfaces: Vec<Surface>) -> Self {
+        self.column.surfaces = surfaces;
+        self
+    }
+
+    /// Sets whether the column is only used to scope queries
+    pub fn filter_only(mut self, filter_only: bool) -> Self {
+        self.column.filter_only = filter_only;
+        self
+    }
+
+    /// Marks the column as deprecated with a message pointing users to the replacement
+    pub fn deprecated(mut self, note: impl Into<String>) -> Self {
+        self.column.deprecated = Some(note.into());
+        self
+    }
+
+    /// Sets the default value of the column
+    pub fn default_value(mut self, default: Value) -> Self {
+        self.column.default = Some(default);
+        self
+    }
+
+    /// Sets whether the column is indexed by the executor
+    pub fn indexed(mut self, indexed: bool) -> Self {
+        self.column.indexed = indexed;
+        self
+    }
+
+    /// Adds a localized name for the given Discord locale
+    pub fn name_localization(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.column
+            .name_localizations
+            .insert(locale.into(), name.into());
+        self
+    }
+
+    /// Adds a localized description for the given Discord locale
+    pub fn description_localization(
+        mut self,
+        locale: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.column
+            .description_localizations
+            .insert(locale.into(), description.into());
+        self
+    }
+
+    /// Builds the column
+    pub fn build(self) -> Column {
+        self.column
+    }
+}
+
+/// A builder for a `Setting`
+///
+/// The title template defaults to empty (the name of the setting is used as the title) and no operations are supported until
+/// `operations` is called
+pub struct SettingBuilder<SettingsData: Clone> {
+    setting: Setting<SettingsData>,
+    columns: Vec<Column>,
+}
+
+impl<SettingsData: Clone> SettingBuilder<SettingsData> {
+    /// Creates a new builder for a setting with the given id, name and description
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            setting: Setting {
+                id: id.into(),
+                name: name.into(),
+                title_template: String::new(),
+                description: description.into(),
+                columns: Arc::new(vec![]),
+                operations: SettingOperations {
+                    view: None,
+                    create: None,
+                    update: None,
+                    delete: None,
+                    validator: None,
+                    authorizer: None,
+                    hooks: None,
+                    autocompleter: None,
+                },
+                ui_options: UiOptions::default(),
+                version_column: None,
+                deprecated_operations: HashMap::new(),
+                strict_columns: false,
+                name_localizations: HashMap::new(),
+                description_localizations: HashMap::new(),
+                required_permissions: None,
+                context_menus: vec![],
+            },
+            columns: vec![],
+        }
+    }
+
+    /// Sets the title template, used for the title of the embeds of an entry
+    pub fn title_template(mut self, title_template: impl Into<String>) -> Self {
+        self.setting.title_template = title_template.into();
+        self
+    }
+
+    /// Adds a column
+    pub fn column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Adds multiple columns
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Column>) -> Self {
+        self.columns.extend(columns);
+        self
+    }
+
+    /// Sets the supported operations
+    pub fn operations(mut self, operations: SettingOperations<SettingsData>) -> Self {
+        self.setting.operations = operations;
+        self
+    }
+
+    /// Sets the UI options
+    pub fn ui_options(mut self, ui_options: UiOptions) -> Self {
+        self.setting.ui_options = ui_options;
+        self
+    }
+
+    /// Sets the integer column used for optimistic concurrency control
+    pub fn version_column(mut self, version_column: impl Into<String>) -> Self {
+        self.setting.version_column = Some(version_column.into());
+        self
+    }
+
+    /// Marks an operation as deprecated with a message pointing users to the replacement
+    pub fn deprecated_operation(
+        mut self,
+        operation_type: OperationType,
+        note: impl Into<String>,
+    ) -> Self {
+        self.setting
+            .deprecated_operations
+            .insert(operation_type, note.into());
+        self
+    }
+
+    /// Sets whether view should error if the executor does not return a non-nullable column
+    pub fn strict_columns(mut self, strict_columns: bool) -> Self {
+        self.setting.strict_columns = strict_columns;
+        self
+    }
+
+    /// Adds a localized name for the given Discord locale
+    pub fn name_localization(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.setting
+            .name_localizations
+            .insert(locale.into(), name.into());
+        self
+    }
+
+    /// Adds a localized description for the given Discord locale
+    pub fn description_localization(
+        mut self,
+        locale: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.setting
+            .description_localizations
+            .insert(locale.into(), description.into());
+        self
+    }
+
+    /// Sets the (Discord) permission bits a member needs to use the setting's commands
+    pub fn required_permissions(mut self, required_permissions: u64) -> Self {
+        self.setting.required_permissions = Some(required_permissions);
+        self
+    }
+
+    /// Adds a context menu command that performs an operation on the targeted user or message
+    pub fn context_menu(
+        mut self,
+        kind: ContextMenuKind,
+        name: impl Into<String>,
+        target_column: impl Into<String>,
+        operation_type: OperationType,
+    ) -> Self {
+        self.setting.context_menus.push(ContextMenu {
+            kind,
+            name: name.into(),
+            target_column: target_column.into(),
+            operation_type,
+        });
+        self
+    }
+
+    /// Builds the setting, checking that there is at least one primary key and that the schema is valid
+    /// (see `Setting::validate_schema`)
+    pub fn build(mut self) -> Result<Setting<SettingsData>, Error> {
+        if !self.columns.iter().any(|c| c.primary_key) {
+            return Err(format!("Setting `{}`: no primary key column", self.setting.id).into());
+        }
+
+        self.setting.columns = Arc::new(self.columns);
+        self.setting.validate_schema()?;
+
+        Ok(self.setting)
+    }
+}
@@ -0,0 +1,385 @@
+use crate::types::{
+    Filter, SettingCreator, SettingDeleter, SettingOperations, SettingUpdater, SettingView, SortBy,
+};
+use crate::Error;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A store for the results of `SettingView::view`, keyed by the (serialized) filters
+///
+/// Implement this to back the cache with e.g. moka or an external store, `TtlViewCache` is a simple
+/// in-process implementation
+#[async_trait]
+pub trait ViewCache: Send + Sync {
+    /// Returns the cached entries for the key, if present and not expired
+    async fn get(&self, key: &str) -> Option<Vec<indexmap::IndexMap<String, Value>>>;
+
+    /// Caches the entries for the key
+    async fn insert(&self, key: String, entries: Vec<indexmap::IndexMap<String, Value>>);
+
+    /// Removes all cached entries, called after every successful create/update/delete
+    async fn invalidate(&self);
+}
+
+/// The cached entries of a key along with when they were cached
+type CacheEntry = (Instant, Vec<indexmap::IndexMap<String, Value>>);
+
+/// An in-process `ViewCache` where entries expire after a fixed time to live
+pub struct TtlViewCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl TtlViewCache {
+    /// Creates a new cache where entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Locks the entries, recovering them if the lock is poisoned
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CacheEntry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl ViewCache for TtlViewCache {
+    async fn get(&self, key: &str) -> Option<Vec<indexmap::IndexMap<String, Value>>> {
+        match self.lock().get(key) {
+            Some((inserted_at, rows)) if inserted_at.elapsed() < self.ttl => Some(rows.clone()),
+            _ => None,
+        }
+    }
+
+    async fn insert(&self, key: String, rows: Vec<indexmap::IndexMap<String, Value>>) {
+        let mut entries = self.lock();
+        entries.retain(|_, (inserted_at, _)| inserted_at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), rows));
+    }
+
+    async fn invalidate(&self) {
+        self.lock().clear();
+    }
+}
+
+/// A read-through cache in front of a `SettingView`
+///
+/// Only `view` is cached, keyed by its filters. Paged, sorted, filtered and count queries are passed
+/// straight to the wrapped view (executors that do not implement them fall back to the cached `view`).
+///
+/// Consistency guarantees:
+/// - when installed through `SettingOperations::with_view_cache`, every successful create/update/delete of
+///   the setting invalidates the cache, so a view started after a write has returned sees that write
+/// - a view that races with a write may cache the entries from before the write, these are served until
+///   the next write or until they expire
+/// - writes made outside of the setting (e.g. directly to the database) are only seen once the cached
+///   entries expire
+/// - the key does not include the context passed to the executor, settings whose results depend on the
+///   context beyond the filters (e.g. a guild id that is not a filter) should not be cached
+pub struct CachedView<SettingsData: Clone> {
+    inner: Arc<dyn SettingView<SettingsData>>,
+    cache: Arc<dyn ViewCache>,
+}
+
+impl<SettingsData: Clone> CachedView<SettingsData> {
+    /// Wraps a view with the given cache
+    pub fn new(inner: Arc<dyn SettingView<SettingsData>>, cache: Arc<dyn ViewCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl<SettingsData: Clone + Send + Sync> SettingView<SettingsData> for CachedView<SettingsData> {
+    async fn view<'a>(
+        &self,
+        context: &SettingsData,
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+        let key = serde_json::to_string(&filters)?;
+
+        if let Some(rows) = self.cache.get(&key).await {
+            return Ok(rows);
+        }
+
+        let rows = self.inner.view(context, filters).await?;
+        self.cache.insert(key, rows.clone()).await;
+
+        Ok(rows)
+    }
+
+    async fn view_page<'a>(
+        &self,
+        context: &SettingsData,
+        filters: indexmap::IndexMap<String, Value>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+        self.inner.view_page(context, filters, limit, offset).await
+    }
+
+    async fn view_sorted_page<'a>(
+        &self,
+        context: &SettingsData,
+        filters: indexmap::IndexMap<String, Value>,
+        sort: &SortBy,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+        self.inner
+            .view_sorted_page(context, filters, sort, limit, offset)
+            .await
+    }
+
+    async fn view_filtered<'a>(
+        &self,
+        context: &SettingsData,
+        filters: indexmap::IndexMap<String, Filter>,
+    ) -> Result<Option<Vec<indexmap::IndexMap<String, Value>>>, Error> {
+        self.inner.view_filtered(context, filters).await
+    }
+
+    async fn count<'a>(
+        &self,
+        context: &SettingsData,
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Option<usize>, Error> {
+        self.inner.count(context, filters).await
+    }
+}
+
+/// Wraps a create/update/delete executor, invalidating the cache after every successful operation
+struct Invalidating<E: ?Sized> {
+    inner: Arc<E>,
+    cache: Arc<dyn ViewCache>,
+}
+
+#[async_trait]
+impl<SettingsData: Clone + Send + Sync> SettingCreator<SettingsData>
+    for Invalidating<dyn SettingCreator<SettingsData>>
+{
+    async fn create<'a>(
+        &self,
+        context: &SettingsData,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let state = self.inner.create(context, state).await?;
+        self.cache.invalidate().await;
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl<SettingsData: Clone + Send + Sync> SettingUpdater<SettingsData>
+    for Invalidating<dyn SettingUpdater<SettingsData>>
+{
+    async fn update<'a>(
+        &self,
+        context: &SettingsData,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let state = self.inner.update(context, state).await?;
+        self.cache.invalidate().await;
+        Ok(state)
+    }
+
+    async fn update_versioned<'a>(
+        &self,
+        context: &SettingsData,
+        state: indexmap::IndexMap<String, Value>,
+        version_column: &'a str,
+        expected_version: i64,
+    ) -> Result<Option<indexmap::IndexMap<String, Value>>, Error> {
+        let state = self
+            .inner
+            .update_versioned(context, state, version_column, expected_version)
+            .await?;
+
+        // None means nothing was updated, the update is then made through `update`
+        if state.is_some() {
+            self.cache.invalidate().await;
+        }
+
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl<SettingsData: Clone + Send + Sync> SettingDeleter<SettingsData>
+    for Invalidating<dyn SettingDeleter<SettingsData>>
+{
+    async fn delete<'a>(
+        &self,
+        context: &SettingsData,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<(), Error> {
+        self.inner.delete(context, state).await?;
+        self.cache.invalidate().await;
+        Ok(())
+    }
+}
+
+impl<SettingsData: Clone + Send + Sync + 'static> SettingOperations<SettingsData> {
+    /// Caches the results of view in `cache` (see `CachedView`), invalidating it whenever an entry is
+    /// created, updated or deleted through these operations
+    ///
+    /// This must be called after the view/create/update/delete executors have been set
+    pub fn with_view_cache(mut self, cache: Arc<dyn ViewCache>) -> Self {
+        self.view = self
+            .view
+            .map(|view| Arc::new(CachedView::new(view, cache.clone())) as _);
+        self.create = self.create.map(|inner| {
+            Arc::new(Invalidating {
+                inner,
+                cache: cache.clone(),
+            }) as _
+        });
+        self.update = self.update.map(|inner| {
+            Arc::new(Invalidating {
+                inner,
+                cache: cache.clone(),
+            }) as _
+        });
+        self.delete = self.delete.map(|inner| {
+            Arc::new(Invalidating {
+                inner,
+                cache: cache.clone(),
+            }) as _
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemorySettings;
+    use serde_json::json;
+
+    fn row(id: i64, name: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "id".to_string() => json!(id),
+            "name".to_string() => json!(name),
+        }
+    }
+
+    /// Operations backed by an in-memory executor with a view cache that does not expire during the test
+    fn cached_operations(executor: &InMemorySettings) -> SettingOperations<()> {
+        SettingOperations::from(executor.clone())
+            .with_view_cache(Arc::new(TtlViewCache::new(Duration::from_secs(3600))))
+    }
+
+    async fn view(operations: &SettingOperations<()>) -> Vec<indexmap::IndexMap<String, Value>> {
+        operations
+            .view
+            .as_ref()
+            .unwrap()
+            .view(&(), indexmap::IndexMap::new())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_ttl() {
+        let cache = TtlViewCache::new(Duration::from_secs(3600));
+        cache.insert("key".to_string(), vec![row(1, "a")]).await;
+        assert_eq!(cache.get("key").await, Some(vec![row(1, "a")]));
+        assert_eq!(cache.get("other").await, None);
+
+        let cache = TtlViewCache::new(Duration::ZERO);
+        cache.insert("key".to_string(), vec![row(1, "a")]).await;
+        assert_eq!(cache.get("key").await, None);
+    }
+
+    #[tokio::test]
+    async fn poisoned_cache_keeps_working() {
+        let cache = TtlViewCache::new(Duration::from_secs(3600));
+        cache.insert("key".to_string(), vec![row(1, "a")]).await;
+
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _entries = cache.entries.lock().unwrap();
+            panic!("poison the lock");
+        }));
+        assert!(cache.entries.is_poisoned());
+
+        assert_eq!(cache.get("key").await, Some(vec![row(1, "a")]));
+        cache.insert("other".to_string(), vec![row(2, "b")]).await;
+        assert_eq!(cache.get("other").await, Some(vec![row(2, "b")]));
+        cache.invalidate().await;
+        assert_eq!(cache.get("key").await, None);
+    }
+
+    #[tokio::test]
+    async fn create_invalidates_cache() {
+        let executor = InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a")]);
+        let operations = cached_operations(&executor);
+        assert_eq!(view(&operations).await, vec![row(1, "a")]);
+
+        // Writes made around the operations are not seen until the cache is invalidated
+        SettingCreator::<()>::create(&executor, &(), row(2, "b"))
+            .await
+            .unwrap();
+        assert_eq!(view(&operations).await, vec![row(1, "a")]);
+
+        operations
+            .create
+            .as_ref()
+            .unwrap()
+            .create(&(), row(3, "c"))
+            .await
+            .unwrap();
+        assert_eq!(
+            view(&operations).await,
+            vec![row(1, "a"), row(2, "b"), row(3, "c")]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_invalidates_cache() {
+        let executor = InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a")]);
+        let operations = cached_operations(&executor);
+        assert_eq!(view(&operations).await, vec![row(1, "a")]);
+
+        let updater = operations.update.as_ref().unwrap();
+        updater.update(&(), row(1, "b")).await.unwrap();
+        assert_eq!(view(&operations).await, vec![row(1, "b")]);
+
+        // Add a version behind the cache's back, the cached entry is kept
+        let mut current = row(1, "b");
+        current.insert("version".to_string(), json!(0));
+        SettingUpdater::<()>::update(&executor, &(), current)
+            .await
+            .unwrap();
+        assert_eq!(view(&operations).await, vec![row(1, "b")]);
+
+        let mut versioned = row(1, "c");
+        versioned.insert("version".to_string(), json!(1));
+        updater
+            .update_versioned(&(), versioned.clone(), "version", 0)
+            .await
+            .unwrap();
+        assert_eq!(view(&operations).await, vec![versioned]);
+    }
+
+    #[tokio::test]
+    async fn delete_invalidates_cache() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a"), row(2, "b")]);
+        let operations = cached_operations(&executor);
+        assert_eq!(view(&operations).await, vec![row(1, "a"), row(2, "b")]);
+
+        operations
+            .delete
+            .as_ref()
+            .unwrap()
+            .delete(&(), row(1, "a"))
+            .await
+            .unwrap();
+        assert_eq!(view(&operations).await, vec![row(2, "b")]);
+    }
+}
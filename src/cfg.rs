@@ -1,525 +1,3339 @@
 use crate::Error;
 
-use super::types::{ColumnType, InnerColumnType, OperationType, Setting};
+use super::types::{
+    Column, ColumnType, Filter, InnerColumnType, OperationType, Setting, SortBy,
+    VERSION_CONFLICT_ERROR,
+};
 use serde_json::{Number, Value};
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+use std::str::FromStr;
+
+/// Parses a boolean from a string
+///
+/// If strict, only `true` and `false` are accepted, otherwise `yes`/`no`, `on`/`off`, `y`/`n` and `1`/`0` are
+/// also accepted. Parsing is case-insensitive and ignores surrounding whitespace
+pub(crate) fn parse_bool(s: &str, strict: bool) -> Option<bool> {
+    let s = s.trim().to_lowercase();
+
+    match s.as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ if strict => None,
+        "yes" | "y" | "on" | "1" => Some(true),
+        "no" | "n" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// The string kinds whose values are Discord IDs (snowflakes)
+pub(crate) const SNOWFLAKE_KINDS: [&str; 4] = ["channel", "user", "role", "guild_id"];
+
+/// Returns whether the string is a valid Discord snowflake (a non-zero u64 that fits in an i64)
+pub(crate) fn is_snowflake(s: &str) -> bool {
+    matches!(s.parse::<u64>(), Ok(v) if v != 0 && v <= i64::MAX as u64)
+}
+
+/// Parses an interval string such as `1d2h`, `90m`, `1w 3d` or `3600s` into a duration
+///
+/// Supported units are `w`, `d`, `h`, `m` and `s`, components may be separated by whitespace. A plain
+/// number without any unit is treated as a number of seconds
+pub fn parse_interval(s: &str) -> Result<chrono::Duration, Error> {
+    let s = s.trim();
+
+    if s.is_empty() {
+        return Err("Interval cannot be empty".into());
+    }
+
+    let total_secs = if let Ok(secs) = s.parse::<i64>() {
+        secs
+    } else {
+        let mut total_secs: i64 = 0;
+        let mut number = String::new();
+
+        for c in s.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+
+            if c.is_whitespace() {
+                if !number.is_empty() {
+                    return Err(format!("Missing unit after `{}` in interval", number).into());
+                }
+
+                continue;
+            }
+
+            let multiplier: i64 = match c.to_ascii_lowercase() {
+                'w' => 7 * 24 * 60 * 60,
+                'd' => 24 * 60 * 60,
+                'h' => 60 * 60,
+                'm' => 60,
+                's' => 1,
+                _ => return Err(format!("Unknown interval unit `{}`", c).into()),
+            };
+
+            if number.is_empty() {
+                return Err(format!("Missing number before unit `{}` in interval", c).into());
+            }
+
+            let value = number
+                .parse::<i64>()
+                .map_err(|_| format!("Interval component `{}{}` is too large", number, c))?;
+
+            total_secs = value
+                .checked_mul(multiplier)
+                .and_then(|v| total_secs.checked_add(v))
+                .ok_or("Interval is too large")?;
+
+            number.clear();
+        }
+
+        if !number.is_empty() {
+            return Err(format!("Missing unit after `{}` in interval", number).into());
+        }
+
+        total_secs
+    };
+
+    if total_secs < 0 {
+        return Err("Interval cannot be negative".into());
+    }
+
+    chrono::Duration::try_seconds(total_secs).ok_or_else(|| "Interval is too large".into())
+}
+
+/// How `merge_value` combines two arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMerge {
+    /// The array being merged in replaces the existing array
+    #[default]
+    Replace,
+    /// The array being merged in is appended to the existing array
+    Concat,
+}
+
+/// Deep-merges `other` into `target`, useful for update executors that fetch the current JSON value and merge
+/// a partial update into it
+///
+/// Maps are merged recursively key by key. Arrays are combined according to `arrays`. Everything else,
+/// including values of mismatching types (e.g. a map merged into a string) and nulls, replaces the existing value
+pub fn merge_value(target: &mut Value, other: Value, arrays: ArrayMerge) {
+    match (target, other) {
+        (Value::Object(target), Value::Object(other)) => {
+            for (key, value) in other {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value, arrays),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(other)) if arrays == ArrayMerge::Concat => {
+            target.extend(other);
+        }
+        (target, other) => {
+            *target = other;
+        }
+    }
+}
+
+/// The reason a value failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// The value is of the wrong type (e.g. a String for an Integer column)
+    TypeMismatch,
+    /// The value is null but the column is not nullable
+    NotNullable,
+    /// The value is shorter than allowed (String length, Array items)
+    TooShort,
+    /// The value is longer than allowed (String length, JSON bytes, Array items)
+    TooLong,
+    /// The value is outside the range of the column
+    OutOfRange,
+    /// The value is not one of the allowed values of the column
+    NotAllowed,
+    /// The value is of the right type but could not be parsed (e.g. an invalid ID, URL or interval)
+    InvalidFormat,
+    /// An Array with unique items contains a duplicate
+    Duplicate,
+    /// A BitFlag value sets bits that are not known to the column
+    UnknownBits,
+    /// An Object value contains a field that is not part of the column
+    UnknownField,
+    /// An Object value is missing a required field
+    MissingField,
+    /// The column itself is misconfigured (e.g. a BitFlag column with no values)
+    InvalidSchema,
+}
+
+/// A value that failed validation against its column
+///
+/// Settings operations return this boxed in `Error`, use `downcast_ref::<ValidationError>()` to find
+/// out which column failed and why
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    /// The id of the column that failed validation, nested fields of Object columns are given as `column.field`
+    pub column_id: String,
+
+    /// The operation being performed, if known
+    pub operation: Option<OperationType>,
+
+    /// The reason the value failed validation
+    pub kind: ValidationErrorKind,
+
+    /// A human readable description of the failure
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(column_id: &str, kind: ValidationErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            column_id: column_id.to_string(),
+            operation: None,
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the operation the error occurred in
+    pub fn with_operation(mut self, operation: OperationType) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Validation error in column {}, {}",
+            self.column_id, self.message
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
 
 /// Parse a value against the schema's column type
-fn validate_value(
+///
+/// With the `tracing` feature, the time taken is recorded as a trace event and failures as a debug event
+pub(crate) fn validate_value(
     v: Value,
     column_type: &ColumnType,
     column_id: &str,
     nullable: bool,
-) -> Result<Value, Error> {
+) -> Result<Value, ValidationError> {
+    #[cfg(feature = "tracing")]
+    {
+        let start = std::time::Instant::now();
+        let result = validate_value_untraced(v, column_type, column_id, nullable);
+        let elapsed_us = start.elapsed().as_micros() as u64;
+
+        match result {
+            Ok(_) => tracing::trace!(column = column_id, elapsed_us, "validated value"),
+            Err(ref e) => tracing::debug!(
+                column = column_id,
+                kind = ?e.kind,
+                elapsed_us,
+                error = %e,
+                "value failed validation"
+            ),
+        }
+
+        result
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    validate_value_untraced(v, column_type, column_id, nullable)
+}
+
+fn validate_value_untraced(
+    v: Value,
+    column_type: &ColumnType,
+    column_id: &str,
+    nullable: bool,
+) -> Result<Value, ValidationError> {
     if v == Value::Null {
         if !nullable {
-            return Err(format!(
-                "Validation error in column {}, expected non-nullable value but got null",
-                column_id
-            )
-            .into());
+            return Err(ValidationError::new(
+                column_id,
+                ValidationErrorKind::NotNullable,
+                "expected non-nullable value but got null",
+            ));
         } else {
             return Ok(Value::Null);
         }
     }
 
-    match &column_type {
-        ColumnType::Scalar { inner } => {
-            // Special case: JSON columns can be any type
-            if matches!(v, Value::Array(_)) && !matches!(inner, InnerColumnType::Json { .. }) {
-                return Err(format!(
-                    "Validation error in column {}, expected scalar but got array",
-                    column_id
-                )
-                .into());
-            }
+    match &column_type {
+        ColumnType::Scalar { inner } => validate_scalar_value(v, inner, column_id, None),
+        ColumnType::Array {
+            inner,
+            min_items,
+            max_items,
+            unique,
+        } => match v {
+            Value::Array(l) => {
+                let expected = match (min_items, max_items) {
+                    (Some(min_items), Some(max_items))
+                        if l.len() < *min_items || l.len() > *max_items =>
+                    {
+                        Some(format!("between {} and {}", min_items, max_items))
+                    }
+                    (Some(min_items), None) if l.len() < *min_items => {
+                        Some(format!("at least {}", min_items))
+                    }
+                    (None, Some(max_items)) if l.len() > *max_items => {
+                        Some(format!("at most {}", max_items))
+                    }
+                    _ => None,
+                };
+
+                if let Some(expected) = expected {
+                    let kind = if min_items.is_some_and(|min_items| l.len() < min_items) {
+                        ValidationErrorKind::TooShort
+                    } else {
+                        ValidationErrorKind::TooLong
+                    };
+
+                    return Err(ValidationError::new(
+                        column_id,
+                        kind,
+                        format!("expected {} items but got {}", expected, l.len()),
+                    ));
+                }
+
+                let mut values: Vec<Value> = Vec::new();
+
+                // Build the set of allowed values once for the whole array instead of scanning allowed_values per element
+                let allowed_values = inner.allowed_values();
+                let allowed_set = if allowed_values.is_empty()
+                    || !matches!(inner, InnerColumnType::String { .. })
+                {
+                    None
+                } else {
+                    Some(
+                        allowed_values
+                            .iter()
+                            .map(|v| v.as_str())
+                            .collect::<HashSet<&str>>(),
+                    )
+                };
+
+                for v in l {
+                    let new_v = if v == Value::Null {
+                        validate_value_untraced(v, column_type, column_id, nullable)?
+                    } else {
+                        validate_scalar_value(v, inner, column_id, allowed_set.as_ref())?
+                    };
+
+                    if *unique && values.contains(&new_v) {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::Duplicate,
+                            format!("expected unique items but got duplicate {}", new_v),
+                        ));
+                    }
+
+                    values.push(new_v);
+                }
+
+                Ok(Value::Array(values))
+            }
+            _ => Err(ValidationError::new(
+                column_id,
+                ValidationErrorKind::TypeMismatch,
+                format!("expected Array but got {:?}", v),
+            )),
+        },
+    }
+}
+
+/// Parse a non-null value against a scalar column type
+///
+/// `allowed_set` is a prebuilt set of the String column's allowed values, this avoids a linear scan of
+/// allowed_values per value when validating many values (e.g. the elements of an array) at once
+fn validate_scalar_value(
+    v: Value,
+    inner: &InnerColumnType,
+    column_id: &str,
+    allowed_set: Option<&HashSet<&str>>,
+) -> Result<Value, ValidationError> {
+    // Special case: JSON columns can be any type
+    if matches!(v, Value::Array(_)) && !matches!(inner, InnerColumnType::Json { .. }) {
+        return Err(ValidationError::new(
+            column_id,
+            ValidationErrorKind::TypeMismatch,
+            "expected scalar but got array",
+        ));
+    }
+
+    match inner {
+        InnerColumnType::String {
+            min_length,
+            max_length,
+            kind,
+            unchecked_ids,
+            ..
+        } => match v {
+            Value::String(s) => {
+                if let Some(min_length) = min_length {
+                    if s.len() < *min_length {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TooShort,
+                            format!(
+                                "expected String with min length {} but got String with length {}",
+                                min_length,
+                                s.len()
+                            ),
+                        ));
+                    }
+                }
+
+                if let Some(max_length) = max_length {
+                    if s.len() > *max_length {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TooLong,
+                            format!(
+                                "expected String with max length {} but got String with length {}",
+                                max_length,
+                                s.len()
+                            ),
+                        ));
+                    }
+                }
+
+                let allowed_values = inner.allowed_values();
+                let is_allowed = match allowed_set {
+                    Some(allowed_set) => allowed_set.contains(s.as_str()),
+                    None => allowed_values.contains(&s),
+                };
+
+                if !allowed_values.is_empty() && !is_allowed {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::NotAllowed,
+                        format!(
+                            "expected String with value in {:?} but got String with value {}",
+                            allowed_values, s
+                        ),
+                    ));
+                }
+
+                if !unchecked_ids && SNOWFLAKE_KINDS.contains(&kind.as_str()) && !is_snowflake(&s) {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::InvalidFormat,
+                        format!(
+                            "expected a valid {} ID but got {}",
+                            kind.trim_end_matches("_id"),
+                            s
+                        ),
+                    ));
+                }
+
+                if kind == "url" {
+                    validate_url(&s).map_err(|e| {
+                        ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::InvalidFormat,
+                            format!("expected a valid URL but got {}: {}", s, e),
+                        )
+                    })?;
+                }
+
+                if kind == "interval" {
+                    parse_interval(&s).map_err(|e| {
+                        ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::InvalidFormat,
+                            format!("expected a valid interval (e.g. 1d2h) but got {}: {}", s, e),
+                        )
+                    })?;
+                }
+
+                Ok(Value::String(s))
+            }
+            _ => Err(ValidationError::new(
+                column_id,
+                ValidationErrorKind::TypeMismatch,
+                format!("expected String but got {:?}", v),
+            )),
+        },
+        InnerColumnType::Integer {
+            min,
+            max,
+            allowed_values,
+        } => {
+            let value = match v {
+                Value::String(s) => {
+                    if s.is_empty() {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::InvalidFormat,
+                            "expected Integer but got empty String",
+                        ));
+                    }
+
+                    match s.parse::<i64>() {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return Err(ValidationError::new(
+                                column_id,
+                                ValidationErrorKind::InvalidFormat,
+                                format!(
+                                    "expected Integer but got String that cannot be parsed: {}",
+                                    e
+                                ),
+                            ));
+                        }
+                    }
+                }
+                Value::Number(v) => match v.as_i64() {
+                    Some(v) => v,
+                    None => {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TypeMismatch,
+                            "expected Integer but got Float",
+                        ));
+                    }
+                },
+                _ => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::TypeMismatch,
+                        format!("expected Integer but got {:?}", v),
+                    ));
+                }
+            };
+
+            if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::OutOfRange,
+                    format!(
+                        "expected Integer in range {} but got {}",
+                        format_range(min, max),
+                        value
+                    ),
+                ));
+            }
+
+            if !allowed_values.is_empty() && !allowed_values.contains(&value) {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::NotAllowed,
+                    format!(
+                        "expected Integer with value in {:?} but got {}",
+                        allowed_values, value
+                    ),
+                ));
+            }
+
+            Ok(Value::Number(value.into()))
+        }
+        InnerColumnType::Float {
+            min,
+            max,
+            allowed_values,
+        } => {
+            let value = match v {
+                Value::String(s) => match s.parse::<f64>() {
+                    Ok(v) => v,
+                    Err(e) => {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::InvalidFormat,
+                            format!("expected Float but got String that cannot be parsed: {}", e),
+                        ));
+                    }
+                },
+                Value::Number(v) => {
+                    if !v.is_f64() {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TypeMismatch,
+                            "expected Float but got Integer",
+                        ));
+                    }
+
+                    v.as_f64().unwrap_or(f64::NAN)
+                }
+                _ => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::TypeMismatch,
+                        format!("expected Float but got {:?}", v),
+                    ));
+                }
+            };
+
+            if !value.is_finite() {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::OutOfRange,
+                    format!("expected a finite Float but got {}", value),
+                ));
+            }
+
+            if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::OutOfRange,
+                    format!(
+                        "expected Float in range {} but got {}",
+                        format_range(min, max),
+                        value
+                    ),
+                ));
+            }
+
+            if !allowed_values.is_empty() && !allowed_values.contains(&value) {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::NotAllowed,
+                    format!(
+                        "expected Float with value in {:?} but got {}",
+                        allowed_values, value
+                    ),
+                ));
+            }
+
+            let number = match Number::from_f64(value) {
+                Some(n) => n,
+                None => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::InvalidFormat,
+                        "expected Float but got Float that cannot be converted to JSON Number",
+                    ));
+                }
+            };
+
+            Ok(Value::Number(number))
+        }
+        InnerColumnType::Decimal {
+            min,
+            max,
+            max_scale,
+        } => {
+            let parsed = match v {
+                Value::String(ref s) => rust_decimal::Decimal::from_str(s.trim())
+                    .or_else(|_| rust_decimal::Decimal::from_scientific(s.trim())),
+                Value::Number(ref n) => rust_decimal::Decimal::from_str(&n.to_string())
+                    .or_else(|_| rust_decimal::Decimal::from_scientific(&n.to_string())),
+                _ => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::TypeMismatch,
+                        format!("expected Decimal but got {:?}", v),
+                    ));
+                }
+            };
+
+            let value = match parsed {
+                Ok(value) => value.normalize(),
+                Err(e) => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::InvalidFormat,
+                        format!(
+                            "expected Decimal but got a value that cannot be parsed: {}",
+                            e
+                        ),
+                    ));
+                }
+            };
+
+            if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::OutOfRange,
+                    format!(
+                        "expected Decimal in range {} but got {}",
+                        format_range(min, max),
+                        value
+                    ),
+                ));
+            }
+
+            if let Some(max_scale) = max_scale {
+                if value.scale() > *max_scale {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::InvalidFormat,
+                        format!(
+                            "expected Decimal with at most {} decimal places but got {}",
+                            max_scale, value
+                        ),
+                    ));
+                }
+            }
+
+            Ok(Value::String(value.to_string()))
+        }
+        InnerColumnType::BitFlag { values, strict } => {
+            let v = match v {
+                Value::String(s) => match s.parse::<i64>() {
+                    Ok(v) => v,
+                    Err(e) => {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::InvalidFormat,
+                            format!(
+                                "expected BitFlag but got String that cannot be parsed: {}",
+                                e
+                            ),
+                        ));
+                    }
+                },
+                Value::Number(v) => {
+                    if v.is_i64() {
+                        v.as_i64().unwrap()
+                    } else {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TypeMismatch,
+                            "expected BitFlag but got Float",
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::TypeMismatch,
+                        format!("expected BitFlag but got {:?}", v),
+                    ))
+                }
+            };
+
+            if *strict {
+                let known_bits = values.values().fold(0, |acc, bit| acc | *bit);
+                let unknown_bits = v & !known_bits;
+
+                if unknown_bits != 0 {
+                    let unknown_bits = (0..64)
+                        .map(|i| 1_i64 << i)
+                        .filter(|bit| unknown_bits & bit != 0)
+                        .map(|bit| bit.to_string())
+                        .collect::<Vec<String>>();
+
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::UnknownBits,
+                        format!("got unknown BitFlag bits: {}", unknown_bits.join(", ")),
+                    ));
+                }
+            }
+
+            let mut final_value = 0;
+
+            // Set all the valid bits in final_value to ensure no unknown bits are being set
+            for (_, bit) in values.iter() {
+                if *bit & v == *bit {
+                    final_value |= *bit;
+                }
+            }
+
+            if final_value == 0 {
+                // Set the first value as the default value
+                let Some(fv) = values.values().next() else {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::InvalidSchema,
+                        "expected BitFlag but no default value found",
+                    ));
+                };
+
+                final_value = *fv;
+            }
+
+            Ok(Value::Number(final_value.into()))
+        }
+        InnerColumnType::Boolean { strict } => match v {
+            Value::String(s) => {
+                let Some(value) = parse_bool(&s, *strict) else {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::InvalidFormat,
+                        format!(
+                            "expected Boolean but got String that cannot be parsed: {}",
+                            s
+                        ),
+                    ));
+                };
+
+                Ok(Value::Bool(value))
+            }
+            Value::Bool(v) => Ok(Value::Bool(v)),
+            _ => Err(ValidationError::new(
+                column_id,
+                ValidationErrorKind::TypeMismatch,
+                format!("expected Boolean but got {:?}", v),
+            )),
+        },
+        InnerColumnType::Json { max_bytes, .. } => {
+            // Convert back to json to get bytes
+            match v {
+                Value::String(s) => {
+                    if s.len() > max_bytes.unwrap_or(0) {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TooLong,
+                            format!(
+                                "expected JSON with max bytes {} but got JSON with bytes {}",
+                                max_bytes.unwrap_or(0),
+                                s.len()
+                            ),
+                        ));
+                    }
+
+                    let v: serde_json::Value = {
+                        if !s.starts_with("[") && !s.starts_with("{") {
+                            serde_json::Value::String(s)
+                        } else {
+                            match serde_json::from_str(&s) {
+                                Ok(v) => v,
+                                Err(e) => {
+                                    return Err(ValidationError::new(
+                                        column_id,
+                                        ValidationErrorKind::InvalidFormat,
+                                        format!(
+                                            "expected JSON but got String that cannot be parsed: {}",
+                                            e
+                                        ),
+                                    ));
+                                }
+                            }
+                        }
+                    };
+
+                    Ok(v)
+                }
+                _ => {
+                    let bytes = match serde_json::to_string(&v) {
+                        Ok(b) => b,
+                        Err(e) => {
+                            return Err(ValidationError::new(
+                                column_id,
+                                ValidationErrorKind::InvalidFormat,
+                                format!(
+                                    "expected JSON but got value that cannot be converted to JSON: {}",
+                                    e
+                                ),
+                            ));
+                        }
+                    };
+
+                    if let Some(max_bytes) = max_bytes {
+                        if bytes.len() > *max_bytes {
+                            return Err(ValidationError::new(
+                                column_id,
+                                ValidationErrorKind::TooLong,
+                                format!(
+                                    "expected JSON with max bytes {} but got JSON with bytes {}",
+                                    max_bytes,
+                                    bytes.len()
+                                ),
+                            ));
+                        }
+                    }
+
+                    Ok(v)
+                }
+            }
+        }
+        InnerColumnType::Object { fields } => {
+            let mut obj = match v {
+                Value::Object(obj) => obj,
+                Value::String(s) => match serde_json::from_str::<Value>(&s) {
+                    Ok(Value::Object(obj)) => obj,
+                    Ok(_) => {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::TypeMismatch,
+                            "expected Object but got String containing a non-object",
+                        ));
+                    }
+                    Err(e) => {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::InvalidFormat,
+                            format!(
+                                "expected Object but got String that cannot be parsed: {}",
+                                e
+                            ),
+                        ));
+                    }
+                },
+                _ => {
+                    return Err(ValidationError::new(
+                        column_id,
+                        ValidationErrorKind::TypeMismatch,
+                        format!("expected Object but got {:?}", v),
+                    ));
+                }
+            };
+
+            if let Some(key) = obj.keys().find(|key| !fields.iter().any(|f| &f.id == *key)) {
+                return Err(ValidationError::new(
+                    column_id,
+                    ValidationErrorKind::UnknownField,
+                    format!("got unknown field {}", key),
+                ));
+            }
+
+            let mut validated = serde_json::Map::new();
+
+            for field in fields {
+                let field_id = format!("{}.{}", column_id, field.id);
+
+                let mut value = obj.remove(&field.id).unwrap_or(Value::Null);
+
+                if value == Value::Null {
+                    if let Some(ref default) = field.default {
+                        value = default.clone();
+                    } else if !field.nullable {
+                        return Err(ValidationError::new(
+                            column_id,
+                            ValidationErrorKind::MissingField,
+                            format!("missing required field {}", field.id),
+                        ));
+                    }
+                }
+
+                let value = validate_value(value, &field.column_type, &field_id, field.nullable)?;
+
+                validated.insert(field.id.to_string(), value);
+            }
+
+            Ok(Value::Object(validated))
+        }
+    }
+}
+
+/// The URL schemes accepted by `url` kind columns
+const URL_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// Checks that a string is an absolute http(s) URL
+fn validate_url(s: &str) -> Result<(), Error> {
+    let url = url::Url::parse(s)?;
+
+    if !URL_SCHEMES.contains(&url.scheme()) {
+        return Err(format!("unsupported scheme `{}`", url.scheme()).into());
+    }
+
+    if url.host_str().is_none() {
+        return Err("URL has no host".into());
+    }
+
+    Ok(())
+}
+
+/// Formats (inclusive) bounds as a range, e.g. `0..=100`, `0..` or `..=100`
+fn format_range<T: std::fmt::Display>(min: &Option<T>, max: &Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{}..={}", min, max),
+        (Some(min), None) => format!("{}..", min),
+        (None, Some(max)) => format!("..={}", max),
+        (None, None) => "..".to_string(),
+    }
+}
+
+/// Runs the authorizer of the setting (if any) for the given operation and actor
+async fn authorize<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    operation_type: OperationType,
+    actor: Option<&str>,
+) -> Result<(), Error> {
+    if let Some(ref authorizer) = setting.operations.authorizer {
+        authorizer
+            .authorize(data, operation_type, actor)
+            .await
+            .map_err(|e| format!("Permission denied: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Runs a settings operation, with the `tracing` feature it runs in a `settings_operation` span (recording
+/// the setting id and operation) and its duration and outcome are recorded as an event
+///
+/// Every Settings API function is traced (dry runs, patches and the other view variants under the operation
+/// they perform), calls between them are not traced again (see `create_many`)
+///
+/// Validation failures are caused by user input and recorded at debug level, other failures (e.g. executor
+/// errors) at warn level
+async fn traced<R>(
+    setting_id: &str,
+    operation: OperationType,
+    fut: impl std::future::Future<Output = Result<R, Error>>,
+) -> Result<R, Error> {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument;
+
+        let span = tracing::debug_span!(
+            "settings_operation",
+            setting = setting_id,
+            operation = %operation
+        );
+
+        let start = std::time::Instant::now();
+        let result = fut.instrument(span.clone()).await;
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        span.in_scope(|| match result {
+            Ok(_) => tracing::debug!(elapsed_ms, "operation succeeded"),
+            Err(ref e) => match e.downcast_ref::<ValidationError>() {
+                Some(e) => tracing::debug!(
+                    elapsed_ms,
+                    column = %e.column_id,
+                    error = %e,
+                    "operation failed validation"
+                ),
+                None => tracing::warn!(elapsed_ms, error = %e, "operation failed"),
+            },
+        });
+
+        result
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (setting_id, operation);
+        fut.await
+    }
+}
+
+/// Settings API: View implementation
+///
+/// `actor` is the user performing the operation and is passed to the authorizer of the setting
+pub async fn settings_view<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    filters: indexmap::IndexMap<String, Value>, // The filters to apply
+    sort: Option<&SortBy>,                      // The order to return the entries in
+    actor: Option<&str>,
+) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+    traced(&setting.id, OperationType::View, async {
+        authorize(setting, data, OperationType::View, actor).await?;
+
+        let Some(sort) = sort else {
+            return settings_view_unauthorized(setting, data, filters).await;
+        };
+
+        validate_sort(setting, sort)?;
+
+        let mut states = settings_view_unauthorized(setting, data, filters).await?;
+        sort_states(&mut states, sort);
+
+        Ok(states)
+    })
+    .await
+}
+
+/// Settings API: View a single entry by its primary key
+///
+/// `primary_key` must contain a value for every primary key column and nothing else. Returns `None` if no
+/// entry matches and errors if more than one does (e.g. if the executor ignores part of the primary key)
+pub async fn settings_view_one<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    primary_key: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<Option<indexmap::IndexMap<String, Value>>, Error> {
+    traced(&setting.id, OperationType::View, async {
+        authorize(setting, data, OperationType::View, actor).await?;
+
+        let mut primary_key = primary_key;
+
+        let mut filters = indexmap::IndexMap::new();
+        for column in setting.columns.iter().filter(|c| c.primary_key) {
+            let Some(value) = primary_key.swap_remove(&column.id) else {
+                return Err(format!("Missing primary key field: {}", column.id).into());
+            };
+
+            let value = validate_value(value, &column.column_type, &column.id, column.nullable)
+                .map_err(|e| e.with_operation(OperationType::View))?;
+            filters.insert(column.id.to_string(), value);
+        }
+
+        if let Some(key) = primary_key.keys().next() {
+            return Err(format!("Not a primary key field: {}", key).into());
+        }
+
+        let mut states = settings_view_unauthorized(setting, data, filters).await?;
+
+        if states.len() > 1 {
+            return Err(format!(
+                "Expected at most one entry for the primary key, found {}",
+                states.len()
+            )
+            .into());
+        }
+
+        Ok(states.pop())
+    })
+    .await
+}
+
+/// View implementation without authorization, used internally to look up entries on behalf of
+/// other (already authorized) operations
+async fn settings_view_unauthorized<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    filters: indexmap::IndexMap<String, Value>,
+) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+    let Some(ref viewer) = setting.operations.view else {
+        return Err(format!("Operation not supported: {}", OperationType::View).into());
+    };
+
+    let filters = validate_filters(setting, filters)?;
+    let states = viewer.view(data, filters).await?;
+
+    process_view_states(setting, states)
+}
+
+/// Settings API: View implementation with filter operators (see `Filter`)
+///
+/// Filter operands are validated against their columns. Executors implementing `view_filtered` evaluate the
+/// filters themselves, otherwise the equality filters are passed to `view` and the other filters are
+/// evaluated in memory
+pub async fn settings_view_filtered<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    filters: indexmap::IndexMap<String, Filter>, // The filters to apply
+    sort: Option<&SortBy>,                       // The order to return the entries in
+    actor: Option<&str>,
+) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+    traced(&setting.id, OperationType::View, async {
+        let Some(ref viewer) = setting.operations.view else {
+            return Err(format!("Operation not supported: {}", OperationType::View).into());
+        };
+
+        authorize(setting, data, OperationType::View, actor).await?;
+
+        if let Some(sort) = sort {
+            validate_sort(setting, sort)?;
+        }
+
+        let mut validated = indexmap::IndexMap::new();
+        for (key, filter) in filters {
+            let filter = match setting.column(&key) {
+                Some(column) => validate_filter(column, filter)?,
+                // Like with equality maps, equality filters on non-columns are passed through to the executor as-is
+                None if matches!(filter, Filter::Eq(_)) => filter,
+                None => {
+                    return Err(format!(
+                        "Cannot filter by unknown column `{}` with operator `{}`",
+                        key,
+                        filter.operator()
+                    )
+                    .into())
+                }
+            };
+
+            validated.insert(key, filter);
+        }
+
+        let states = match viewer.view_filtered(data, validated.clone()).await? {
+            Some(states) => states,
+            None => {
+                let equality = validated
+                    .iter()
+                    .filter_map(|(key, filter)| match filter {
+                        Filter::Eq(value) => Some((key.to_string(), value.clone())),
+                        _ => None,
+                    })
+                    .collect();
+
+                viewer
+                    .view(data, equality)
+                    .await?
+                    .into_iter()
+                    .filter(|state| {
+                        validated.iter().all(|(key, filter)| {
+                            let Some(column) = setting.column(key) else {
+                                return true;
+                            };
+
+                            let value = state.get(key).cloned().unwrap_or(Value::Null);
+                            let value =
+                                validate_value(value.clone(), &column.column_type, key, true)
+                                    .unwrap_or(value);
+
+                            filter_matches(column, filter, &value)
+                        })
+                    })
+                    .collect()
+            }
+        };
+
+        let mut states = process_view_states(setting, states)?;
+
+        if let Some(sort) = sort {
+            sort_states(&mut states, sort);
+        }
+
+        Ok(states)
+    })
+    .await
+}
+
+/// Validates the operator and operand of a filter against its column
+fn validate_filter(column: &Column, filter: Filter) -> Result<Filter, Error> {
+    let scalar_type = ColumnType::new_scalar(column.column_type.inner().clone());
+    let validate_scalar = |value: Value| {
+        validate_value(value, &scalar_type, &column.id, true)
+            .map_err(|e| e.with_operation(OperationType::View))
+    };
+
+    let unsupported = || -> Error {
+        format!(
+            "Operator `{}` is not supported for column `{}` of type {}",
+            filter.operator(),
+            column.id,
+            column.column_type.type_label()
+        )
+        .into()
+    };
+
+    match filter {
+        Filter::Eq(ref value) | Filter::Ne(ref value) => {
+            let value = validate_value(value.clone(), &column.column_type, &column.id, true)
+                .map_err(|e| e.with_operation(OperationType::View))?;
+
+            Ok(match filter {
+                Filter::Eq(_) => Filter::Eq(value),
+                _ => Filter::Ne(value),
+            })
+        }
+        Filter::Gt(ref value) | Filter::Lt(ref value) => {
+            let orderable = match column.column_type {
+                ColumnType::Scalar { ref inner } => match inner {
+                    InnerColumnType::Integer { .. }
+                    | InnerColumnType::Float { .. }
+                    | InnerColumnType::Decimal { .. } => true,
+                    InnerColumnType::String { kind, .. } => kind == "timestamp",
+                    _ => false,
+                },
+                ColumnType::Array { .. } => false,
+            };
+
+            if !orderable {
+                return Err(unsupported());
+            }
+
+            let value = validate_scalar(value.clone())?;
+
+            Ok(match filter {
+                Filter::Gt(_) => Filter::Gt(value),
+                _ => Filter::Lt(value),
+            })
+        }
+        Filter::In(ref values) => {
+            if matches!(column.column_type, ColumnType::Array { .. }) {
+                return Err(unsupported());
+            }
+
+            let mut validated = Vec::new();
+            for value in values.iter() {
+                validated.push(validate_scalar(value.clone())?);
+            }
+
+            Ok(Filter::In(validated))
+        }
+        Filter::Contains(ref value) => match column.column_type {
+            ColumnType::Array { .. } => Ok(Filter::Contains(validate_scalar(value.clone())?)),
+            ColumnType::Scalar {
+                inner: InnerColumnType::String { .. },
+            } if value.is_string() => Ok(Filter::Contains(value.clone())),
+            _ => Err(unsupported()),
+        },
+    }
+}
+
+/// Evaluates a (validated) filter against the value of its column, nulls never match `Gt`/`Lt`/`Contains`
+fn filter_matches(column: &Column, filter: &Filter, value: &Value) -> bool {
+    match filter {
+        Filter::Eq(operand) => value == operand,
+        Filter::Ne(operand) => value != operand,
+        Filter::Gt(operand) => {
+            !value.is_null() && compare_filter_values(column, value, operand) == Ordering::Greater
+        }
+        Filter::Lt(operand) => {
+            !value.is_null() && compare_filter_values(column, value, operand) == Ordering::Less
+        }
+        Filter::In(operands) => operands.contains(value),
+        Filter::Contains(operand) => match value {
+            Value::Array(values) => values.contains(operand),
+            Value::String(s) => operand.as_str().is_some_and(|operand| s.contains(operand)),
+            _ => false,
+        },
+    }
+}
+
+/// Compares two values of a column, decimals (which are strings) are compared numerically
+fn compare_filter_values(column: &Column, a: &Value, b: &Value) -> Ordering {
+    if let InnerColumnType::Decimal { .. } = column.column_type.inner() {
+        let parse = |v: &Value| {
+            v.as_str()
+                .and_then(|s| rust_decimal::Decimal::from_str(s).ok())
+        };
+
+        if let (Some(a), Some(b)) = (parse(a), parse(b)) {
+            return a.cmp(&b);
+        }
+    }
+
+    compare_values(a, b)
+}
+
+/// Settings API: Paged view implementation
+///
+/// Returns at most `limit` entries starting at `offset` along with the total number of entries
+pub async fn settings_view_page<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    filters: indexmap::IndexMap<String, Value>, // The filters to apply
+    sort: Option<&SortBy>,                      // The order to return the entries in
+    limit: usize,
+    offset: usize,
+    actor: Option<&str>,
+) -> Result<(Vec<indexmap::IndexMap<String, Value>>, usize), Error> {
+    traced(&setting.id, OperationType::View, async {
+        let Some(ref viewer) = setting.operations.view else {
+            return Err(format!("Operation not supported: {}", OperationType::View).into());
+        };
+
+        authorize(setting, data, OperationType::View, actor).await?;
+
+        if let Some(sort) = sort {
+            validate_sort(setting, sort)?;
+        }
+
+        let filters = validate_filters(setting, filters)?;
+        let page = match sort {
+            Some(sort) => {
+                viewer
+                    .view_sorted_page(data, filters.clone(), sort, limit, offset)
+                    .await?
+            }
+            None => {
+                viewer
+                    .view_page(data, filters.clone(), limit, offset)
+                    .await?
+            }
+        };
+
+        let (states, total_count) = match page {
+            Some(page) => page,
+            None => {
+                // The executor does not support paging, fetch everything and slice out the page
+                let mut states = viewer.view(data, filters).await?;
+                let total_count = states.len();
+
+                if let Some(sort) = sort {
+                    sort_states(&mut states, sort);
+                }
+
+                (
+                    states.into_iter().skip(offset).take(limit).collect(),
+                    total_count,
+                )
+            }
+        };
+
+        Ok((process_view_states(setting, states)?, total_count))
+    })
+    .await
+}
+
+/// Settings API: Count implementation
+///
+/// Returns the number of entries matching `filters`, this is used to display totals without fetching
+/// all entries when the executor implements `count`
+pub async fn settings_count<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    filters: indexmap::IndexMap<String, Value>, // The filters to apply
+    actor: Option<&str>,
+) -> Result<usize, Error> {
+    traced(&setting.id, OperationType::View, async {
+        let Some(ref viewer) = setting.operations.view else {
+            return Err(format!("Operation not supported: {}", OperationType::View).into());
+        };
+
+        authorize(setting, data, OperationType::View, actor).await?;
+
+        let filters = validate_filters(setting, filters)?;
+
+        match viewer.count(data, filters.clone()).await? {
+            Some(count) => Ok(count),
+            None => Ok(viewer.view(data, filters).await?.len()),
+        }
+    })
+    .await
+}
+
+/// Validates that a view may be sorted by the column of `sort`
+fn validate_sort<T: Clone>(setting: &Setting<T>, sort: &SortBy) -> Result<(), Error> {
+    let Some(column) = setting.column(&sort.column) else {
+        return Err(format!("Cannot sort by unknown column `{}`", sort.column).into());
+    };
+
+    if !column.is_sortable() {
+        return Err(format!("Cannot sort by column `{}`", column.id).into());
+    }
+
+    Ok(())
+}
+
+/// Sorts entries in memory, this is used when the executor does not sort entries itself
+fn sort_states(states: &mut [indexmap::IndexMap<String, Value>], sort: &SortBy) {
+    states.sort_by(|a, b| {
+        let ordering = compare_values(
+            a.get(&sort.column).unwrap_or(&Value::Null),
+            b.get(&sort.column).unwrap_or(&Value::Null),
+        );
+
+        if sort.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Compares two values for sorting, nulls sort first and values of differing types are ordered by type
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn rank(v: &Value) -> u8 {
+        match v {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a
+                .as_f64()
+                .unwrap_or_default()
+                .total_cmp(&b.as_f64().unwrap_or_default()),
+        },
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| compare_values(a, b))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Validates the filters of a view against the types of their columns
+///
+/// Filters that do not correspond to a column are passed through to the executor as-is
+fn validate_filters<T: Clone>(
+    setting: &Setting<T>,
+    filters: indexmap::IndexMap<String, Value>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    let mut validated = indexmap::IndexMap::new();
+
+    for (key, value) in filters {
+        let value = match setting.column(&key) {
+            Some(column) => validate_value(value, &column.column_type, &column.id, true)
+                .map_err(|e| e.with_operation(OperationType::View))?,
+            None => value,
+        };
+
+        validated.insert(key, value);
+    }
+
+    Ok(validated)
+}
+
+/// Validates the entries returned by the view executor, masking secret columns and removing ignored columns
+fn process_view_states<T: Clone>(
+    setting: &Setting<T>,
+    states: Vec<indexmap::IndexMap<String, Value>>,
+) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+    let mut values: Vec<indexmap::IndexMap<String, Value>> = Vec::new();
+
+    for mut state in states {
+        // We know that the columns are in the same order as the row
+        for col in setting.columns.iter() {
+            let mut val = match state.swap_remove(&col.id) {
+                Some(val) => val,
+                None => {
+                    if setting.strict_columns && !col.nullable {
+                        return Err(format!(
+                            "Internal error: Column `{}` was not returned by the executor",
+                            col.id
+                        )
+                        .into());
+                    }
+
+                    Value::Null
+                }
+            };
+
+            // Validate the value
+            val = validate_value(val, &col.column_type, &col.id, col.nullable)
+                .map_err(|e| e.with_operation(OperationType::View))?;
+
+            // Reinsert
+            state.insert(col.id.to_string(), val);
+        }
+
+        // Remove ignored columns + mask secret columns now that the actions have been executed
+        for col in setting.columns.iter() {
+            if col.ignored_for.contains(&OperationType::View) {
+                state.swap_remove(&col.id);
+                continue;
+            }
+
+            if col.secret {
+                // This applies to view only as create/update return the user's own input
+                let masked = state
+                    .get(&col.id)
+                    .and_then(|val| col.secret_display.mask(val));
+
+                match masked {
+                    Some(masked) => state.insert(col.id.to_string(), masked),
+                    None => state.swap_remove(&col.id),
+                };
+            }
+        }
+
+        values.push(state);
+    }
+
+    Ok(values)
+}
+
+/// A cursor over the (validated) entries of a setting, for programmatic consumers
+///
+/// Entries are fetched lazily, page by page, from the view operation as `next` is called:
+///
+/// ```ignore
+/// let mut cursor = SettingsCursor::new(&setting, &data, filters, Some(&user_id));
+/// while let Some(row) = cursor.next().await? {
+///     // ...
+/// }
+/// ```
+pub struct SettingsCursor<'a, T: Clone> {
+    setting: &'a Setting<T>,
+    data: &'a T,
+    filters: indexmap::IndexMap<String, Value>,
+    sort: Option<SortBy>,
+    actor: Option<&'a str>,
+    page_size: usize,
+    offset: usize,
+    buffer: VecDeque<indexmap::IndexMap<String, Value>>,
+    exhausted: bool,
+}
+
+impl<'a, T: Clone> SettingsCursor<'a, T> {
+    /// Creates a new cursor over the entries of the setting matching `filters`
+    pub fn new(
+        setting: &'a Setting<T>,
+        data: &'a T,
+        filters: indexmap::IndexMap<String, Value>,
+        actor: Option<&'a str>,
+    ) -> Self {
+        Self {
+            setting,
+            data,
+            filters,
+            sort: None,
+            actor,
+            page_size: 100,
+            offset: 0,
+            buffer: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Sets the number of entries fetched per page
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Sets the order in which entries are returned
+    pub fn sort_by(mut self, sort: SortBy) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// Returns the next entry, or None once all entries have been returned
+    pub async fn next(&mut self) -> Result<Option<indexmap::IndexMap<String, Value>>, Error> {
+        if self.buffer.is_empty() && !self.exhausted {
+            self.fetch().await?;
+        }
+
+        Ok(self.buffer.pop_front())
+    }
+
+    /// Fetches the next page of entries into the buffer
+    async fn fetch(&mut self) -> Result<(), Error> {
+        let (rows, total_count) = settings_view_page(
+            self.setting,
+            self.data,
+            self.filters.clone(),
+            self.sort.as_ref(),
+            self.page_size,
+            self.offset,
+            self.actor,
+        )
+        .await?;
+
+        self.offset += rows.len();
+        self.exhausted = rows.is_empty() || self.offset >= total_count;
+        self.buffer.extend(rows);
+
+        Ok(())
+    }
+}
+
+/// Settings API: Create implementation
+pub async fn settings_create<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    traced(&setting.id, OperationType::Create, async {
+        if setting.operations.create.is_none() {
+            return Err(format!("Operation not supported: {}", OperationType::Create).into());
+        }
+
+        authorize(setting, data, OperationType::Create, actor).await?;
+
+        create_authorized(setting, data, fields, actor).await
+    })
+    .await
+}
+
+/// Settings API: Bulk create implementation
+///
+/// Each entry is validated and created independently, a failing entry does not abort the rest of the
+/// batch. The returned results are in the same order as `entries`. Authorization is checked once for
+/// the whole batch
+pub async fn settings_create_many<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    entries: Vec<indexmap::IndexMap<String, Value>>,
+    actor: Option<&str>,
+) -> Result<Vec<Result<indexmap::IndexMap<String, Value>, Error>>, Error> {
+    traced(
+        &setting.id,
+        OperationType::Create,
+        create_many(setting, data, entries, actor),
+    )
+    .await
+}
+
+/// The untraced implementation of `settings_create_many`, shared with `settings_import_json`
+async fn create_many<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    entries: Vec<indexmap::IndexMap<String, Value>>,
+    actor: Option<&str>,
+) -> Result<Vec<Result<indexmap::IndexMap<String, Value>, Error>>, Error> {
+    if setting.operations.create.is_none() {
+        return Err(format!("Operation not supported: {}", OperationType::Create).into());
+    }
+
+    authorize(setting, data, OperationType::Create, actor).await?;
+
+    let mut results = Vec::with_capacity(entries.len());
+
+    for fields in entries {
+        results.push(create_authorized(setting, data, fields, actor).await);
+    }
+
+    Ok(results)
+}
+
+/// Settings API: Exports all entries matching `filters` as a JSON array, e.g. for backups
+///
+/// Values are exported as returned by view (validated but not display-formatted) so that they can be
+/// re-imported losslessly with `settings_import_json`. Secret columns are never exported
+pub async fn settings_export_json<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    filters: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<Value, Error> {
+    let rows = settings_view(setting, data, filters, None, actor).await?;
+
+    let entries = rows
+        .into_iter()
+        .map(|mut row| {
+            for column in setting.columns.iter().filter(|c| c.secret) {
+                row.swap_remove(&column.id);
+            }
+
+            Value::Object(row.into_iter().collect())
+        })
+        .collect();
+
+    Ok(Value::Array(entries))
+}
+
+/// Settings API: Imports a JSON array of entries (as exported by `settings_export_json`) through
+/// `settings_create_many`
+///
+/// Every entry is validated and created independently, the returned results are in the same order as
+/// the entries of `json`
+pub async fn settings_import_json<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    json: Value,
+    actor: Option<&str>,
+) -> Result<Vec<Result<indexmap::IndexMap<String, Value>, Error>>, Error> {
+    traced(&setting.id, OperationType::Create, async {
+        let Value::Array(entries) = json else {
+            return Err("Expected a JSON array of entries".into());
+        };
+
+        // Entries that are not objects fail on their own without being sent to create
+        let mut results: Vec<Option<Result<indexmap::IndexMap<String, Value>, Error>>> =
+            Vec::with_capacity(entries.len());
+        let mut objects = Vec::new();
+
+        for (idx, entry) in entries.into_iter().enumerate() {
+            match entry {
+                Value::Object(obj) => {
+                    objects.push(obj.into_iter().collect());
+                    results.push(None);
+                }
+                v => results.push(Some(Err(
+                    format!("Entry {} is not an object: {}", idx, v).into()
+                ))),
+            }
+        }
+
+        let mut created = create_many(setting, data, objects, actor)
+            .await?
+            .into_iter();
+
+        Ok(results
+            .into_iter()
+            .map(|result| match result {
+                Some(result) => result,
+                None => created
+                    .next()
+                    .unwrap_or_else(|| Err("Internal error: missing result for entry".into())),
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Create implementation without authorization, used by both single and bulk creates
+async fn create_authorized<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    let Some(ref creator) = setting.operations.create else {
+        return Err(format!("Operation not supported: {}", OperationType::Create).into());
+    };
+
+    let state = prepare_create(setting, data, fields).await?;
+
+    let new_state = creator.create(data, state).await?;
+
+    if let Some(ref hooks) = setting.operations.hooks {
+        hooks
+            .on_create(data, &new_state, actor)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Created successfully but the post-create hook failed: {}",
+                    e
+                )
+            })?;
+    }
+
+    Ok(new_state)
+}
+
+/// Parses and validates the fields of a create, returning the state to pass to the executor
+async fn prepare_create<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    // Ensure all columns exist in fields, note that we can ignore extra fields so this one single loop is enough
+    let mut state = fields;
+    for column in setting.columns.iter() {
+        if column.ignored_for.contains(&OperationType::Create) {
+            continue;
+        }
+
+        // If the column is ignored for, only parse, otherwise parse and validate
+        let value = {
+            // Get the value, falling back to the column default if not provided
+            let val = match state.swap_remove(&column.id) {
+                None | Some(Value::Null) => column.default.clone().unwrap_or(Value::Null),
+                Some(val) => val,
+            };
+
+            validate_value(val, &column.column_type, &column.id, column.nullable)
+                .map_err(|e| e.with_operation(OperationType::Create))?
+        };
+
+        state.insert(column.id.to_string(), value);
+    }
+
+    // Now execute all actions and handle null checks
+    for column in setting.columns.iter() {
+        // Checks should only happen if the column is not being intentionally ignored
+        if column.ignored_for.contains(&OperationType::Create) {
+            continue;
+        }
+
+        let Some(value) = state.get(&column.id) else {
+            return Err(format!(
+                "Internal error: Column `{}` not found in state despite just being parsed",
+                column.id
+            )
+            .into());
+        };
+
+        // Check if the column is nullable
+        if !column.nullable && matches!(value, Value::Null) {
+            return Err(format!("Missing or invalid field: {}", column.id).into());
+        }
+    }
+
+    // Remove ignored columns now that the actions have been executed
+    for col in setting.columns.iter() {
+        if col.ignored_for.contains(&OperationType::Create) {
+            state.swap_remove(&col.id);
+        }
+    }
+
+    if let Some(ref validator) = setting.operations.validator {
+        validator
+            .validate(data, &state, OperationType::Create)
+            .await?;
+    }
+
+    Ok(state)
+}
+
+/// Settings API: Create implementation without executing the create
+///
+/// Runs exactly the same parsing, null checks and validation as `settings_create` but does not call the
+/// executor, returning the (normalized) state that would have been created. This is useful for previews
+pub async fn settings_create_dry_run<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    traced(&setting.id, OperationType::Create, async {
+        if setting.operations.create.is_none() {
+            return Err(format!("Operation not supported: {}", OperationType::Create).into());
+        }
+
+        authorize(setting, data, OperationType::Create, actor).await?;
+
+        prepare_create(setting, data, fields).await
+    })
+    .await
+}
+
+/// Settings API: Update implementation
+pub async fn settings_update<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    traced(&setting.id, OperationType::Update, async {
+        if setting.operations.update.is_none() {
+            return Err(format!("Operation not supported: {}", OperationType::Update).into());
+        }
+
+        authorize(setting, data, OperationType::Update, actor).await?;
+
+        update_authorized(setting, data, fields, actor).await
+    })
+    .await
+}
+
+/// Update implementation without authorization, used by both updates and patches
+async fn update_authorized<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    let Some(ref updater) = setting.operations.update else {
+        return Err(format!("Operation not supported: {}", OperationType::Update).into());
+    };
+
+    let (state, expected_version) = prepare_update(setting, data, fields).await?;
+
+    let versioned_state = match (&setting.version_column, expected_version) {
+        (Some(version_column), Some(expected_version)) => {
+            updater
+                .update_versioned(data, state.clone(), version_column, expected_version)
+                .await?
+        }
+        _ => None,
+    };
+
+    let new_state = match versioned_state {
+        Some(new_state) => new_state,
+        None => updater.update(data, state).await?,
+    };
+
+    if let Some(ref hooks) = setting.operations.hooks {
+        hooks
+            .on_update(data, &new_state, actor)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Updated successfully but the post-update hook failed: {}",
+                    e
+                )
+            })?;
+    }
+
+    Ok(new_state)
+}
+
+/// Parses and validates the fields of an update, returning the state to pass to the executor along with
+/// the expected version of the entry (for settings with a version column)
+async fn prepare_update<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+) -> Result<(indexmap::IndexMap<String, Value>, Option<i64>), Error> {
+    // Ensure all columns exist in fields, note that we can ignore extra fields so this one single loop is enough
+    let mut state = fields;
+    for column in setting.columns.iter() {
+        if column.ignored_for.contains(&OperationType::Update) {
+            continue;
+        }
+
+        // If the column is ignored for, only parse, otherwise parse and validate
+        let value = {
+            // Get the value
+            let val = state.swap_remove(&column.id).unwrap_or(Value::Null);
+            validate_value(val, &column.column_type, &column.id, column.nullable)
+                .map_err(|e| e.with_operation(OperationType::Update))?
+        };
+
+        state.insert(column.id.to_string(), value);
+    }
+
+    // Now execute all actions and handle null checks
+    for column in setting.columns.iter() {
+        // Checks should only happen if the column is not being intentionally ignored
+        if column.ignored_for.contains(&OperationType::Update) {
+            continue;
+        }
+
+        let Some(value) = state.get(&column.id) else {
+            return Err(format!(
+                "Internal error: Column `{}` not found in state despite just being parsed",
+                column.id
+            )
+            .into());
+        };
+
+        // Check if the column is nullable
+        if !column.nullable && matches!(value, Value::Null) {
+            return Err(format!("Missing or invalid field: {}", column.id).into());
+        }
+    }
+
+    let expected_version = match setting.version_column {
+        Some(ref version_column) => {
+            Some(check_and_increment_version(setting, data, &mut state, version_column).await?)
+        }
+        None => None,
+    };
+
+    // Remove ignored columns now that the actions have been executed
+    for col in setting.columns.iter() {
+        if col.ignored_for.contains(&OperationType::Update) {
+            state.swap_remove(&col.id);
+        }
+    }
+
+    if let Some(ref validator) = setting.operations.validator {
+        validator
+            .validate(data, &state, OperationType::Update)
+            .await?;
+    }
+
+    Ok((state, expected_version))
+}
+
+/// Settings API: Update implementation without executing the update
+///
+/// Runs exactly the same parsing, null checks and validation (including the version check, if any) as
+/// `settings_update` but does not call the executor, returning the (normalized) state that would have been
+/// updated. This is useful for previews
+pub async fn settings_update_dry_run<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    traced(&setting.id, OperationType::Update, async {
+        if setting.operations.update.is_none() {
+            return Err(format!("Operation not supported: {}", OperationType::Update).into());
+        }
+
+        authorize(setting, data, OperationType::Update, actor).await?;
+
+        prepare_update(setting, data, fields)
+            .await
+            .map(|(state, _)| state)
+    })
+    .await
+}
+
+/// Settings API: Delete implementation
+#[allow(clippy::too_many_arguments)]
+pub async fn settings_delete<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<(), Error> {
+    traced(&setting.id, OperationType::Delete, async {
+        let Some(ref deleter) = setting.operations.delete else {
+            return Err(format!("Operation not supported: {}", OperationType::Delete).into());
+        };
+
+        authorize(setting, data, OperationType::Delete, actor).await?;
+
+        let mut fields = fields;
+
+        // If the primary key is not fully provided, try to resolve it from the alternate key instead
+        let has_primary_key = setting
+            .columns
+            .iter()
+            .filter(|c| c.primary_key && !c.ignored_for.contains(&OperationType::Delete))
+            .all(|c| fields.contains_key(&c.id));
+
+        if !has_primary_key && setting.columns.iter().any(|c| c.alternate_key) {
+            fields = resolve_alternate_key(setting, data, fields).await?;
+        }
+
+        let mut state = indexmap::IndexMap::with_capacity(setting.columns.len());
+        for column in setting.columns.iter() {
+            if column.ignored_for.contains(&OperationType::Delete) || !column.primary_key {
+                continue;
+            }
+
+            let Some(value) = fields.swap_remove(&column.id) else {
+                return Err(format!(
+                    "Missing or invalid required/primary key field: {}",
+                    column.id
+                )
+                .into());
+            };
+
+            let value = validate_value(value, &column.column_type, &column.id, column.nullable)
+                .map_err(|e| e.with_operation(OperationType::Delete))?;
+            state.insert(column.id.to_string(), value);
+        }
+
+        if let Some(ref validator) = setting.operations.validator {
+            validator
+                .validate(data, &state, OperationType::Delete)
+                .await?;
+        }
+
+        let Some(ref hooks) = setting.operations.hooks else {
+            deleter.delete(data, state).await?;
+            return Ok(());
+        };
+
+        deleter.delete(data, state.clone()).await?;
+
+        hooks.on_delete(data, &state, actor).await.map_err(|e| {
+            format!(
+                "Deleted successfully but the post-delete hook failed: {}",
+                e
+            )
+        })?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Returns whether the row matches all of the given (e.g. primary key) values
+pub(crate) fn row_matches_state(
+    row: &indexmap::IndexMap<String, Value>,
+    state: &indexmap::IndexMap<String, Value>,
+) -> bool {
+    state.iter().all(|(key, value)| row.get(key) == Some(value))
+}
+
+/// Checks the expected version in `state` against the current entry, then increments it, returning the
+/// expected version
+///
+/// This check alone can race with concurrent updates, the expected version is therefore also passed to
+/// `SettingUpdater::update_versioned` so that the executor can make the update conditional on it
+async fn check_and_increment_version<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    state: &mut indexmap::IndexMap<String, Value>,
+    version_column: &str,
+) -> Result<i64, Error> {
+    let Some(expected_version) = state.get(version_column).and_then(|v| v.as_i64()) else {
+        return Err(format!("Missing or invalid version field: {}", version_column).into());
+    };
+
+    let mut pkey_state = indexmap::IndexMap::new();
+    for column in setting.columns.iter() {
+        if !column.primary_key {
+            continue;
+        }
+
+        let Some(value) = state.get(&column.id) else {
+            return Err(format!(
+                "Missing or invalid required/primary key field: {}",
+                column.id
+            )
+            .into());
+        };
+
+        pkey_state.insert(column.id.to_string(), value.clone());
+    }
+
+    let rows = settings_view_unauthorized(setting, data, pkey_state.clone()).await?;
+
+    let Some(row) = rows
+        .into_iter()
+        .find(|row| row_matches_state(row, &pkey_state))
+    else {
+        return Err("No entry found matching the given primary key".into());
+    };
+
+    if row.get(version_column).and_then(|v| v.as_i64()) != Some(expected_version) {
+        return Err(VERSION_CONFLICT_ERROR.into());
+    }
+
+    state.insert(
+        version_column.to_string(),
+        Value::Number((expected_version + 1).into()),
+    );
+
+    Ok(expected_version)
+}
+
+/// Resolves the primary key of an entry from a complete alternate key using the view operation
+///
+/// Returns the primary key fields of the single matching entry
+async fn resolve_alternate_key<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    fields: indexmap::IndexMap<String, Value>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    let mut fields = fields;
+    let mut alt_state = indexmap::IndexMap::new();
+    for column in setting.columns.iter() {
+        if !column.alternate_key {
+            continue;
+        }
+
+        let Some(value) = fields.swap_remove(&column.id) else {
+            return Err(format!(
+                "Missing or invalid required/primary key field: expected either the primary key or {}",
+                column.id
+            )
+            .into());
+        };
+
+        let value = validate_value(value, &column.column_type, &column.id, column.nullable)
+            .map_err(|e| e.with_operation(OperationType::Delete))?;
+        alt_state.insert(column.id.to_string(), value);
+    }
+
+    let rows = settings_view_unauthorized(setting, data, alt_state.clone()).await?;
+
+    let mut matching = rows
+        .into_iter()
+        .filter(|row| row_matches_state(row, &alt_state));
+
+    let Some(row) = matching.next() else {
+        return Err("No entry found matching the given alternate key".into());
+    };
+
+    if matching.next().is_some() {
+        return Err("More than one entry matches the given alternate key".into());
+    }
+
+    let mut pkey_state = indexmap::IndexMap::new();
+    for column in setting.columns.iter() {
+        if !column.primary_key {
+            continue;
+        }
+
+        let Some(value) = row.get(&column.id) else {
+            return Err(format!(
+                "Could not resolve primary key field `{}` from the alternate key",
+                column.id
+            )
+            .into());
+        };
+
+        pkey_state.insert(column.id.to_string(), value.clone());
+    }
+
+    Ok(pkey_state)
+}
+
+/// Settings API: Patch implementation
+///
+/// Fetches the current entry (matched by the primary key fields in `patch`), applies the fields
+/// of the patch on top of it and then updates the entry. This means that only the changed fields
+/// need to be sent
+///
+/// Secret columns and columns ignored for View cannot be read back through view and so must always be
+/// provided in the patch. The same goes for the version column of versioned settings, as otherwise the
+/// version of the current entry would be used and the optimistic concurrency check skipped
+pub async fn settings_apply_patch<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    patch: indexmap::IndexMap<String, Value>,
+    actor: Option<&str>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    traced(&setting.id, OperationType::Update, async {
+        if setting.operations.update.is_none() {
+            return Err(format!("Operation not supported: {}", OperationType::Update).into());
+        };
+
+        authorize(setting, data, OperationType::Update, actor).await?;
+
+        for key in patch.keys() {
+            let Some(column) = setting.column(key) else {
+                return Err(format!("Unknown field in patch: {}", key).into());
+            };
+
+            if column.ignored_for.contains(&OperationType::Update) {
+                return Err(format!("Field `{}` is read-only and cannot be patched", key).into());
+            }
+        }
+
+        if let Some(ref version_column) = setting.version_column {
+            if !patch.contains_key(version_column) {
+                return Err(format!("Missing or invalid version field: {}", version_column).into());
+            }
+        }
+
+        let mut pkey_state = indexmap::IndexMap::new();
+        for column in setting.columns.iter() {
+            if !column.ignored_for.contains(&OperationType::Update)
+                && !patch.contains_key(&column.id)
+            {
+                if column.secret {
+                    return Err(format!(
+                        "Field `{}` is secret and must be provided in the patch",
+                        column.id
+                    )
+                    .into());
+                }
+
+                if column.ignored_for.contains(&OperationType::View) {
+                    return Err(format!(
+                        "Field `{}` is hidden from view and must be provided in the patch",
+                        column.id
+                    )
+                    .into());
+                }
+            }
+
+            if !column.primary_key {
+                continue;
+            }
+
+            let Some(value) = patch.get(&column.id) else {
+                return Err(format!(
+                    "Missing or invalid required/primary key field: {}",
+                    column.id
+                )
+                .into());
+            };
+
+            let value = validate_value(
+                value.clone(),
+                &column.column_type,
+                &column.id,
+                column.nullable,
+            )
+            .map_err(|e| e.with_operation(OperationType::Update))?;
+            pkey_state.insert(column.id.to_string(), value);
+        }
+
+        let rows = settings_view_unauthorized(setting, data, pkey_state.clone()).await?;
+
+        let Some(mut row) = rows
+            .into_iter()
+            .find(|row| row_matches_state(row, &pkey_state))
+        else {
+            return Err("No entry found matching the given primary key".into());
+        };
+
+        for (key, value) in patch {
+            row.insert(key, value);
+        }
+
+        update_authorized(setting, data, row, actor).await
+    })
+    .await
+}
+
+/// Returns the entry matching `primary_key` with the edited fields in `changes` applied on top of it, for
+/// passing on to update
+///
+/// Unlike view, this uses the entry as returned by the executor, so secret columns and columns ignored for
+/// View keep their stored values instead of being masked or dropped (and so nulled by the update). No
+/// authorization is done here, the caller is expected to have viewed the entry already
+pub(crate) async fn edited_entry<T: Clone>(
+    setting: &Setting<T>,
+    data: &T,
+    primary_key: indexmap::IndexMap<String, Value>,
+    changes: indexmap::IndexMap<String, Value>,
+) -> Result<indexmap::IndexMap<String, Value>, Error> {
+    let Some(ref viewer) = setting.operations.view else {
+        return Err(format!("Operation not supported: {}", OperationType::View).into());
+    };
+
+    let filters = validate_filters(setting, primary_key)?;
+    let rows = viewer.view(data, filters.clone()).await?;
+
+    let Some(mut row) = rows
+        .into_iter()
+        .find(|row| row_matches_state(row, &filters))
+    else {
+        return Err("No entry found matching the given primary key".into());
+    };
+
+    for (key, value) in changes {
+        row.insert(key, value);
+    }
+
+    Ok(row)
+}
+
+/// Checks that all `guild_id` kind columns of the entry match the given (invoking) guild
+///
+/// This is a safety net against executors returning entries belonging to other guilds
+pub fn settings_check_guild_id<T: Clone>(
+    setting: &Setting<T>,
+    state: &indexmap::IndexMap<String, Value>,
+    guild_id: &str,
+) -> Result<(), Error> {
+    for column in setting.columns.iter() {
+        let InnerColumnType::String { ref kind, .. } = column.column_type.inner() else {
+            continue;
+        };
+
+        if kind != "guild_id" {
+            continue;
+        }
+
+        match state.get(&column.id) {
+            Some(Value::String(s)) if s != guild_id => {
+                return Err(format!(
+                    "Column `{}` has guild ID {} which does not match the current guild {}",
+                    column.id, s, guild_id
+                )
+                .into());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ColumnBuilder, SettingBuilder};
+    use crate::common_columns;
+    use crate::memory::InMemorySettings;
+    use crate::types::{
+        SettingAuthorizer, SettingOperations, SettingUpdater, SettingValidator, SettingView,
+    };
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+
+    /// An authorizer allowing everything that counts how often it was called
+    #[derive(Clone, Default)]
+    struct CountingAuthorizer {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl SettingAuthorizer<()> for CountingAuthorizer {
+        async fn authorize<'a>(
+            &self,
+            _context: &(),
+            _operation_type: OperationType,
+            _actor: Option<&'a str>,
+        ) -> Result<(), Error> {
+            self.calls.fetch_add(1, AtomicOrdering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn string_column(id: &str) -> Column {
+        ColumnBuilder::new(
+            id,
+            id,
+            ColumnType::new_scalar(InnerColumnType::String {
+                min_length: None,
+                max_length: None,
+                allowed_values: vec![],
+                kind: "normal".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            }),
+        )
+        .build()
+    }
+
+    fn id_column() -> Column {
+        let mut column = common_columns::integer("id", "ID", "The ID");
+        column.primary_key = true;
+        column
+    }
+
+    fn row(id: i64, name: &str, note: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "id".to_string() => json!(id),
+            "name".to_string() => json!(name),
+            "note".to_string() => json!(note),
+        }
+    }
+
+    /// A setting with an `id` primary key, a `name` and a `note` that is read-only after creation
+    fn setting(executor: InMemorySettings, authorizer: CountingAuthorizer) -> Setting<()> {
+        let mut note = string_column("note");
+        note.ignored_for = vec![OperationType::Update];
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .column(id_column())
+            .column(string_column("name"))
+            .column(note)
+            .operations(SettingOperations::from(executor).with_authorizer(authorizer))
+            .build()
+            .unwrap()
+    }
+
+    /// A validator that bumps the version of every entry, simulating a concurrent update made between
+    /// the version check and the write
+    #[derive(Clone)]
+    struct ConcurrentUpdate {
+        executor: InMemorySettings,
+    }
+
+    #[async_trait::async_trait]
+    impl SettingValidator<()> for ConcurrentUpdate {
+        async fn validate<'a>(
+            &self,
+            _context: &(),
+            _state: &indexmap::IndexMap<String, Value>,
+            _operation_type: OperationType,
+        ) -> Result<(), Error> {
+            for mut row in self.executor.rows() {
+                let version = row["version"].as_i64().unwrap();
+                row.insert("version".to_string(), json!(version + 1));
+                SettingUpdater::<()>::update(&self.executor, &(), row).await?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn versioned_setting(executor: InMemorySettings, concurrent_update: bool) -> Setting<()> {
+        let mut operations = SettingOperations::from(executor.clone());
+        if concurrent_update {
+            operations = operations.with_validator(ConcurrentUpdate { executor });
+        }
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .column(id_column())
+            .column(string_column("name"))
+            .column(common_columns::integer("version", "Version", "The version"))
+            .operations(operations)
+            .version_column("version")
+            .build()
+            .unwrap()
+    }
+
+    fn versioned_row(name: &str, version: i64) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!(name),
+            "version".to_string() => json!(version),
+        }
+    }
+
+    #[tokio::test]
+    async fn versioned_update_increments_version() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![versioned_row("a", 3)]);
+        let setting = versioned_setting(executor.clone(), false);
+
+        let updated = settings_update(&setting, &(), versioned_row("b", 3), None)
+            .await
+            .unwrap();
+
+        assert_eq!(updated, versioned_row("b", 4));
+        assert_eq!(executor.rows(), vec![versioned_row("b", 4)]);
+    }
+
+    #[tokio::test]
+    async fn versioned_update_rejects_stale_version() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![versioned_row("a", 3)]);
+        let setting = versioned_setting(executor.clone(), false);
+
+        let err = settings_update(&setting, &(), versioned_row("b", 2), None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), VERSION_CONFLICT_ERROR);
+        assert_eq!(executor.rows(), vec![versioned_row("a", 3)]);
+    }
+
+    #[tokio::test]
+    async fn versioned_update_rejects_concurrent_update() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![versioned_row("a", 3)]);
+        let setting = versioned_setting(executor.clone(), true);
+
+        // The version check passes but the entry is modified before the write
+        let err = settings_update(&setting, &(), versioned_row("b", 3), None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), VERSION_CONFLICT_ERROR);
+        assert_eq!(executor.rows(), vec![versioned_row("a", 4)]);
+    }
+
+    #[tokio::test]
+    async fn patch_updates_single_field() {
+        let executor = InMemorySettings::with_rows(
+            vec!["id".to_string()],
+            vec![row(1, "a", "first"), row(2, "b", "second")],
+        );
+        let setting = setting(executor.clone(), CountingAuthorizer::default());
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(2),
+            "name".to_string() => json!("c"),
+        };
+        let updated = settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap();
+
+        assert_eq!(updated, row(2, "c", "second"));
+        assert_eq!(
+            executor.rows(),
+            vec![row(1, "a", "first"), row(2, "c", "second")]
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_rejects_read_only_field() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a", "first")]);
+        let setting = setting(executor.clone(), CountingAuthorizer::default());
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "note".to_string() => json!("changed"),
+        };
+        let err = settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("read-only"));
+        assert_eq!(executor.rows(), vec![row(1, "a", "first")]);
+    }
+
+    #[tokio::test]
+    async fn patch_authorizes_once() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a", "first")]);
+        let authorizer = CountingAuthorizer::default();
+        let setting = setting(executor, authorizer.clone());
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("b"),
+        };
+        settings_apply_patch(&setting, &(), patch, Some("1234"))
+            .await
+            .unwrap();
+
+        assert_eq!(authorizer.calls.load(AtomicOrdering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn patch_requires_version() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![versioned_row("a", 3)]);
+        let setting = versioned_setting(executor.clone(), false);
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("b"),
+        };
+        let err = settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("version"));
+        assert_eq!(executor.rows(), vec![versioned_row("a", 3)]);
+
+        // A stale version in the patch is still caught
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("b"),
+            "version".to_string() => json!(2),
+        };
+        let err = settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), VERSION_CONFLICT_ERROR);
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("b"),
+            "version".to_string() => json!(3),
+        };
+        settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap();
+
+        assert_eq!(executor.rows(), vec![versioned_row("b", 4)]);
+    }
+
+    #[tokio::test]
+    async fn patch_requires_fields_hidden_from_view() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a", "first")]);
+
+        let mut note = string_column("note");
+        note.ignored_for = vec![OperationType::View];
+        let setting = SettingBuilder::new("test", "Test", "A test setting")
+            .column(id_column())
+            .column(string_column("name"))
+            .column(note)
+            .operations(SettingOperations::from(executor.clone()))
+            .build()
+            .unwrap();
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("b"),
+        };
+        let err = settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("hidden from view"));
+        assert_eq!(executor.rows(), vec![row(1, "a", "first")]);
+
+        let patch = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("b"),
+            "note".to_string() => json!("first"),
+        };
+        settings_apply_patch(&setting, &(), patch, None)
+            .await
+            .unwrap();
+
+        assert_eq!(executor.rows(), vec![row(1, "b", "first")]);
+    }
+
+    #[tokio::test]
+    async fn edited_entry_keeps_secret_and_hidden_values() {
+        let stored = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("a"),
+            "note".to_string() => json!("first"),
+            "token".to_string() => json!("hunter2"),
+        };
+        let executor = InMemorySettings::with_rows(vec!["id".to_string()], vec![stored.clone()]);
+
+        let mut note = string_column("note");
+        note.ignored_for = vec![OperationType::View];
+        let mut token = string_column("token");
+        token.secret = true;
+        let setting = SettingBuilder::new("test", "Test", "A test setting")
+            .column(id_column())
+            .column(string_column("name"))
+            .column(note)
+            .column(token)
+            .operations(SettingOperations::from(executor.clone()))
+            .build()
+            .unwrap();
+
+        // What an editor gets to see
+        let viewed = settings_view_one(
+            &setting,
+            &(),
+            indexmap::indexmap! { "id".to_string() => json!(1) },
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_ne!(viewed.get("token"), Some(&json!("hunter2")));
+        assert!(!viewed.contains_key("note"));
+
+        let fields = edited_entry(
+            &setting,
+            &(),
+            indexmap::indexmap! { "id".to_string() => json!(1) },
+            indexmap::indexmap! { "name".to_string() => json!("b") },
+        )
+        .await
+        .unwrap();
+        settings_update(&setting, &(), fields, None).await.unwrap();
+
+        let mut expected = stored;
+        expected.insert("name".to_string(), json!("b"));
+        assert_eq!(executor.rows(), vec![expected]);
+    }
+
+    #[test]
+    fn parse_bool_accepts_natural_spellings() {
+        for s in ["true", "TRUE", "yes", "Yes", "y", "on", "ON", "1", " true "] {
+            assert_eq!(parse_bool(s, false), Some(true), "{}", s);
+        }
+
+        for s in ["false", "False", "no", "NO", "n", "off", "Off", "0", " no "] {
+            assert_eq!(parse_bool(s, false), Some(false), "{}", s);
+        }
+
+        for s in ["", "maybe", "2", "-1", "truee", "yess", "o"] {
+            assert_eq!(parse_bool(s, false), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn parse_bool_strict_only_accepts_true_and_false() {
+        assert_eq!(parse_bool("True", true), Some(true));
+        assert_eq!(parse_bool("false", true), Some(false));
 
-            match inner {
-                InnerColumnType::String {
-                    min_length,
-                    max_length,
-                    allowed_values,
-                    ..
-                } => match v {
-                    Value::String(s) => {
-                        if let Some(min_length) = min_length {
-                            if s.len() < *min_length {
-                                return Err(format!(
-                                    "Validation error in column {}, expected String with min length {} but got String with length {}",
-                                    column_id, min_length, s.len()
-                                )
-                                .into());
-                            }
-                        }
+        for s in ["yes", "no", "on", "off", "1", "0"] {
+            assert_eq!(parse_bool(s, true), None, "{}", s);
+        }
+    }
 
-                        if let Some(max_length) = max_length {
-                            if s.len() > *max_length {
-                                return Err(format!(
-                                    "Validation error in column {}, expected String with max length {} but got String with length {}",
-                                    column_id, max_length, s.len()
-                                )
-                                .into());
-                            }
-                        }
+    #[test]
+    fn validate_value_parses_booleans_per_column() {
+        let lenient = ColumnType::new_scalar(InnerColumnType::Boolean { strict: false });
+        let strict = ColumnType::new_scalar(InnerColumnType::Boolean { strict: true });
 
-                        if !allowed_values.is_empty() && !allowed_values.contains(&s) {
-                            return Err(format!(
-                                "Validation error in column {}, expected String with value in {:?} but got String with value {}",
-                                column_id, allowed_values, s
-                            )
-                            .into());
-                        }
+        assert_eq!(
+            validate_value(json!("on"), &lenient, "enabled", false).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            validate_value(json!(false), &strict, "enabled", false).unwrap(),
+            json!(false)
+        );
 
-                        Ok(Value::String(s))
-                    }
-                    _ => Err(format!(
-                        "Validation error in column {}, expected String but got {:?}",
-                        column_id, v
-                    )
-                    .into()),
-                },
-                InnerColumnType::Integer {} => match v {
-                    Value::String(s) => {
-                        if s.is_empty() {
-                            Err(format!(
-                                "Validation error in column {}, expected Integer but got empty String",
-                                column_id
-                            ).into())
-                        } else {
-                            let value = match s.parse::<i64>() {
-                                Ok(v) => v,
-                                Err(e) => {
-                                    return Err(format!(
-                                        "Validation error in column {}, expected Integer but got String that cannot be parsed: {}",
-                                        column_id, e
-                                    )
-                                    .into());
-                                }
-                            };
+        let err = validate_value(json!("on"), &strict, "enabled", false).unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::InvalidFormat);
 
-                            Ok(Value::Number(value.into()))
-                        }
-                    }
-                    Value::Number(v) => {
-                        if v.is_i64() {
-                            Ok(Value::Number(v))
-                        } else {
-                            Err(format!(
-                                "Validation error in column {}, expected Integer but got Float",
-                                column_id
-                            )
-                            .into())
-                        }
-                    }
-                    _ => Err(format!(
-                        "Validation error in column {}, expected Integer but got {:?}",
-                        column_id, v
-                    )
-                    .into()),
-                },
-                InnerColumnType::Float {} => match v {
-                    Value::String(s) => {
-                        let value = match s.parse::<f64>() {
-                            Ok(v) => v,
-                            Err(e) => {
-                                return Err(format!(
-                                    "Validation error in column {}, expected Float but got String that cannot be parsed: {}",
-                                    column_id, e
-                                )
-                                .into());
-                            }
-                        };
-
-                        let number = match Number::from_f64(value) {
-                            Some(n) => n,
-                            None => {
-                                return Err(format!(
-                                    "Validation error in column {}, expected Float but got Float that cannot be converted to JSON Number",
-                                    column_id
-                                )
-                                .into());
-                            }
-                        };
+        let err = validate_value(json!("maybe"), &lenient, "enabled", false).unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::InvalidFormat);
+        assert_eq!(err.column_id, "enabled");
+    }
+    fn role_array(allowed_values: Vec<String>, unique: bool) -> ColumnType {
+        ColumnType::Array {
+            inner: InnerColumnType::String {
+                min_length: None,
+                max_length: None,
+                allowed_values,
+                kind: "role".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            },
+            min_items: None,
+            max_items: None,
+            unique,
+        }
+    }
 
-                        Ok(Value::Number(number))
-                    }
-                    Value::Number(v) => {
-                        if v.is_f64() {
-                            Ok(Value::Number(v))
-                        } else {
-                            Err(format!(
-                                "Validation error in column {}, expected Float but got Integer",
-                                column_id
-                            )
-                            .into())
-                        }
-                    }
-                    _ => Err(format!(
-                        "Validation error in column {}, expected Float but got {:?}",
-                        column_id, v
-                    )
-                    .into()),
-                },
-                InnerColumnType::BitFlag { values } => {
-                    let v = match v {
-                        Value::String(s) => match s.parse::<i64>() {
-                            Ok(v) => v,
-                            Err(e) => {
-                                return Err(format!(
-                                        "Validation error in column {}, expected BitFlag but got String that cannot be parsed: {}",
-                                        column_id, e
-                                    )
-                                    .into());
-                            }
-                        },
-                        Value::Number(v) => {
-                            if v.is_i64() {
-                                v.as_i64().unwrap()
-                            } else {
-                                return Err(format!(
-                                    "Validation error in column {}, expected BitFlag but got Float",
-                                    column_id
-                                )
-                                .into());
-                            }
-                        }
-                        _ => {
-                            return Err(format!(
-                                "Validation error in column {}, expected BitFlag but got {:?}",
-                                column_id, v
-                            )
-                            .into())
-                        }
-                    };
+    #[test]
+    fn array_elements_are_checked_against_allowed_values() {
+        let column_type = role_array(vec!["1".to_string(), "2".to_string()], false);
 
-                    let mut final_value = 0;
+        assert_eq!(
+            validate_value(json!(["2", "1", "2"]), &column_type, "roles", false).unwrap(),
+            json!(["2", "1", "2"])
+        );
 
-                    // Set all the valid bits in final_value to ensure no unknown bits are being set
-                    for (_, bit) in values.iter() {
-                        if *bit & v == *bit {
-                            final_value |= *bit;
-                        }
-                    }
+        let err = validate_value(json!(["1", "3"]), &column_type, "roles", false).unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::NotAllowed);
 
-                    if final_value == 0 {
-                        // Set the first value as the default value
-                        let Some(fv) = values.values().next() else {
-                            return Err(
-                                format!(
-                                    "Validation error in column {}, expected BitFlag but no default value found",
-                                    column_id
-                                )
-                                .into()
-                            );
-                        };
-
-                        final_value = *fv;
-                    }
+        // No allowed values means any role is accepted
+        let column_type = role_array(vec![], false);
+        assert!(validate_value(json!(["1", "3"]), &column_type, "roles", false).is_ok());
+    }
 
-                    Ok(Value::Number(final_value.into()))
-                }
-                InnerColumnType::Boolean {} => match v {
-                    Value::String(s) => {
-                        let value = match s.parse::<bool>() {
-                            Ok(v) => v,
-                            Err(e) => {
-                                return Err(format!(
-                                    "Validation error in column {}, expected Boolean but got String that cannot be parsed: {}",
-                                    column_id, e
-                                )
-                                .into());
-                            }
-                        };
+    #[test]
+    fn large_array_against_large_allowed_set() {
+        // With a per-element linear scan this is 10000 * 20000 comparisons, the prebuilt set keeps it linear
+        let allowed_values = (1..=20_000).map(|v| v.to_string()).collect::<Vec<_>>();
+        let column_type = role_array(allowed_values, false);
 
-                        Ok(Value::Bool(value))
-                    }
-                    Value::Bool(v) => Ok(Value::Bool(v)),
-                    _ => Err(format!(
-                        "Validation error in column {}, expected Boolean but got {:?}",
-                        column_id, v
-                    )
-                    .into()),
-                },
-                InnerColumnType::Json { max_bytes, .. } => {
-                    // Convert back to json to get bytes
-                    match v {
-                        Value::String(s) => {
-                            if s.len() > max_bytes.unwrap_or(0) {
-                                return Err(
-                                    format!(
-                                        "Validation error in column {}, expected JSON with max bytes {} but got JSON with bytes {}",
-                                        column_id, max_bytes.unwrap_or(0), s.len()
-                                    )
-                                    .into()
-                                );
-                            }
+        let roles = (10_001..=20_000).map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            validate_value(json!(roles), &column_type, "roles", false).unwrap(),
+            json!(roles)
+        );
 
-                            let v: serde_json::Value = {
-                                if !s.starts_with("[") && !s.starts_with("{") {
-                                    serde_json::Value::String(s)
-                                } else {
-                                    match serde_json::from_str(&s) {
-                                        Ok(v) => v,
-                                        Err(e) => {
-                                            return Err(
-                                                format!(
-                                                    "Validation error in column {}, expected JSON but got String that cannot be parsed: {}",
-                                                    column_id, e
-                                                )
-                                                .into()
-                                            );
-                                        }
-                                    }
-                                }
-                            };
+        let mut roles = roles;
+        roles.push("20001".to_string());
+        let err = validate_value(json!(roles), &column_type, "roles", false).unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::NotAllowed);
+    }
+    fn guild_setting() -> Setting<()> {
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([
+                id_column(),
+                common_columns::guild_id("guild_id", "Guild ID", "The guild"),
+            ])
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "id".to_string()
+            ])))
+            .build()
+            .unwrap()
+    }
 
-                            Ok(v)
-                        }
-                        _ => {
-                            let bytes = match serde_json::to_string(&v) {
-                                Ok(b) => b,
-                                Err(e) => {
-                                    return Err(
-                                        format!(
-                                            "Validation error in column {}, expected JSON but got value that cannot be converted to JSON: {}",
-                                            column_id, e
-                                        )
-                                        .into()
-                                    );
-                                }
-                            };
+    #[test]
+    fn guild_id_must_be_a_snowflake() {
+        let column = common_columns::guild_id("guild_id", "Guild ID", "The guild");
 
-                            if let Some(max_bytes) = max_bytes {
-                                if bytes.len() > *max_bytes {
-                                    return Err(
-                                        format!(
-                                            "Validation error in column {}, expected JSON with max bytes {} but got JSON with bytes {}",
-                                            column_id, max_bytes, bytes.len()
-                                        )
-                                        .into()
-                                    );
-                                }
-                            }
+        assert_eq!(
+            validate_value(
+                json!("1234567890123456789"),
+                &column.column_type,
+                "guild_id",
+                false
+            )
+            .unwrap(),
+            json!("1234567890123456789")
+        );
 
-                            Ok(v)
-                        }
-                    }
-                }
-            }
+        for invalid in ["", "0", "abc", "-1", "18446744073709551615"] {
+            let err =
+                validate_value(json!(invalid), &column.column_type, "guild_id", false).unwrap_err();
+            assert_eq!(err.kind, ValidationErrorKind::InvalidFormat, "{}", invalid);
         }
-        ColumnType::Array { inner } => match v {
-            Value::Array(l) => {
-                let mut values: Vec<Value> = Vec::new();
-
-                let column_type = ColumnType::new_scalar(inner.clone());
-                for v in l {
-                    let new_v = validate_value(v, &column_type, column_id, nullable)?;
+    }
 
-                    values.push(new_v);
-                }
+    #[test]
+    fn guild_id_matching_the_invoking_guild_passes() {
+        let setting = guild_setting();
+        let state = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "guild_id".to_string() => json!("1234"),
+        };
 
-                Ok(Value::Array(values))
-            }
-            _ => Err(format!(
-                "Validation error in column {}, expected Array but got {:?}",
-                column_id, v
-            )
-            .into()),
-        },
+        assert!(settings_check_guild_id(&setting, &state, "1234").is_ok());
     }
-}
 
-/// Settings API: View implementation
-pub async fn settings_view<T: Clone>(
-    setting: &Setting<T>,
-    data: &T,
-    filters: indexmap::IndexMap<String, Value>, // The filters to apply
-) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
-    let Some(ref viewer) = setting.operations.view else {
-        return Err(format!("Operation not supported: {}", OperationType::View).into());
-    };
+    #[test]
+    fn guild_id_mismatch_is_flagged() {
+        let setting = guild_setting();
+        let state = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "guild_id".to_string() => json!("1234"),
+        };
 
-    let states = viewer.view(data, filters).await?;
+        let err = settings_check_guild_id(&setting, &state, "5678").unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{}", err);
+    }
+    /// A setting with an `id` primary key and a unique `name` alternate key
+    fn alternate_key_setting(executor: InMemorySettings) -> Setting<()> {
+        let mut name = string_column("name");
+        name.alternate_key = true;
 
-    let mut values: Vec<indexmap::IndexMap<String, Value>> = Vec::new();
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id_column(), name, string_column("note")])
+            .operations(SettingOperations::from(executor))
+            .build()
+            .unwrap()
+    }
 
-    for mut state in states {
-        // We know that the columns are in the same order as the row
-        for col in setting.columns.iter() {
-            let mut val = state.swap_remove(&col.id).unwrap_or(Value::Null);
+    #[tokio::test]
+    async fn delete_by_primary_key() {
+        let executor = InMemorySettings::with_rows(
+            vec!["id".to_string()],
+            vec![row(1, "a", "first"), row(2, "b", "second")],
+        );
+        let setting = alternate_key_setting(executor.clone());
 
-            // Validate the value
-            val = validate_value(val, &col.column_type, &col.id, col.nullable)?;
+        let fields = indexmap::indexmap! { "id".to_string() => json!(1) };
+        settings_delete(&setting, &(), fields, None).await.unwrap();
 
-            // Reinsert
-            state.insert(col.id.to_string(), val);
-        }
+        assert_eq!(executor.rows(), vec![row(2, "b", "second")]);
+    }
 
-        // Remove ignored columns + secret columns now that the actions have been executed
-        for col in setting.columns.iter() {
-            if col.secret {
-                state.swap_remove(&col.id);
-                continue; // Skip secret columns in view. **this applies to view and update only as create is creating a new object**
-            }
+    #[tokio::test]
+    async fn delete_by_alternate_key() {
+        let executor = InMemorySettings::with_rows(
+            vec!["id".to_string()],
+            vec![row(1, "a", "first"), row(2, "b", "second")],
+        );
+        let setting = alternate_key_setting(executor.clone());
 
-            if col.ignored_for.contains(&OperationType::View) {
-                state.swap_remove(&col.id);
-            }
-        }
+        let fields = indexmap::indexmap! { "name".to_string() => json!("b") };
+        settings_delete(&setting, &(), fields, None).await.unwrap();
 
-        values.push(state);
+        assert_eq!(executor.rows(), vec![row(1, "a", "first")]);
     }
 
-    Ok(values)
-}
+    #[tokio::test]
+    async fn delete_by_unknown_alternate_key_fails() {
+        let executor =
+            InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a", "first")]);
+        let setting = alternate_key_setting(executor.clone());
 
-/// Settings API: Create implementation
-pub async fn settings_create<T: Clone>(
-    setting: &Setting<T>,
-    data: &T,
-    fields: indexmap::IndexMap<String, Value>,
-) -> Result<indexmap::IndexMap<String, Value>, Error> {
-    let Some(ref creator) = setting.operations.create else {
-        return Err(format!("Operation not supported: {}", OperationType::Create).into());
-    };
+        let fields = indexmap::indexmap! { "name".to_string() => json!("b") };
+        let err = settings_delete(&setting, &(), fields, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No entry found"), "{}", err);
 
-    // Ensure all columns exist in fields, note that we can ignore extra fields so this one single loop is enough
-    let mut state = fields;
-    for column in setting.columns.iter() {
-        if column.ignored_for.contains(&OperationType::Create) {
-            continue;
+        // Neither key given
+        let err = settings_delete(&setting, &(), indexmap::IndexMap::new(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Missing"), "{}", err);
+
+        assert_eq!(executor.rows(), vec![row(1, "a", "first")]);
+    }
+    /// A setting partitioned by a filter-only `guild_id` that is part of the primary key
+    fn partitioned_setting(executor: InMemorySettings) -> Setting<()> {
+        let mut guild_id = string_column("guild_id");
+        guild_id.primary_key = true;
+        guild_id.filter_only = true;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([guild_id, id_column(), string_column("name")])
+            .operations(SettingOperations::from(executor))
+            .build()
+            .unwrap()
+    }
+
+    fn partitioned_row(guild_id: &str, id: i64, name: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "guild_id".to_string() => json!(guild_id),
+            "id".to_string() => json!(id),
+            "name".to_string() => json!(name),
         }
+    }
 
-        // If the column is ignored for, only parse, otherwise parse and validate
-        let value = {
-            // Get the value
-            let val = state.swap_remove(&column.id).unwrap_or(Value::Null);
+    #[tokio::test]
+    async fn filter_only_column_is_usable_as_filter() {
+        let executor = InMemorySettings::with_rows(
+            vec!["guild_id".to_string(), "id".to_string()],
+            vec![
+                partitioned_row("1", 1, "a"),
+                partitioned_row("2", 1, "b"),
+                partitioned_row("1", 2, "c"),
+            ],
+        );
+        let setting = partitioned_setting(executor);
 
-            validate_value(val, &column.column_type, &column.id, column.nullable)?
-        };
+        let rows = settings_view(
+            &setting,
+            &(),
+            indexmap::indexmap! { "guild_id".to_string() => json!("1") },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![partitioned_row("1", 1, "a"), partitioned_row("1", 2, "c")]
+        );
 
-        state.insert(column.id.to_string(), value);
+        // And in primary key matching
+        let row = settings_view_one(
+            &setting,
+            &(),
+            indexmap::indexmap! {
+                "guild_id".to_string() => json!("2"),
+                "id".to_string() => json!(1),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(row, Some(partitioned_row("2", 1, "b")));
+    }
+    /// A view that supports paging, counting how many pages were fetched
+    struct PagedView {
+        executor: InMemorySettings,
+        pages: Arc<AtomicUsize>,
     }
 
-    // Now execute all actions and handle null checks
-    for column in setting.columns.iter() {
-        // Checks should only happen if the column is not being intentionally ignored
-        if column.ignored_for.contains(&OperationType::Create) {
-            continue;
+    #[async_trait::async_trait]
+    impl SettingView<()> for PagedView {
+        async fn view<'a>(
+            &self,
+            context: &(),
+            filters: indexmap::IndexMap<String, Value>,
+        ) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+            self.executor.view(context, filters).await
         }
 
-        let Some(value) = state.get(&column.id) else {
-            return Err(format!(
-                "Internal error: Column `{}` not found in state despite just being parsed",
-                column.id
-            )
-            .into());
-        };
+        async fn view_page<'a>(
+            &self,
+            context: &(),
+            filters: indexmap::IndexMap<String, Value>,
+            limit: usize,
+            offset: usize,
+        ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+            self.pages.fetch_add(1, AtomicOrdering::SeqCst);
 
-        // Check if the column is nullable
-        if !column.nullable && matches!(value, Value::Null) {
-            return Err(format!("Missing or invalid field: {}", column.id).into());
+            let rows = self.executor.view(context, filters).await?;
+            let total_count = rows.len();
+
+            Ok(Some((
+                rows.into_iter().skip(offset).take(limit).collect(),
+                total_count,
+            )))
         }
     }
 
-    // Remove ignored columns now that the actions have been executed
-    for col in setting.columns.iter() {
-        if col.ignored_for.contains(&OperationType::Create) {
-            state.swap_remove(&col.id);
-        }
+    fn cursor_rows() -> Vec<indexmap::IndexMap<String, Value>> {
+        (1..=5)
+            .map(|id| row(id, &format!("name {}", id), "note"))
+            .collect()
     }
 
-    let new_state = creator.create(data, state).await?;
+    #[tokio::test]
+    async fn cursor_iterates_all_pages() {
+        let executor = InMemorySettings::with_rows(vec!["id".to_string()], cursor_rows());
+        let pages = Arc::new(AtomicUsize::new(0));
 
-    Ok(new_state)
-}
+        let mut operations = SettingOperations::from(executor.clone());
+        operations.view = Some(Arc::new(PagedView {
+            executor,
+            pages: pages.clone(),
+        }));
 
-/// Settings API: Update implementation
-pub async fn settings_update<T: Clone>(
-    setting: &Setting<T>,
-    data: &T,
-    fields: indexmap::IndexMap<String, Value>,
-) -> Result<indexmap::IndexMap<String, Value>, Error> {
-    let Some(ref updater) = setting.operations.update else {
-        return Err(format!("Operation not supported: {}", OperationType::Update).into());
-    };
+        let setting = SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id_column(), string_column("name"), string_column("note")])
+            .operations(operations)
+            .build()
+            .unwrap();
 
-    // Ensure all columns exist in fields, note that we can ignore extra fields so this one single loop is enough
-    let mut state = fields;
-    for column in setting.columns.iter() {
-        if column.ignored_for.contains(&OperationType::Update) {
-            continue;
+        let mut cursor =
+            SettingsCursor::new(&setting, &(), indexmap::IndexMap::new(), None).page_size(2);
+
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.next().await.unwrap() {
+            rows.push(row);
         }
 
-        // If the column is ignored for, only parse, otherwise parse and validate
-        let value = {
-            // Get the value
-            let val = state.swap_remove(&column.id).unwrap_or(Value::Null);
-            validate_value(val, &column.column_type, &column.id, column.nullable)?
-        };
+        assert_eq!(rows, cursor_rows());
+        assert_eq!(pages.load(AtomicOrdering::SeqCst), 3);
 
-        state.insert(column.id.to_string(), value);
+        // The cursor stays exhausted
+        assert_eq!(cursor.next().await.unwrap(), None);
+        assert_eq!(pages.load(AtomicOrdering::SeqCst), 3);
     }
 
-    // Now execute all actions and handle null checks
-    for column in setting.columns.iter() {
-        // Checks should only happen if the column is not being intentionally ignored
-        if column.ignored_for.contains(&OperationType::Update) {
-            continue;
-        }
+    #[tokio::test]
+    async fn cursor_falls_back_to_eager_view() {
+        let executor = InMemorySettings::with_rows(vec!["id".to_string()], cursor_rows());
+        let setting = setting(executor, CountingAuthorizer::default());
 
-        let Some(value) = state.get(&column.id) else {
-            return Err(format!(
-                "Internal error: Column `{}` not found in state despite just being parsed",
-                column.id
-            )
-            .into());
-        };
+        let mut cursor = SettingsCursor::new(&setting, &(), indexmap::IndexMap::new(), None)
+            .page_size(2)
+            .sort_by(SortBy {
+                column: "id".to_string(),
+                descending: true,
+            });
 
-        // Check if the column is nullable
-        if !column.nullable && matches!(value, Value::Null) {
-            return Err(format!("Missing or invalid field: {}", column.id).into());
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.next().await.unwrap() {
+            rows.push(row);
         }
+
+        let mut expected = cursor_rows();
+        expected.reverse();
+        assert_eq!(rows, expected);
     }
+    fn strict_setting(executor: InMemorySettings, strict_columns: bool) -> Setting<()> {
+        let mut nickname = string_column("nickname");
+        nickname.nullable = true;
 
-    // Remove ignored columns now that the actions have been executed
-    for col in setting.columns.iter() {
-        if col.ignored_for.contains(&OperationType::Update) {
-            state.swap_remove(&col.id);
-        }
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id_column(), string_column("name"), nickname])
+            .operations(SettingOperations::from(executor))
+            .strict_columns(strict_columns)
+            .build()
+            .unwrap()
     }
 
-    let new_state = updater.update(data, state).await?;
+    #[tokio::test]
+    async fn strict_columns_flags_missing_non_nullable_column() {
+        // The executor does not return `name` at all
+        let executor = InMemorySettings::with_rows(
+            vec!["id".to_string()],
+            vec![indexmap::indexmap! { "id".to_string() => json!(1) }],
+        );
 
-    Ok(new_state)
-}
+        let err = settings_view(
+            &strict_setting(executor.clone(), true),
+            &(),
+            indexmap::IndexMap::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Internal error: Column `name` was not returned by the executor"
+        );
 
-/// Settings API: Delete implementation
-#[allow(clippy::too_many_arguments)]
-pub async fn settings_delete<T: Clone>(
-    setting: &Setting<T>,
-    data: &T,
-    fields: indexmap::IndexMap<String, Value>,
-) -> Result<(), Error> {
-    let Some(ref deleter) = setting.operations.delete else {
-        return Err(format!("Operation not supported: {}", OperationType::Delete).into());
-    };
+        // The lenient default treats the column as null, which then fails validation as usual
+        let err = settings_view(
+            &strict_setting(executor, false),
+            &(),
+            indexmap::IndexMap::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ValidationError>().unwrap().kind,
+            ValidationErrorKind::NotNullable
+        );
+    }
 
-    let mut fields = fields;
-    let mut state = indexmap::IndexMap::with_capacity(setting.columns.len());
-    for column in setting.columns.iter() {
-        if column.ignored_for.contains(&OperationType::Delete) || !column.primary_key {
-            continue;
-        }
+    #[tokio::test]
+    async fn strict_columns_allows_missing_nullable_column() {
+        let executor = InMemorySettings::with_rows(
+            vec!["id".to_string()],
+            vec![indexmap::indexmap! {
+                "id".to_string() => json!(1),
+                "name".to_string() => json!("a"),
+            }],
+        );
 
-        let Some(value) = fields.swap_remove(&column.id) else {
-            return Err(format!("Missing or invalid required/primary key field: {}", column.id).into());
+        let rows = settings_view(
+            &strict_setting(executor, true),
+            &(),
+            indexmap::IndexMap::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![indexmap::indexmap! {
+                "id".to_string() => json!(1),
+                "name".to_string() => json!("a"),
+                "nickname".to_string() => Value::Null,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn strict_columns_treats_explicit_null_as_returned() {
+        let executor = InMemorySettings::with_rows(
+            vec!["id".to_string()],
+            vec![indexmap::indexmap! {
+                "id".to_string() => json!(1),
+                "name".to_string() => Value::Null,
+            }],
+        );
+
+        let err = settings_view(
+            &strict_setting(executor, true),
+            &(),
+            indexmap::IndexMap::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ValidationError>().unwrap().kind,
+            ValidationErrorKind::NotNullable
+        );
+    }
+    #[test]
+    fn unique_array_rejects_duplicate_snowflakes() {
+        let column_type = role_array(vec![], true);
+
+        assert!(validate_value(json!(["1234", "5678"]), &column_type, "roles", false).is_ok());
+
+        let err = validate_value(
+            json!(["1234", "5678", "1234"]),
+            &column_type,
+            "roles",
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::Duplicate);
+        assert_eq!(err.column_id, "roles");
+        assert!(err.message.contains("\"1234\""), "{}", err.message);
+
+        // Duplicates are fine unless the array is unique
+        let column_type = role_array(vec![], false);
+        assert!(validate_value(json!(["1234", "1234"]), &column_type, "roles", false).is_ok());
+    }
+
+    #[test]
+    fn unique_array_compares_validated_values() {
+        let column_type = ColumnType::Array {
+            inner: InnerColumnType::Float {
+                min: None,
+                max: None,
+                allowed_values: vec![],
+            },
+            min_items: None,
+            max_items: None,
+            unique: true,
         };
 
-        let value = validate_value(value, &column.column_type, &column.id, column.nullable)?;
-        state.insert(column.id.to_string(), value);
+        for duplicates in [json!(["1.5", 1.5]), json!([0.0, -0.0])] {
+            let err =
+                validate_value(duplicates.clone(), &column_type, "values", false).unwrap_err();
+            assert_eq!(err.kind, ValidationErrorKind::Duplicate, "{}", duplicates);
+        }
+
+        assert!(validate_value(json!([1.5, 2.5]), &column_type, "values", false).is_ok());
     }
+    #[test]
+    fn merge_value_merges_nested_maps() {
+        let mut target = json!({
+            "name": "a",
+            "limits": { "max": 5, "min": 1, "nested": { "a": 1 } },
+        });
 
-    deleter.delete(data, state).await?;
+        merge_value(
+            &mut target,
+            json!({
+                "limits": { "max": 10, "nested": { "b": 2 } },
+                "enabled": true,
+            }),
+            ArrayMerge::default(),
+        );
 
-    Ok(())
+        assert_eq!(
+            target,
+            json!({
+                "name": "a",
+                "limits": { "max": 10, "min": 1, "nested": { "a": 1, "b": 2 } },
+                "enabled": true,
+            })
+        );
+    }
+
+    #[test]
+    fn merge_value_replaces_mismatched_types() {
+        let mut target = json!({ "a": "string", "b": { "c": 1 }, "d": 1 });
+
+        merge_value(
+            &mut target,
+            json!({ "a": { "c": 1 }, "b": [1, 2], "d": null }),
+            ArrayMerge::Concat,
+        );
+
+        assert_eq!(target, json!({ "a": { "c": 1 }, "b": [1, 2], "d": null }));
+
+        // A scalar merged into a map replaces it entirely
+        let mut target = json!({ "a": 1 });
+        merge_value(&mut target, json!(5), ArrayMerge::Replace);
+        assert_eq!(target, json!(5));
+    }
+
+    #[test]
+    fn merge_value_array_policy() {
+        let mut target = json!({ "items": [1, 2] });
+        merge_value(&mut target, json!({ "items": [3] }), ArrayMerge::Replace);
+        assert_eq!(target, json!({ "items": [3] }));
+
+        let mut target = json!({ "items": [1, 2] });
+        merge_value(&mut target, json!({ "items": [3] }), ArrayMerge::Concat);
+        assert_eq!(target, json!({ "items": [1, 2, 3] }));
+    }
+    #[test]
+    fn date_like_strings_stay_strings() {
+        let string = string_column("note").column_type;
+        let json_column = ColumnType::new_scalar(InnerColumnType::Json {
+            kind: "normal".to_string(),
+            max_bytes: Some(1024),
+        });
+
+        for s in [
+            "2024-01-01",
+            "2024-01-01T00:00:00Z",
+            "2024-13-45",
+            "1700000000",
+            "12:30",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+        ] {
+            assert_eq!(
+                validate_value(json!(s), &string, "note", false).unwrap(),
+                json!(s),
+                "{}",
+                s
+            );
+            assert_eq!(
+                validate_value(json!(s), &json_column, "data", false).unwrap(),
+                json!(s),
+                "{}",
+                s
+            );
+        }
+    }
 }
@@ -1,100 +1,233 @@
-use super::types::{Column, ColumnSuggestion, ColumnType, InnerColumnType, OperationType};
+use super::builder::ColumnBuilder;
+use super::types::{Column, ColumnType, InnerColumnType, OperationType};
 
 /// Standard created_at column
 pub fn created_at() -> Column {
-    Column {
-        id: "created_at".to_string(),
-        name: "Created At".to_string(),
-        description: "The time the record was created.".to_string(),
-        primary_key: false,
-        column_type: ColumnType::new_scalar(InnerColumnType::String {
+    ColumnBuilder::new(
+        "created_at",
+        "Created At",
+        ColumnType::new_scalar(InnerColumnType::String {
             min_length: None,
             max_length: None,
             allowed_values: vec![],
             kind: "timestamp".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
         }),
-        nullable: false,
-        ignored_for: vec![OperationType::Create, OperationType::Update],
-        secret: false,
-        suggestions: ColumnSuggestion::None {},
-    }
+    )
+    .description("The time the record was created.")
+    .ignored_for(vec![OperationType::Create, OperationType::Update])
+    .build()
 }
 
 /// Standard created_by column
 pub fn created_by() -> Column {
-    Column {
-        id: "created_by".to_string(),
-        name: "Created By".to_string(),
-        description: "The user who created the record.".to_string(),
-        primary_key: false,
-        column_type: ColumnType::new_scalar(InnerColumnType::String {
+    ColumnBuilder::new(
+        "created_by",
+        "Created By",
+        ColumnType::new_scalar(InnerColumnType::String {
             min_length: None,
             max_length: None,
             allowed_values: vec![],
             kind: "user".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
         }),
-        ignored_for: vec![OperationType::Create, OperationType::Update],
-        secret: false,
-        nullable: false,
-        suggestions: ColumnSuggestion::None {},
-    }
+    )
+    .description("The user who created the record.")
+    .ignored_for(vec![OperationType::Create, OperationType::Update])
+    .build()
 }
 
 /// Standard last_updated_at column
 pub fn last_updated_at() -> Column {
-    Column {
-        id: "last_updated_at".to_string(),
-        name: "Last Updated At".to_string(),
-        description: "The time the record was last updated.".to_string(),
-        primary_key: false,
-        column_type: ColumnType::new_scalar(InnerColumnType::String {
+    ColumnBuilder::new(
+        "last_updated_at",
+        "Last Updated At",
+        ColumnType::new_scalar(InnerColumnType::String {
             min_length: None,
             max_length: None,
             allowed_values: vec![],
             kind: "timestamp".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
         }),
-        ignored_for: vec![OperationType::Create, OperationType::Update],
-        secret: false,
-        nullable: false,
-        suggestions: ColumnSuggestion::None {},
-    }
+    )
+    .description("The time the record was last updated.")
+    .ignored_for(vec![OperationType::Create, OperationType::Update])
+    .build()
 }
 
 /// Standard last_updated_by column
 pub fn last_updated_by() -> Column {
-    Column {
-        id: "last_updated_by".to_string(),
-        name: "Last Updated By".to_string(),
-        description: "The user who last updated the record.".to_string(),
-        primary_key: false,
-        column_type: ColumnType::new_scalar(InnerColumnType::String {
+    ColumnBuilder::new(
+        "last_updated_by",
+        "Last Updated By",
+        ColumnType::new_scalar(InnerColumnType::String {
             min_length: None,
             max_length: None,
             allowed_values: vec![],
             kind: "user".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
         }),
-        ignored_for: vec![OperationType::Create, OperationType::Update],
-        secret: false,
-        nullable: false,
-        suggestions: ColumnSuggestion::None {},
-    }
+    )
+    .description("The user who last updated the record.")
+    .ignored_for(vec![OperationType::Create, OperationType::Update])
+    .build()
 }
 
 pub fn guild_id(id: &'static str, name: &'static str, description: &'static str) -> Column {
-    Column {
-        id: id.to_string(),
-        name: name.to_string(),
-        description: description.to_string(),
-        primary_key: false,
-        column_type: ColumnType::new_scalar(InnerColumnType::String {
+    ColumnBuilder::new(
+        id,
+        name,
+        ColumnType::new_scalar(InnerColumnType::String {
             min_length: None,
             max_length: None,
             allowed_values: vec![],
             kind: "guild_id".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
         }),
-        nullable: false,
-        suggestions: ColumnSuggestion::None {},
-        ignored_for: vec![OperationType::Create, OperationType::Update],
-        secret: false,
+    )
+    .description(description)
+    .ignored_for(vec![OperationType::Create, OperationType::Update])
+    .build()
+}
+
+/// Standard integer column
+pub fn integer(id: &'static str, name: &'static str, description: &'static str) -> Column {
+    scalar(
+        id,
+        name,
+        description,
+        InnerColumnType::Integer {
+            min: None,
+            max: None,
+            allowed_values: Vec::new(),
+        },
+    )
+}
+
+/// Standard float column
+pub fn float(id: &'static str, name: &'static str, description: &'static str) -> Column {
+    scalar(
+        id,
+        name,
+        description,
+        InnerColumnType::Float {
+            min: None,
+            max: None,
+            allowed_values: Vec::new(),
+        },
+    )
+}
+
+/// Standard (lenient) boolean column
+pub fn boolean(id: &'static str, name: &'static str, description: &'static str) -> Column {
+    scalar(
+        id,
+        name,
+        description,
+        InnerColumnType::Boolean { strict: false },
+    )
+}
+
+/// Standard bitflag column
+pub fn bitflag(
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    values: indexmap::IndexMap<String, i64>,
+) -> Column {
+    scalar(
+        id,
+        name,
+        description,
+        InnerColumnType::BitFlag {
+            values,
+            strict: false,
+        },
+    )
+}
+
+/// A non-nullable, non-secret scalar column that is not ignored for any operation
+fn scalar(
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    inner: InnerColumnType,
+) -> Column {
+    ColumnBuilder::new(id, name, ColumnType::new_scalar(inner))
+        .description(description)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_defaults(column: &Column, id: &str, name: &str, description: &str) {
+        assert_eq!(column.id, id);
+        assert_eq!(column.name, name);
+        assert_eq!(column.description, description);
+        assert!(!column.primary_key);
+        assert!(!column.nullable);
+        assert!(!column.secret);
+        assert!(column.ignored_for.is_empty());
+        assert!(!column.column_type.is_array());
+    }
+
+    #[test]
+    fn integer_column() {
+        let column = integer("count", "Count", "The count");
+        assert_defaults(&column, "count", "Count", "The count");
+        assert!(matches!(
+            column.column_type.inner(),
+            InnerColumnType::Integer {
+                min: None,
+                max: None,
+                allowed_values
+            } if allowed_values.is_empty()
+        ));
+    }
+
+    #[test]
+    fn float_column() {
+        let column = float("ratio", "Ratio", "The ratio");
+        assert_defaults(&column, "ratio", "Ratio", "The ratio");
+        assert!(matches!(
+            column.column_type.inner(),
+            InnerColumnType::Float {
+                min: None,
+                max: None,
+                allowed_values
+            } if allowed_values.is_empty()
+        ));
+    }
+
+    #[test]
+    fn boolean_column() {
+        let column = boolean("enabled", "Enabled", "Whether it is enabled");
+        assert_defaults(&column, "enabled", "Enabled", "Whether it is enabled");
+        assert!(matches!(
+            column.column_type.inner(),
+            InnerColumnType::Boolean { strict: false }
+        ));
+    }
+
+    #[test]
+    fn bitflag_column() {
+        let values = indexmap::indexmap! {
+            "read".to_string() => 1,
+            "write".to_string() => 2,
+        };
+
+        let column = bitflag("perms", "Permissions", "The permissions", values.clone());
+        assert_defaults(&column, "perms", "Permissions", "The permissions");
+        assert!(matches!(
+            column.column_type.inner(),
+            InnerColumnType::BitFlag { values: v, strict: false } if *v == values
+        ));
     }
 }
@@ -0,0 +1,373 @@
+use crate::cfg::validate_value;
+use crate::types::{Column, ColumnType, InnerColumnType, OperationType, Setting};
+use crate::Error;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// The state of a form (wizard) that collects the fields of a setting one at a time
+///
+/// This is independent of Discord, front-ends are expected to drive it by showing the
+/// current step to the user and calling `submit`, `skip`, `back` based on their input
+pub struct FormState {
+    /// The columns of the setting
+    columns: Arc<Vec<Column>>,
+
+    /// The indexes (into columns) of the columns that need to be prompted for
+    steps: Vec<usize>,
+
+    /// The current step
+    index: usize,
+
+    /// The operation the form is collecting fields for
+    operation_type: OperationType,
+
+    /// The values collected so far
+    values: indexmap::IndexMap<String, Value>,
+}
+
+impl FormState {
+    /// Creates a new form for the given operation, `prefilled` values are kept as-is and are not prompted for
+    pub fn new<T: Clone>(
+        setting: &Setting<T>,
+        operation_type: OperationType,
+        prefilled: indexmap::IndexMap<String, Value>,
+    ) -> Self {
+        let mut steps = Vec::new();
+
+        for (idx, column) in setting.columns.iter().enumerate() {
+            if column.ignored_for.contains(&operation_type) {
+                continue;
+            }
+
+            if prefilled.contains_key(&column.id) || column.filter_only {
+                continue;
+            }
+
+            steps.push(idx);
+        }
+
+        Self {
+            columns: setting.columns.clone(),
+            steps,
+            index: 0,
+            operation_type,
+            values: prefilled,
+        }
+    }
+
+    /// Returns the column currently being prompted for, or None if the form is complete
+    pub fn current(&self) -> Option<&Column> {
+        self.steps.get(self.index).map(|idx| &self.columns[*idx])
+    }
+
+    /// Returns the current step (1-indexed) and the total number of steps
+    pub fn position(&self) -> (usize, usize) {
+        (
+            usize::min(self.index + 1, self.steps.len()),
+            self.steps.len(),
+        )
+    }
+
+    /// Returns whether all steps have been completed
+    pub fn is_complete(&self) -> bool {
+        self.index >= self.steps.len()
+    }
+
+    /// Returns whether there is a previous step to go back to
+    pub fn can_go_back(&self) -> bool {
+        self.index > 0
+    }
+
+    /// Returns whether the current step may be skipped
+    pub fn can_skip(&self) -> bool {
+        match self.current() {
+            Some(column) => !column.is_required_for_operation_type(self.operation_type),
+            None => false,
+        }
+    }
+
+    /// Returns the operation the form is collecting fields for
+    pub fn operation_type(&self) -> OperationType {
+        self.operation_type
+    }
+
+    /// Submits a value for the current step, validating it against the column before advancing
+    pub fn submit(&mut self, value: Value) -> Result<(), Error> {
+        let Some(idx) = self.steps.get(self.index) else {
+            return Err("Form is already complete".into());
+        };
+
+        let column = &self.columns[*idx];
+        let value = validate_value(value, &column.column_type, &column.id, column.nullable)
+            .map_err(|e| e.with_operation(self.operation_type))?;
+
+        if !column.nullable && matches!(value, Value::Null) {
+            return Err(format!("Missing or invalid field: {}", column.id).into());
+        }
+
+        self.values.insert(column.id.to_string(), value);
+        self.index += 1;
+
+        Ok(())
+    }
+
+    /// Skips the current step, this is only allowed for columns not required by the operation
+    pub fn skip(&mut self) -> Result<(), Error> {
+        let Some(idx) = self.steps.get(self.index) else {
+            return Err("Form is already complete".into());
+        };
+
+        let column = &self.columns[*idx];
+        if column.is_required_for_operation_type(self.operation_type) {
+            return Err(format!("Field `{}` is required and cannot be skipped", column.id).into());
+        }
+
+        self.values.swap_remove(&column.id);
+        self.index += 1;
+
+        Ok(())
+    }
+
+    /// Goes back to the previous step, returning false if already at the first step
+    pub fn back(&mut self) -> bool {
+        if self.index == 0 {
+            return false;
+        }
+
+        self.index -= 1;
+        true
+    }
+
+    /// Returns the value currently collected for the current step, if any
+    pub fn current_value(&self) -> Option<&Value> {
+        self.current().and_then(|c| self.values.get(&c.id))
+    }
+
+    /// Returns the values collected so far
+    pub fn values(&self) -> &indexmap::IndexMap<String, Value> {
+        &self.values
+    }
+
+    /// Consumes the form, returning the collected values
+    pub fn into_values(self) -> indexmap::IndexMap<String, Value> {
+        self.values
+    }
+}
+
+/// Converts user-entered text into a Value suitable for the given column type
+///
+/// Arrays are comma separated and bitflags may be given as `;` separated flag names
+pub fn parse_form_input(column_type: &ColumnType, input: &str) -> Value {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Value::Null;
+    }
+
+    match column_type {
+        ColumnType::Scalar { inner } => parse_form_scalar(inner, input),
+        ColumnType::Array { inner, .. } => Value::Array(
+            input
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_form_scalar(inner, s))
+                .collect(),
+        ),
+    }
+}
+
+fn parse_form_scalar(inner: &InnerColumnType, input: &str) -> Value {
+    match inner {
+        InnerColumnType::BitFlag { values, .. } => {
+            if input.parse::<i64>().is_ok() {
+                return Value::String(input.to_string());
+            }
+
+            let mut bitflags = 0;
+
+            for value in input.split(';') {
+                if let Some(value) = values.get(value.trim()) {
+                    bitflags |= *value;
+                }
+            }
+
+            Value::Number(bitflags.into())
+        }
+        _ => Value::String(input.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ColumnBuilder, SettingBuilder};
+    use crate::common_columns;
+    use crate::memory::InMemorySettings;
+    use crate::types::SettingOperations;
+    use serde_json::json;
+
+    fn setting() -> Setting<()> {
+        let note = ColumnBuilder::new(
+            "note",
+            "Note",
+            ColumnType::new_scalar(InnerColumnType::String {
+                min_length: None,
+                max_length: None,
+                allowed_values: vec![],
+                kind: "normal".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            }),
+        )
+        .nullable(true)
+        .build();
+
+        let mut id = common_columns::integer("id", "ID", "The ID");
+        id.primary_key = true;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .column(id)
+            .column(common_columns::integer("count", "Count", "The count"))
+            .column(note)
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "id".to_string()
+            ])))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn submit_advances_until_complete() {
+        let setting = setting();
+        let mut form = FormState::new(&setting, OperationType::Create, indexmap::IndexMap::new());
+
+        assert_eq!(form.position(), (1, 3));
+        assert_eq!(form.current().unwrap().id, "id");
+        assert!(!form.can_go_back());
+
+        form.submit(json!("1")).unwrap();
+        assert_eq!(form.position(), (2, 3));
+        form.submit(json!(5)).unwrap();
+        form.submit(json!("hello")).unwrap();
+
+        assert!(form.is_complete());
+        assert!(form.current().is_none());
+        assert_eq!(form.position(), (3, 3));
+        assert!(form.submit(json!("extra")).is_err());
+        assert!(form.skip().is_err());
+
+        assert_eq!(
+            form.into_values(),
+            indexmap::indexmap! {
+                "id".to_string() => json!(1),
+                "count".to_string() => json!(5),
+                "note".to_string() => json!("hello"),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_submit_does_not_advance() {
+        let setting = setting();
+        let mut form = FormState::new(&setting, OperationType::Create, indexmap::IndexMap::new());
+
+        assert!(form.submit(json!("not a number")).is_err());
+        assert!(form.submit(Value::Null).is_err());
+        assert_eq!(form.position(), (1, 3));
+        assert!(form.values().is_empty());
+    }
+
+    #[test]
+    fn skip_only_optional_columns() {
+        let setting = setting();
+        let mut form = FormState::new(&setting, OperationType::Create, indexmap::IndexMap::new());
+
+        // id is required for create
+        assert!(!form.can_skip());
+        assert!(form.skip().is_err());
+        assert_eq!(form.position(), (1, 3));
+
+        form.submit(json!(1)).unwrap();
+        form.submit(json!(2)).unwrap();
+
+        assert!(form.can_skip());
+        form.skip().unwrap();
+        assert!(form.is_complete());
+        assert!(!form.values().contains_key("note"));
+    }
+
+    #[test]
+    fn back_keeps_collected_values() {
+        let setting = setting();
+        let mut form = FormState::new(&setting, OperationType::Create, indexmap::IndexMap::new());
+
+        // Going back at the first step does nothing
+        assert!(!form.back());
+        assert_eq!(form.position(), (1, 3));
+
+        form.submit(json!(1)).unwrap();
+        form.submit(json!(2)).unwrap();
+
+        assert!(form.back());
+        assert_eq!(form.current().unwrap().id, "count");
+        assert_eq!(form.current_value(), Some(&json!(2)));
+
+        form.submit(json!(3)).unwrap();
+        assert_eq!(form.current().unwrap().id, "note");
+        assert_eq!(form.values().get("count"), Some(&json!(3)));
+    }
+
+    #[test]
+    fn skipping_after_back_removes_value() {
+        let setting = setting();
+        let mut form = FormState::new(&setting, OperationType::Create, indexmap::IndexMap::new());
+
+        form.submit(json!(1)).unwrap();
+        form.submit(json!(2)).unwrap();
+        form.submit(json!("hello")).unwrap();
+
+        assert!(form.back());
+        form.skip().unwrap();
+        assert!(form.is_complete());
+        assert!(!form.values().contains_key("note"));
+    }
+
+    #[test]
+    fn prefilled_and_ignored_columns_are_not_prompted() {
+        let setting = setting();
+        let form = FormState::new(
+            &setting,
+            OperationType::Update,
+            indexmap::indexmap! { "id".to_string() => json!(1) },
+        );
+
+        assert_eq!(form.position(), (1, 2));
+        assert_eq!(form.current().unwrap().id, "count");
+
+        // Only primary key columns are required for update
+        assert!(form.can_skip());
+        assert_eq!(form.operation_type(), OperationType::Update);
+    }
+
+    #[test]
+    fn parse_form_input_handles_arrays_and_bitflags() {
+        let array = ColumnType::new_array(InnerColumnType::Integer {
+            min: None,
+            max: None,
+            allowed_values: vec![],
+        });
+        assert_eq!(
+            parse_form_input(&array, " 1, 2,,3 "),
+            json!(["1", "2", "3"])
+        );
+        assert_eq!(parse_form_input(&array, "  "), Value::Null);
+
+        let bitflag = ColumnType::new_scalar(InnerColumnType::BitFlag {
+            values: indexmap::indexmap! { "a".to_string() => 1, "b".to_string() => 4 },
+            strict: false,
+        });
+        assert_eq!(parse_form_input(&bitflag, "a; b"), json!(5));
+        assert_eq!(parse_form_input(&bitflag, "2"), json!("2"));
+    }
+}
@@ -1,5 +1,14 @@
+pub mod builder;
+pub mod cache;
 pub mod cfg;
 pub mod common_columns;
+pub mod form;
+mod macros;
+pub mod memory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod registry;
+pub mod schema;
 pub mod serenity;
 pub mod types;
 
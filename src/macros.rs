@@ -0,0 +1,53 @@
+/// Defines a `Setting` through `SettingBuilder`, evaluating to `Result<Setting<_>, Error>`
+///
+/// The id must be a string literal and is checked to be non-empty at compile time. Keys must be given in
+/// the order below, `title_template`, `ui_options`, `version_column` and `required_permissions` are
+/// optional. The schema is checked when the macro is evaluated (see `SettingBuilder::build`), so an
+/// unknown column in the title template or a missing primary key is reported as soon as the setting is
+/// defined rather than on first use:
+///
+/// ```ignore
+/// let setting = ar_settings::setting! {
+///     id: "warn_thresholds",
+///     name: "Warn Thresholds",
+///     description: "Actions taken once a member reaches a number of warnings",
+///     title_template: "{warnings} warnings",
+///     columns: [
+///         common_columns::guild_id("guild_id", "Guild ID", "The guild the threshold belongs to"),
+///         ColumnBuilder::new("warnings", "Warnings", ColumnType::new_scalar(InnerColumnType::Integer {
+///             min: Some(1),
+///             max: None,
+///             allowed_values: vec![],
+///         }))
+///         .primary_key(true)
+///         .build(),
+///     ],
+///     operations: SettingOperations::from(executor),
+/// }?;
+/// ```
+#[macro_export]
+macro_rules! setting {
+    (
+        id: $id:literal,
+        name: $name:expr,
+        description: $description:expr,
+        $(title_template: $title_template:expr,)?
+        columns: [$($column:expr),* $(,)?],
+        operations: $operations:expr
+        $(, ui_options: $ui_options:expr)?
+        $(, version_column: $version_column:expr)?
+        $(, required_permissions: $required_permissions:expr)?
+        $(,)?
+    ) => {{
+        const _: () = assert!(!$id.is_empty(), "setting id must not be empty");
+
+        $crate::builder::SettingBuilder::new($id, $name, $description)
+            $(.title_template($title_template))?
+            .columns([$($column),*])
+            .operations($operations)
+            $(.ui_options($ui_options))?
+            $(.version_column($version_column))?
+            $(.required_permissions($required_permissions))?
+            .build()
+    }};
+}
@@ -0,0 +1,332 @@
+use crate::cfg::row_matches_state;
+use crate::types::{
+    SettingCreator, SettingDeleter, SettingUpdater, SettingView, VERSION_CONFLICT_ERROR,
+};
+use crate::Error;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// An executor that keeps the entries of a setting in memory
+///
+/// This implements all four operations and is meant for prototyping settings and for tests, entries are
+/// lost once the last clone is dropped. Clones share the same entries.
+///
+/// A panic while the entries are locked does not poison the executor: every operation leaves the entries
+/// valid at all times, so later operations (and `rows`) keep using them:
+///
+/// ```ignore
+/// let executor = InMemorySettings::new(vec!["id".to_string()]);
+///
+/// let setting = SettingBuilder::new("notes", "Notes", "Notes for the server")
+///     .column(id_column)
+///     .operations(SettingOperations::from(executor.clone()))
+///     .build()?;
+/// ```
+#[derive(Clone)]
+pub struct InMemorySettings {
+    /// The ids of the primary key columns, used to find the entry to update or delete
+    primary_key: Arc<Vec<String>>,
+
+    /// The entries
+    rows: Arc<RwLock<Vec<indexmap::IndexMap<String, Value>>>>,
+}
+
+impl InMemorySettings {
+    /// Creates an empty executor, `primary_key` are the ids of the primary key columns of the setting
+    pub fn new(primary_key: Vec<String>) -> Self {
+        Self::with_rows(primary_key, Vec::new())
+    }
+
+    /// Creates an executor seeded with the given entries
+    pub fn with_rows(
+        primary_key: Vec<String>,
+        rows: Vec<indexmap::IndexMap<String, Value>>,
+    ) -> Self {
+        Self {
+            primary_key: Arc::new(primary_key),
+            rows: Arc::new(RwLock::new(rows)),
+        }
+    }
+
+    /// Returns a snapshot of all entries
+    pub fn rows(&self) -> Vec<indexmap::IndexMap<String, Value>> {
+        self.read().clone()
+    }
+
+    /// Locks the entries for reading, recovering them if the lock is poisoned
+    fn read(&self) -> RwLockReadGuard<'_, Vec<indexmap::IndexMap<String, Value>>> {
+        self.rows.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the entries for writing, recovering them if the lock is poisoned
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<indexmap::IndexMap<String, Value>>> {
+        self.rows.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the primary key fields of `state`, erroring if any of them is missing
+    fn primary_key_state(
+        &self,
+        state: &indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let mut pkey_state = indexmap::IndexMap::new();
+
+        for id in self.primary_key.iter() {
+            let Some(value) = state.get(id) else {
+                return Err(format!("Missing primary key field: {}", id).into());
+            };
+
+            pkey_state.insert(id.to_string(), value.clone());
+        }
+
+        Ok(pkey_state)
+    }
+
+    /// Updates the entry matching the primary key of `state`, if `expected_version` is set the entry
+    /// must also have the given version
+    fn update_entry(
+        &self,
+        state: indexmap::IndexMap<String, Value>,
+        expected_version: Option<(&str, i64)>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let pkey_state = self.primary_key_state(&state)?;
+
+        let mut rows = self.write();
+
+        let Some(row) = rows
+            .iter_mut()
+            .find(|row| row_matches_state(row, &pkey_state))
+        else {
+            return Err("No entry found matching the given primary key".into());
+        };
+
+        if let Some((version_column, expected_version)) = expected_version {
+            if row.get(version_column).and_then(|v| v.as_i64()) != Some(expected_version) {
+                return Err(VERSION_CONFLICT_ERROR.into());
+            }
+        }
+
+        // Fields not present in the state (e.g. columns ignored for update) keep their current value
+        for (key, value) in state {
+            row.insert(key, value);
+        }
+
+        Ok(row.clone())
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingView<T> for InMemorySettings {
+    async fn view<'a>(
+        &self,
+        _context: &T,
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+        let rows = self.read();
+
+        Ok(rows
+            .iter()
+            .filter(|row| row_matches_state(row, &filters))
+            .cloned()
+            .collect())
+    }
+
+    async fn count<'a>(
+        &self,
+        _context: &T,
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Option<usize>, Error> {
+        let rows = self.read();
+
+        Ok(Some(
+            rows.iter()
+                .filter(|row| row_matches_state(row, &filters))
+                .count(),
+        ))
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingCreator<T> for InMemorySettings {
+    async fn create<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let pkey_state = self.primary_key_state(&state)?;
+
+        let mut rows = self.write();
+
+        if rows.iter().any(|row| row_matches_state(row, &pkey_state)) {
+            return Err("An entry with the given primary key already exists".into());
+        }
+
+        rows.push(state.clone());
+
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingUpdater<T> for InMemorySettings {
+    async fn update<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        self.update_entry(state, None)
+    }
+
+    async fn update_versioned<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+        version_column: &'a str,
+        expected_version: i64,
+    ) -> Result<Option<indexmap::IndexMap<String, Value>>, Error> {
+        self.update_entry(state, Some((version_column, expected_version)))
+            .map(Some)
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingDeleter<T> for InMemorySettings {
+    async fn delete<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<(), Error> {
+        let pkey_state = self.primary_key_state(&state)?;
+
+        let mut rows = self.write();
+
+        let len = rows.len();
+        rows.retain(|row| !row_matches_state(row, &pkey_state));
+
+        if rows.len() == len {
+            return Err("No entry found matching the given primary key".into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(id: i64, name: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "id".to_string() => json!(id),
+            "name".to_string() => json!(name),
+        }
+    }
+
+    fn executor() -> InMemorySettings {
+        InMemorySettings::with_rows(vec!["id".to_string()], vec![row(1, "a"), row(2, "b")])
+    }
+
+    #[tokio::test]
+    async fn view_applies_filters() {
+        let executor = executor();
+
+        let rows = SettingView::<()>::view(&executor, &(), indexmap::IndexMap::new())
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![row(1, "a"), row(2, "b")]);
+
+        let filters = indexmap::indexmap! { "name".to_string() => json!("b") };
+        let rows = SettingView::<()>::view(&executor, &(), filters.clone())
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![row(2, "b")]);
+
+        let count = SettingView::<()>::count(&executor, &(), filters)
+            .await
+            .unwrap();
+        assert_eq!(count, Some(1));
+    }
+
+    #[tokio::test]
+    async fn create_rejects_duplicate_primary_key() {
+        let executor = executor();
+
+        SettingCreator::<()>::create(&executor, &(), row(3, "c"))
+            .await
+            .unwrap();
+        assert!(SettingCreator::<()>::create(&executor, &(), row(1, "d"))
+            .await
+            .is_err());
+
+        assert_eq!(executor.rows(), vec![row(1, "a"), row(2, "b"), row(3, "c")]);
+    }
+
+    #[tokio::test]
+    async fn update_and_delete_match_primary_key() {
+        let executor = executor();
+
+        let updated = SettingUpdater::<()>::update(&executor, &(), row(2, "c"))
+            .await
+            .unwrap();
+        assert_eq!(updated, row(2, "c"));
+        assert!(SettingUpdater::<()>::update(&executor, &(), row(3, "c"))
+            .await
+            .is_err());
+
+        SettingDeleter::<()>::delete(
+            &executor,
+            &(),
+            indexmap::indexmap! { "id".to_string() => json!(1) },
+        )
+        .await
+        .unwrap();
+        assert!(SettingDeleter::<()>::delete(
+            &executor,
+            &(),
+            indexmap::indexmap! { "id".to_string() => json!(1) }
+        )
+        .await
+        .is_err());
+
+        assert_eq!(executor.rows(), vec![row(2, "c")]);
+    }
+
+    #[tokio::test]
+    async fn missing_primary_key_is_rejected() {
+        let executor = executor();
+
+        let state = indexmap::indexmap! { "name".to_string() => json!("a") };
+        assert!(SettingUpdater::<()>::update(&executor, &(), state.clone())
+            .await
+            .is_err());
+        assert!(SettingDeleter::<()>::delete(&executor, &(), state)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn poisoned_lock_is_recovered() {
+        let executor = executor();
+
+        let poisoner = executor.clone();
+        let _ = std::thread::spawn(move || {
+            let _rows = poisoner.rows.write().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(executor.rows.is_poisoned());
+
+        assert_eq!(executor.rows(), vec![row(1, "a"), row(2, "b")]);
+        SettingCreator::<()>::create(&executor, &(), row(3, "c"))
+            .await
+            .unwrap();
+        assert_eq!(
+            SettingView::<()>::view(&executor, &(), indexmap::IndexMap::new())
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+}
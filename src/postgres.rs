@@ -0,0 +1,948 @@
+use crate::types::{
+    Column, ColumnType, InnerColumnType, SettingCreator, SettingDeleter, SettingUpdater,
+    SettingView, SortBy, VERSION_CONFLICT_ERROR,
+};
+use crate::Error;
+use async_trait::async_trait;
+use serde_json::{Number, Value};
+use sqlx::postgres::PgRow;
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The Postgres type a column is stored as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlType {
+    Text,
+    Uuid,
+    Timestamp,
+    BigInt,
+    Double,
+    Numeric,
+    Boolean,
+    Jsonb,
+}
+
+impl SqlType {
+    /// Returns the Postgres type of an inner column type
+    ///
+    /// - `uuid` kind strings are `uuid` and `timestamp` kind strings are `timestamptz` (RFC 3339 strings)
+    /// - other strings (including Discord IDs and intervals) are `text`
+    /// - integers and bit flags are `bigint`, floats are `double precision` and decimals are `numeric`
+    /// - JSON and object columns are `jsonb`
+    fn of(inner: &InnerColumnType) -> Self {
+        match inner {
+            InnerColumnType::String { kind, .. } => match kind.as_str() {
+                "uuid" => SqlType::Uuid,
+                "timestamp" => SqlType::Timestamp,
+                _ => SqlType::Text,
+            },
+            InnerColumnType::Integer { .. } | InnerColumnType::BitFlag { .. } => SqlType::BigInt,
+            InnerColumnType::Float { .. } => SqlType::Double,
+            InnerColumnType::Decimal { .. } => SqlType::Numeric,
+            InnerColumnType::Boolean { .. } => SqlType::Boolean,
+            InnerColumnType::Json { .. } | InnerColumnType::Object { .. } => SqlType::Jsonb,
+        }
+    }
+}
+
+/// An executor that stores the entries of a setting in a Postgres table, generating parameterized
+/// `SELECT`/`INSERT`/`UPDATE`/`DELETE` queries from the columns of the setting
+///
+/// The table must have a column (of the type given by the mapping below) for every column of the setting,
+/// named after the column id. Array columns are stored as Postgres arrays of the inner type, except for
+/// arrays of JSON/object columns which are stored as a single `jsonb` value:
+/// - `uuid` kind strings are `uuid` and `timestamp` kind strings are `timestamptz` (RFC 3339 strings)
+/// - other strings (including Discord IDs and intervals) are `text`
+/// - integers and bit flags are `bigint`, floats are `double precision` and decimals are `numeric`
+/// - booleans are `boolean`, JSON and object columns are `jsonb`
+///
+/// Columns missing from the state on create (e.g. columns ignored for create) are left to the defaults of
+/// the table, and are returned along with the rest of the entry. Fields of the state that are not columns of
+/// the setting are ignored on create and update
+#[derive(Clone)]
+pub struct PostgresSettings {
+    /// The connection pool
+    pool: sqlx::PgPool,
+
+    /// The (possibly schema-qualified) name of the table
+    table: String,
+
+    /// The columns of the setting
+    columns: Arc<Vec<Column>>,
+}
+
+impl PostgresSettings {
+    /// Creates a new executor for the given table and the columns of the setting
+    pub fn new(pool: sqlx::PgPool, table: impl Into<String>, columns: Arc<Vec<Column>>) -> Self {
+        Self {
+            pool,
+            table: table.into(),
+            columns,
+        }
+    }
+
+    fn column(&self, id: &str) -> Result<&Column, Error> {
+        self.columns
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| format!("Unknown column `{}`", id).into())
+    }
+
+    /// Pushes `SELECT <columns> FROM <table>`
+    fn push_select(&self, qb: &mut QueryBuilder<'_, Postgres>) {
+        qb.push("SELECT ");
+        self.push_column_list(qb);
+        qb.push(" FROM ");
+        qb.push(quote_table(&self.table));
+    }
+
+    /// Pushes the comma separated (quoted) names of all columns
+    fn push_column_list(&self, qb: &mut QueryBuilder<'_, Postgres>) {
+        for (idx, column) in self.columns.iter().enumerate() {
+            if idx > 0 {
+                qb.push(", ");
+            }
+
+            qb.push(quote_ident(&column.id));
+        }
+    }
+
+    /// Pushes a `WHERE` clause matching all fields of `state`, nulls are matched with `IS NULL`
+    fn push_conditions(
+        &self,
+        qb: &mut QueryBuilder<'_, Postgres>,
+        state: &indexmap::IndexMap<String, Value>,
+    ) -> Result<(), Error> {
+        for (idx, (key, value)) in state.iter().enumerate() {
+            let column = self.column(key)?;
+
+            qb.push(if idx == 0 { " WHERE " } else { " AND " });
+            qb.push(quote_ident(&column.id));
+
+            if value.is_null() {
+                qb.push(" IS NULL");
+            } else {
+                qb.push(" = ");
+                push_bind_value(qb, column, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pushes an `ORDER BY` clause, the primary key is always appended so that pages are stable
+    fn push_order_by(
+        &self,
+        qb: &mut QueryBuilder<'_, Postgres>,
+        sort: Option<&SortBy>,
+    ) -> Result<(), Error> {
+        let mut order = Vec::new();
+
+        if let Some(sort) = sort {
+            let column = self.column(&sort.column)?;
+            order.push(format!(
+                "{} {}",
+                quote_ident(&column.id),
+                if sort.descending { "DESC" } else { "ASC" }
+            ));
+        }
+
+        for column in self.columns.iter().filter(|c| c.primary_key) {
+            order.push(quote_ident(&column.id));
+        }
+
+        if !order.is_empty() {
+            qb.push(" ORDER BY ");
+            qb.push(order.join(", "));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the primary key fields of `state`, erroring if any of them is missing
+    fn primary_key_state(
+        &self,
+        state: &indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let mut pkey_state = indexmap::IndexMap::new();
+
+        for column in self.columns.iter().filter(|c| c.primary_key) {
+            let Some(value) = state.get(&column.id) else {
+                return Err(format!("Missing primary key field: {}", column.id).into());
+            };
+
+            pkey_state.insert(column.id.to_string(), value.clone());
+        }
+
+        Ok(pkey_state)
+    }
+
+    /// Returns the fields of `state` along with their columns, skipping fields that are not columns of the
+    /// setting
+    fn known_fields<'s>(
+        &'s self,
+        state: &'s indexmap::IndexMap<String, Value>,
+    ) -> Vec<(&'s Column, &'s Value)> {
+        state
+            .iter()
+            .filter_map(|(key, value)| Some((self.columns.iter().find(|c| c.id == *key)?, value)))
+            .collect()
+    }
+
+    /// Builds the `INSERT` query of `create`
+    fn insert_query(
+        &self,
+        state: &indexmap::IndexMap<String, Value>,
+    ) -> Result<QueryBuilder<'static, Postgres>, Error> {
+        let fields = self.known_fields(state);
+
+        let mut qb = QueryBuilder::new("INSERT INTO ");
+        qb.push(quote_table(&self.table));
+
+        if fields.is_empty() {
+            qb.push(" DEFAULT VALUES");
+        } else {
+            qb.push(" (");
+            for (idx, (column, _)) in fields.iter().enumerate() {
+                if idx > 0 {
+                    qb.push(", ");
+                }
+
+                qb.push(quote_ident(&column.id));
+            }
+
+            qb.push(") VALUES (");
+            for (idx, (column, value)) in fields.iter().enumerate() {
+                if idx > 0 {
+                    qb.push(", ");
+                }
+
+                push_bind_value(&mut qb, column, value)?;
+            }
+            qb.push(")");
+        }
+
+        qb.push(" RETURNING ");
+        self.push_column_list(&mut qb);
+
+        Ok(qb)
+    }
+
+    /// Builds the `UPDATE` query of `update_entry`
+    fn update_query(
+        &self,
+        state: &indexmap::IndexMap<String, Value>,
+        expected_version: Option<(&str, i64)>,
+    ) -> Result<QueryBuilder<'static, Postgres>, Error> {
+        let pkey_state = self.primary_key_state(state)?;
+
+        let mut qb = QueryBuilder::new("UPDATE ");
+        qb.push(quote_table(&self.table));
+        qb.push(" SET ");
+
+        let mut first = true;
+        for (column, value) in self.known_fields(state) {
+            if column.primary_key {
+                continue;
+            }
+
+            if !first {
+                qb.push(", ");
+            }
+            first = false;
+
+            qb.push(quote_ident(&column.id));
+            qb.push(" = ");
+            push_bind_value(&mut qb, column, value)?;
+        }
+
+        if first {
+            return Err("Nothing to update".into());
+        }
+
+        // Versioned updates only match the entry if it still has the expected version
+        let mut conditions = pkey_state;
+        if let Some((version_column, expected_version)) = expected_version {
+            conditions.insert(version_column.to_string(), Value::from(expected_version));
+        }
+
+        self.push_conditions(&mut qb, &conditions)?;
+        qb.push(" RETURNING ");
+        self.push_column_list(&mut qb);
+
+        Ok(qb)
+    }
+
+    /// Updates the entry matching the primary key of `state`, if `expected_version` is set the entry
+    /// must also have the given version
+    async fn update_entry(
+        &self,
+        state: indexmap::IndexMap<String, Value>,
+        expected_version: Option<(&str, i64)>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let mut qb = self.update_query(&state, expected_version)?;
+
+        let Some(row) = qb.build().fetch_optional(&self.pool).await? else {
+            if expected_version.is_some() {
+                return Err(VERSION_CONFLICT_ERROR.into());
+            }
+
+            return Err("No entry found matching the given primary key".into());
+        };
+
+        self.row_to_state(&row)
+    }
+
+    /// Converts a row into an entry
+    fn row_to_state(&self, row: &PgRow) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let mut state = indexmap::IndexMap::new();
+
+        for column in self.columns.iter() {
+            state.insert(column.id.to_string(), decode_value(row, column)?);
+        }
+
+        Ok(state)
+    }
+
+    async fn fetch_page(
+        &self,
+        filters: &indexmap::IndexMap<String, Value>,
+        sort: Option<&SortBy>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<indexmap::IndexMap<String, Value>>, usize), Error> {
+        let mut qb = QueryBuilder::new("");
+        self.push_select(&mut qb);
+        self.push_conditions(&mut qb, filters)?;
+        self.push_order_by(&mut qb, sort)?;
+        qb.push(" LIMIT ");
+        qb.push_bind(i64::try_from(limit)?);
+        qb.push(" OFFSET ");
+        qb.push_bind(i64::try_from(offset)?);
+
+        let rows = qb.build().fetch_all(&self.pool).await?;
+
+        let mut states = Vec::new();
+        for row in rows.iter() {
+            states.push(self.row_to_state(row)?);
+        }
+
+        Ok((states, self.count_matching(filters).await?))
+    }
+
+    async fn count_matching(
+        &self,
+        filters: &indexmap::IndexMap<String, Value>,
+    ) -> Result<usize, Error> {
+        let mut qb = QueryBuilder::new("SELECT COUNT(*) FROM ");
+        qb.push(quote_table(&self.table));
+        self.push_conditions(&mut qb, filters)?;
+
+        let count: i64 = qb.build_query_scalar().fetch_one(&self.pool).await?;
+
+        Ok(usize::try_from(count)?)
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingView<T> for PostgresSettings {
+    async fn view<'a>(
+        &self,
+        _context: &T,
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+        let mut qb = QueryBuilder::new("");
+        self.push_select(&mut qb);
+        self.push_conditions(&mut qb, &filters)?;
+        self.push_order_by(&mut qb, None)?;
+
+        let rows = qb.build().fetch_all(&self.pool).await?;
+
+        let mut states = Vec::new();
+        for row in rows.iter() {
+            states.push(self.row_to_state(row)?);
+        }
+
+        Ok(states)
+    }
+
+    async fn view_page<'a>(
+        &self,
+        _context: &T,
+        filters: indexmap::IndexMap<String, Value>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+        Ok(Some(self.fetch_page(&filters, None, limit, offset).await?))
+    }
+
+    async fn view_sorted_page<'a>(
+        &self,
+        _context: &T,
+        filters: indexmap::IndexMap<String, Value>,
+        sort: &SortBy,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+        Ok(Some(
+            self.fetch_page(&filters, Some(sort), limit, offset).await?,
+        ))
+    }
+
+    async fn count<'a>(
+        &self,
+        _context: &T,
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Option<usize>, Error> {
+        Ok(Some(self.count_matching(&filters).await?))
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingCreator<T> for PostgresSettings {
+    async fn create<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        let mut qb = self.insert_query(&state)?;
+        let row = qb.build().fetch_one(&self.pool).await?;
+
+        self.row_to_state(&row)
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingUpdater<T> for PostgresSettings {
+    async fn update<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        self.update_entry(state, None).await
+    }
+
+    async fn update_versioned<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+        version_column: &'a str,
+        expected_version: i64,
+    ) -> Result<Option<indexmap::IndexMap<String, Value>>, Error> {
+        self.update_entry(state, Some((version_column, expected_version)))
+            .await
+            .map(Some)
+    }
+}
+
+#[async_trait]
+impl<T: Clone> SettingDeleter<T> for PostgresSettings {
+    async fn delete<'a>(
+        &self,
+        _context: &T,
+        state: indexmap::IndexMap<String, Value>,
+    ) -> Result<(), Error> {
+        let pkey_state = self.primary_key_state(&state)?;
+
+        if pkey_state.is_empty() {
+            return Err("Cannot delete from a setting without a primary key".into());
+        }
+
+        let mut qb = QueryBuilder::new("DELETE FROM ");
+        qb.push(quote_table(&self.table));
+        self.push_conditions(&mut qb, &pkey_state)?;
+
+        let result = qb.build().execute(&self.pool).await?;
+
+        if result.rows_affected() == 0 {
+            return Err("No entry found matching the given primary key".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes an identifier, escaping any double quotes in it
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quotes a (possibly schema-qualified) table name
+fn quote_table(table: &str) -> String {
+    table
+        .split('.')
+        .map(quote_ident)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Binds a value as the Postgres type of the column, nulls are bound as typed nulls
+fn push_bind_value(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    column: &Column,
+    value: &Value,
+) -> Result<(), Error> {
+    match column.column_type {
+        ColumnType::Scalar { ref inner } => match SqlType::of(inner) {
+            SqlType::Text => {
+                qb.push_bind(nullable(value, |v| to_text(column, v))?);
+            }
+            SqlType::Uuid => {
+                qb.push_bind(nullable(value, |v| to_uuid(column, v))?);
+            }
+            SqlType::Timestamp => {
+                qb.push_bind(nullable(value, |v| to_timestamp(column, v))?);
+            }
+            SqlType::BigInt => {
+                qb.push_bind(nullable(value, |v| to_bigint(column, v))?);
+            }
+            SqlType::Double => {
+                qb.push_bind(nullable(value, |v| to_double(column, v))?);
+            }
+            SqlType::Numeric => {
+                qb.push_bind(nullable(value, |v| to_numeric(column, v))?);
+            }
+            SqlType::Boolean => {
+                qb.push_bind(nullable(value, |v| to_boolean(column, v))?);
+            }
+            SqlType::Jsonb => {
+                qb.push_bind(nullable(value, |v| Ok(sqlx::types::Json(v.clone())))?);
+            }
+        },
+        ColumnType::Array { ref inner, .. } => match SqlType::of(inner) {
+            SqlType::Text => {
+                qb.push_bind(nullable_array(value, |v| to_text(column, v))?);
+            }
+            SqlType::Uuid => {
+                qb.push_bind(nullable_array(value, |v| to_uuid(column, v))?);
+            }
+            SqlType::Timestamp => {
+                qb.push_bind(nullable_array(value, |v| to_timestamp(column, v))?);
+            }
+            SqlType::BigInt => {
+                qb.push_bind(nullable_array(value, |v| to_bigint(column, v))?);
+            }
+            SqlType::Double => {
+                qb.push_bind(nullable_array(value, |v| to_double(column, v))?);
+            }
+            SqlType::Numeric => {
+                qb.push_bind(nullable_array(value, |v| to_numeric(column, v))?);
+            }
+            SqlType::Boolean => {
+                qb.push_bind(nullable_array(value, |v| to_boolean(column, v))?);
+            }
+            // Arrays of JSON values are stored as a single jsonb value
+            SqlType::Jsonb => {
+                qb.push_bind(nullable(value, |v| Ok(sqlx::types::Json(v.clone())))?);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Converts a value using `f`, nulls are converted to None
+fn nullable<T>(value: &Value, f: impl Fn(&Value) -> Result<T, Error>) -> Result<Option<T>, Error> {
+    match value {
+        Value::Null => Ok(None),
+        _ => f(value).map(Some),
+    }
+}
+
+/// Converts each element of an array value using `f`, nulls are converted to None
+fn nullable_array<T>(
+    value: &Value,
+    f: impl Fn(&Value) -> Result<T, Error>,
+) -> Result<Option<Vec<T>>, Error> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Array(values) => values
+            .iter()
+            .map(f)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        _ => Err(format!("Expected an array but got {:?}", value).into()),
+    }
+}
+
+fn bind_error(column: &Column, expected: &str, value: &Value) -> Error {
+    format!(
+        "Cannot bind {:?} to column `{}`, expected {}",
+        value, column.id, expected
+    )
+    .into()
+}
+
+fn to_text(column: &Column, value: &Value) -> Result<String, Error> {
+    match value {
+        Value::String(s) => Ok(s.to_string()),
+        _ => Err(bind_error(column, "a string", value)),
+    }
+}
+
+fn to_uuid(column: &Column, value: &Value) -> Result<uuid::Uuid, Error> {
+    match value {
+        Value::String(s) => Ok(uuid::Uuid::parse_str(s)?),
+        _ => Err(bind_error(column, "a UUID string", value)),
+    }
+}
+
+fn to_timestamp(column: &Column, value: &Value) -> Result<chrono::DateTime<chrono::Utc>, Error> {
+    match value {
+        Value::String(s) => {
+            Ok(chrono::DateTime::parse_from_rfc3339(s)?.with_timezone(&chrono::Utc))
+        }
+        _ => Err(bind_error(column, "an RFC 3339 timestamp string", value)),
+    }
+}
+
+fn to_bigint(column: &Column, value: &Value) -> Result<i64, Error> {
+    value
+        .as_i64()
+        .ok_or_else(|| bind_error(column, "an integer", value))
+}
+
+fn to_double(column: &Column, value: &Value) -> Result<f64, Error> {
+    value
+        .as_f64()
+        .ok_or_else(|| bind_error(column, "a number", value))
+}
+
+fn to_numeric(column: &Column, value: &Value) -> Result<rust_decimal::Decimal, Error> {
+    match value {
+        Value::String(s) => Ok(rust_decimal::Decimal::from_str(s)?),
+        Value::Number(n) => Ok(rust_decimal::Decimal::from_str(&n.to_string())?),
+        _ => Err(bind_error(column, "a decimal string", value)),
+    }
+}
+
+fn to_boolean(column: &Column, value: &Value) -> Result<bool, Error> {
+    value
+        .as_bool()
+        .ok_or_else(|| bind_error(column, "a boolean", value))
+}
+
+/// Converts a decoded double to a JSON number, erroring on NaN and infinities as JSON cannot represent them
+fn from_double(column: &Column, value: f64) -> Result<Value, Error> {
+    Number::from_f64(value).map(Value::Number).ok_or_else(|| {
+        format!(
+            "Cannot decode {} from column `{}`, expected a finite number",
+            value, column.id
+        )
+        .into()
+    })
+}
+
+/// Decodes the value of a column from a row, SQL nulls become JSON nulls
+fn decode_value(row: &PgRow, column: &Column) -> Result<Value, Error> {
+    let id = column.id.as_str();
+
+    let value = match column.column_type {
+        ColumnType::Scalar { ref inner } => match SqlType::of(inner) {
+            SqlType::Text => row.try_get::<Option<String>, _>(id)?.map(Value::String),
+            SqlType::Uuid => row
+                .try_get::<Option<uuid::Uuid>, _>(id)?
+                .map(|v| Value::String(v.to_string())),
+            SqlType::Timestamp => row
+                .try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(id)?
+                .map(|v| Value::String(v.to_rfc3339())),
+            SqlType::BigInt => row.try_get::<Option<i64>, _>(id)?.map(Value::from),
+            SqlType::Double => row
+                .try_get::<Option<f64>, _>(id)?
+                .map(|v| from_double(column, v))
+                .transpose()?,
+            SqlType::Numeric => row
+                .try_get::<Option<rust_decimal::Decimal>, _>(id)?
+                .map(|v| Value::String(v.normalize().to_string())),
+            SqlType::Boolean => row.try_get::<Option<bool>, _>(id)?.map(Value::Bool),
+            SqlType::Jsonb => row
+                .try_get::<Option<sqlx::types::Json<Value>>, _>(id)?
+                .map(|v| v.0),
+        },
+        ColumnType::Array { ref inner, .. } => match SqlType::of(inner) {
+            SqlType::Text => row
+                .try_get::<Option<Vec<String>>, _>(id)?
+                .map(|v| v.into_iter().map(Value::String).collect()),
+            SqlType::Uuid => row.try_get::<Option<Vec<uuid::Uuid>>, _>(id)?.map(|v| {
+                v.into_iter()
+                    .map(|v| Value::String(v.to_string()))
+                    .collect()
+            }),
+            SqlType::Timestamp => row
+                .try_get::<Option<Vec<chrono::DateTime<chrono::Utc>>>, _>(id)?
+                .map(|v| {
+                    v.into_iter()
+                        .map(|v| Value::String(v.to_rfc3339()))
+                        .collect()
+                }),
+            SqlType::BigInt => row
+                .try_get::<Option<Vec<i64>>, _>(id)?
+                .map(|v| v.into_iter().map(Value::from).collect()),
+            SqlType::Double => row
+                .try_get::<Option<Vec<f64>>, _>(id)?
+                .map(|v| {
+                    v.into_iter()
+                        .map(|v| from_double(column, v))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?
+                .map(Value::Array),
+            SqlType::Numeric => row
+                .try_get::<Option<Vec<rust_decimal::Decimal>>, _>(id)?
+                .map(|v| {
+                    v.into_iter()
+                        .map(|v| Value::String(v.normalize().to_string()))
+                        .collect()
+                }),
+            SqlType::Boolean => row
+                .try_get::<Option<Vec<bool>>, _>(id)?
+                .map(|v| v.into_iter().map(Value::Bool).collect()),
+            SqlType::Jsonb => row
+                .try_get::<Option<sqlx::types::Json<Value>>, _>(id)?
+                .map(|v| v.0),
+        },
+    };
+
+    Ok(value.unwrap_or(Value::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ColumnBuilder;
+    use crate::common_columns;
+    use serde_json::json;
+
+    fn string_column(id: &str) -> Column {
+        ColumnBuilder::new(
+            id,
+            id,
+            ColumnType::new_scalar(InnerColumnType::String {
+                min_length: None,
+                max_length: None,
+                allowed_values: vec![],
+                kind: "normal".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            }),
+        )
+        .build()
+    }
+
+    fn float_array_column(id: &str) -> Column {
+        ColumnBuilder::new(
+            id,
+            id,
+            ColumnType::new_array(InnerColumnType::Float {
+                min: None,
+                max: None,
+                allowed_values: vec![],
+            }),
+        )
+        .build()
+    }
+
+    /// An executor for `public.test` with an `id` primary key, a `name` and a `version`, the pool never
+    /// connects
+    fn executor() -> PostgresSettings {
+        let mut id = common_columns::integer("id", "ID", "The ID");
+        id.primary_key = true;
+
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+
+        PostgresSettings::new(
+            pool,
+            "public.test",
+            Arc::new(vec![
+                id,
+                string_column("name"),
+                common_columns::integer("version", "Version", "The version"),
+            ]),
+        )
+    }
+
+    #[test]
+    fn quote_ident_escapes_quotes() {
+        assert_eq!(quote_ident("name"), "\"name\"");
+        assert_eq!(quote_ident("we\"ird"), "\"we\"\"ird\"");
+        assert_eq!(quote_ident("\""), "\"\"\"\"");
+    }
+
+    #[test]
+    fn quote_table_quotes_each_part() {
+        assert_eq!(quote_table("settings"), "\"settings\"");
+        assert_eq!(quote_table("public.settings"), "\"public\".\"settings\"");
+        assert_eq!(
+            quote_table("my\"schema.my\"table"),
+            "\"my\"\"schema\".\"my\"\"table\""
+        );
+    }
+
+    #[tokio::test]
+    async fn insert_query_skips_unknown_fields() {
+        let executor = executor();
+
+        let state = indexmap::indexmap! {
+            "name".to_string() => json!("a"),
+            "computed".to_string() => json!(1),
+        };
+        let qb = executor.insert_query(&state).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO \"public\".\"test\" (\"name\") VALUES ($1) RETURNING \"id\", \"name\", \"version\""
+        );
+
+        let state = indexmap::indexmap! { "computed".to_string() => json!(1) };
+        let qb = executor.insert_query(&state).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO \"public\".\"test\" DEFAULT VALUES RETURNING \"id\", \"name\", \"version\""
+        );
+    }
+
+    #[tokio::test]
+    async fn update_query_skips_unknown_fields() {
+        let executor = executor();
+
+        let state = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("a"),
+            "computed".to_string() => json!(1),
+        };
+        let qb = executor.update_query(&state, None).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE \"public\".\"test\" SET \"name\" = $1 WHERE \"id\" = $2 RETURNING \"id\", \"name\", \"version\""
+        );
+
+        let state = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "computed".to_string() => json!(1),
+        };
+        let err = executor.update_query(&state, None).err().unwrap();
+        assert_eq!(err.to_string(), "Nothing to update");
+    }
+
+    #[tokio::test]
+    async fn versioned_update_query_matches_version() {
+        let executor = executor();
+
+        let state = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "name".to_string() => json!("a"),
+            "version".to_string() => json!(4),
+        };
+        let qb = executor.update_query(&state, Some(("version", 3))).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE \"public\".\"test\" SET \"name\" = $1, \"version\" = $2 WHERE \"id\" = $3 AND \"version\" = $4 RETURNING \"id\", \"name\", \"version\""
+        );
+
+        // The primary key is required
+        let state = indexmap::indexmap! { "name".to_string() => json!("a") };
+        assert!(executor.update_query(&state, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn conditions_and_order_are_quoted() {
+        let executor = executor();
+
+        let filters = indexmap::indexmap! {
+            "name".to_string() => Value::Null,
+            "version".to_string() => json!(2),
+        };
+        let sort = SortBy {
+            column: "name".to_string(),
+            descending: true,
+        };
+
+        let mut qb = QueryBuilder::new("");
+        executor.push_select(&mut qb);
+        executor.push_conditions(&mut qb, &filters).unwrap();
+        executor.push_order_by(&mut qb, Some(&sort)).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT \"id\", \"name\", \"version\" FROM \"public\".\"test\" WHERE \"name\" IS NULL AND \"version\" = $1 ORDER BY \"name\" DESC, \"id\""
+        );
+
+        // Filters and sorts must still be columns
+        let filters = indexmap::indexmap! { "computed".to_string() => json!(1) };
+        let mut qb = QueryBuilder::new("");
+        assert!(executor.push_conditions(&mut qb, &filters).is_err());
+    }
+
+    #[test]
+    fn values_are_encoded_as_the_column_type() {
+        let column = string_column("name");
+
+        assert_eq!(to_text(&column, &json!("a")).unwrap(), "a");
+        assert!(to_text(&column, &json!(1)).is_err());
+
+        assert_eq!(to_bigint(&column, &json!(12)).unwrap(), 12);
+        assert!(to_bigint(&column, &json!(1.5)).is_err());
+        assert_eq!(to_double(&column, &json!(1.5)).unwrap(), 1.5);
+        assert!(to_double(&column, &json!("1.5")).is_err());
+        assert!(to_boolean(&column, &json!(true)).unwrap());
+        assert!(to_boolean(&column, &json!("true")).is_err());
+
+        assert_eq!(
+            to_numeric(&column, &json!("1.50")).unwrap(),
+            rust_decimal::Decimal::new(150, 2)
+        );
+        assert_eq!(
+            to_numeric(&column, &json!(2.5)).unwrap(),
+            rust_decimal::Decimal::new(25, 1)
+        );
+        assert!(to_numeric(&column, &json!("abc")).is_err());
+
+        assert_eq!(
+            to_timestamp(&column, &json!("2024-01-02T03:04:05+01:00"))
+                .unwrap()
+                .to_rfc3339(),
+            "2024-01-02T02:04:05+00:00"
+        );
+        assert!(to_timestamp(&column, &json!("yesterday")).is_err());
+
+        let uuid = uuid::Uuid::new_v4();
+        assert_eq!(to_uuid(&column, &json!(uuid.to_string())).unwrap(), uuid);
+        assert!(to_uuid(&column, &json!("not-a-uuid")).is_err());
+    }
+
+    #[test]
+    fn nulls_and_arrays_are_encoded() {
+        let column = float_array_column("weights");
+
+        assert_eq!(
+            nullable(&Value::Null, |v| to_double(&column, v)).unwrap(),
+            None
+        );
+        assert_eq!(
+            nullable_array(&json!([1.0, 2.5]), |v| to_double(&column, v)).unwrap(),
+            Some(vec![1.0, 2.5])
+        );
+        assert_eq!(
+            nullable_array(&Value::Null, |v| to_double(&column, v)).unwrap(),
+            None
+        );
+        assert!(nullable_array(&json!(1.0), |v| to_double(&column, v)).is_err());
+        assert!(nullable_array(&json!([1.0, "a"]), |v| to_double(&column, v)).is_err());
+
+        // Binding checks the value against the column type before the query is sent
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        assert!(push_bind_value(&mut qb, &column, &json!([1.0, 2.0])).is_ok());
+        assert!(push_bind_value(&mut qb, &column, &json!(["a"])).is_err());
+    }
+
+    #[test]
+    fn non_finite_doubles_are_not_decoded() {
+        let column = float_array_column("weights");
+
+        assert_eq!(from_double(&column, 1.5).unwrap(), json!(1.5));
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(from_double(&column, value).is_err());
+        }
+    }
+}
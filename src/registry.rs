@@ -0,0 +1,96 @@
+use crate::cfg::{settings_create, settings_delete, settings_update, settings_view};
+use crate::types::{Setting, SortBy};
+use crate::Error;
+use serde_json::Value;
+
+/// A collection of settings keyed by id, giving non-Discord front-ends (e.g. HTTP handlers or CLIs) a
+/// single entry point to invoke operations on a setting by its id
+pub struct SettingsRegistry<SettingsData: Clone> {
+    settings: indexmap::IndexMap<String, Setting<SettingsData>>,
+}
+
+impl<SettingsData: Clone> Default for SettingsRegistry<SettingsData> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<SettingsData: Clone> SettingsRegistry<SettingsData> {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self {
+            settings: indexmap::IndexMap::new(),
+        }
+    }
+
+    /// Registers a setting, erroring if a setting with the same id is already registered
+    pub fn register(&mut self, setting: Setting<SettingsData>) -> Result<(), Error> {
+        if self.settings.contains_key(&setting.id) {
+            return Err(format!("Setting `{}` is already registered", setting.id).into());
+        }
+
+        self.settings.insert(setting.id.to_string(), setting);
+
+        Ok(())
+    }
+
+    /// Returns the setting with the given id, if registered
+    pub fn get(&self, id: &str) -> Option<&Setting<SettingsData>> {
+        self.settings.get(id)
+    }
+
+    /// Returns the registered settings in registration order
+    pub fn settings(&self) -> impl Iterator<Item = &Setting<SettingsData>> {
+        self.settings.values()
+    }
+
+    fn setting(&self, id: &str) -> Result<&Setting<SettingsData>, Error> {
+        self.get(id)
+            .ok_or_else(|| format!("No such setting: {}", id).into())
+    }
+
+    /// Views the entries of the setting with the given id (see `settings_view`)
+    pub async fn view(
+        &self,
+        id: &str,
+        data: &SettingsData,
+        filters: indexmap::IndexMap<String, Value>,
+        sort: Option<&SortBy>,
+        actor: Option<&str>,
+    ) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error> {
+        settings_view(self.setting(id)?, data, filters, sort, actor).await
+    }
+
+    /// Creates an entry of the setting with the given id (see `settings_create`)
+    pub async fn create(
+        &self,
+        id: &str,
+        data: &SettingsData,
+        fields: indexmap::IndexMap<String, Value>,
+        actor: Option<&str>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        settings_create(self.setting(id)?, data, fields, actor).await
+    }
+
+    /// Updates an entry of the setting with the given id (see `settings_update`)
+    pub async fn update(
+        &self,
+        id: &str,
+        data: &SettingsData,
+        fields: indexmap::IndexMap<String, Value>,
+        actor: Option<&str>,
+    ) -> Result<indexmap::IndexMap<String, Value>, Error> {
+        settings_update(self.setting(id)?, data, fields, actor).await
+    }
+
+    /// Deletes an entry of the setting with the given id (see `settings_delete`)
+    pub async fn delete(
+        &self,
+        id: &str,
+        data: &SettingsData,
+        fields: indexmap::IndexMap<String, Value>,
+        actor: Option<&str>,
+    ) -> Result<(), Error> {
+        settings_delete(self.setting(id)?, data, fields, actor).await
+    }
+}
@@ -0,0 +1,406 @@
+use crate::cfg::SNOWFLAKE_KINDS;
+use crate::types::{Column, ColumnType, InnerColumnType, OperationType, Setting};
+use serde_json::{json, Map, Value};
+
+/// Describes the operations of a setting and what each of them requires, for serving to frontends as JSON
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SettingDescriptor {
+    /// The ID of the setting
+    pub id: String,
+
+    /// The name of the setting
+    pub name: String,
+
+    /// The description of the setting
+    pub description: String,
+
+    /// The ids of the primary key columns, these identify the entry to update or delete
+    pub primary_key: Vec<String>,
+
+    /// The ids of the alternate key columns, these may be given instead of the primary key when deleting
+    pub alternate_key: Vec<String>,
+
+    /// The supported operations
+    pub operations: Vec<OperationDescriptor>,
+}
+
+/// Describes a single operation of a setting
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OperationDescriptor {
+    /// The operation
+    pub operation: OperationType,
+
+    /// The ids of the columns that must be provided
+    pub required_columns: Vec<String>,
+
+    /// The ids of the columns that may optionally be provided
+    pub optional_columns: Vec<String>,
+
+    /// If set, the operation is deprecated. This is the message pointing users to the replacement
+    pub deprecated: Option<String>,
+}
+
+/// Generates a descriptor of the supported operations of the setting
+///
+/// Required and optional columns follow the same rules as the generated commands, so frontends
+/// do not need to reimplement them
+pub fn setting_descriptor<T: Clone>(setting: &Setting<T>) -> SettingDescriptor {
+    let operations = setting
+        .operations
+        .supported_operations()
+        .into_iter()
+        .map(|operation| OperationDescriptor {
+            operation,
+            required_columns: setting.required_fields(operation),
+            optional_columns: setting.optional_fields(operation),
+            deprecated: setting.deprecated_operations.get(&operation).cloned(),
+        })
+        .collect();
+
+    SettingDescriptor {
+        id: setting.id.to_string(),
+        name: setting.name.to_string(),
+        description: setting.description.to_string(),
+        primary_key: setting
+            .columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| c.id.to_string())
+            .collect(),
+        alternate_key: setting
+            .columns
+            .iter()
+            .filter(|c| c.alternate_key)
+            .map(|c| c.id.to_string())
+            .collect(),
+        operations,
+    }
+}
+
+/// Generates a JSON Schema (draft 2020-12) document describing an entry of the setting
+///
+/// This gives non-Discord frontends (e.g. web dashboards) a contract to render forms from. Keywords
+/// that JSON Schema has no equivalent for are emitted as `x-` extensions:
+/// - `x-operations`: the operations a column is accepted by (on the setting, the supported operations)
+/// - `x-required-for`: the operations a column must be provided for
+/// - `x-kind`: the kind of String and JSON columns (e.g. `channel`, `interval`)
+/// - `x-flags`: the flag names and bits of BitFlag columns, whose values are a combination of the bits
+/// - `x-max-bytes`: the maximum size of JSON columns once serialized
+/// - `x-minimum`/`x-maximum`/`x-max-scale`: the range and maximum decimal places of Decimal columns, whose
+///   values are strings
+/// - `x-group`: the group a column is shown under
+/// - `x-primary-key`: the ids of the primary key columns
+///
+/// The top-level `required` lists the columns required to create an entry
+pub fn setting_to_json_schema<T: Clone>(setting: &Setting<T>) -> Value {
+    let supported_operations = setting.operations.supported_operations();
+
+    let mut properties = Map::new();
+
+    for column in setting.columns.iter() {
+        let mut schema = column_schema(column);
+
+        let operations = supported_operations
+            .iter()
+            .filter(|op| {
+                setting.required_fields(**op).contains(&column.id)
+                    || setting.optional_fields(**op).contains(&column.id)
+            })
+            .collect::<Vec<_>>();
+
+        let required_for = supported_operations
+            .iter()
+            .filter(|op| setting.required_fields(**op).contains(&column.id))
+            .collect::<Vec<_>>();
+
+        schema.insert("x-operations".to_string(), json!(operations));
+        schema.insert("x-required-for".to_string(), json!(required_for));
+
+        properties.insert(column.id.to_string(), Value::Object(schema));
+    }
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": setting.name,
+        "description": setting.description,
+        "type": "object",
+        "properties": properties,
+        "required": setting.required_fields(OperationType::Create),
+        "additionalProperties": false,
+        "x-operations": supported_operations,
+        "x-primary-key": setting
+            .columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| c.id.to_string())
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Returns the schema of a single column, including its name, description and nullability
+fn column_schema(column: &Column) -> Map<String, Value> {
+    let mut schema = match column.column_type {
+        ColumnType::Scalar { ref inner } => inner_schema(inner),
+        ColumnType::Array {
+            ref inner,
+            min_items,
+            max_items,
+            unique,
+        } => {
+            let mut schema = Map::new();
+            schema.insert("type".to_string(), json!("array"));
+            schema.insert("items".to_string(), Value::Object(inner_schema(inner)));
+
+            if let Some(min_items) = min_items {
+                schema.insert("minItems".to_string(), json!(min_items));
+            }
+
+            if let Some(max_items) = max_items {
+                schema.insert("maxItems".to_string(), json!(max_items));
+            }
+
+            if unique {
+                schema.insert("uniqueItems".to_string(), json!(true));
+            }
+
+            schema
+        }
+    };
+
+    if column.nullable {
+        make_nullable(&mut schema);
+    }
+
+    schema.insert("title".to_string(), json!(column.name));
+    schema.insert("description".to_string(), json!(column.description));
+
+    if let Some(ref default) = column.default {
+        schema.insert("default".to_string(), default.clone());
+    }
+
+    if column.deprecated.is_some() {
+        schema.insert("deprecated".to_string(), json!(true));
+    }
+
+    if let Some(ref group) = column.group {
+        schema.insert("x-group".to_string(), json!(group));
+    }
+
+    if column.ignored_for.contains(&OperationType::Create)
+        && column.ignored_for.contains(&OperationType::Update)
+    {
+        schema.insert("readOnly".to_string(), json!(true));
+    }
+
+    if column.secret {
+        schema.insert("writeOnly".to_string(), json!(true));
+    }
+
+    schema
+}
+
+/// Returns the schema of an inner column type (a scalar or the items of an array)
+fn inner_schema(inner: &InnerColumnType) -> Map<String, Value> {
+    let mut schema = Map::new();
+
+    match inner {
+        InnerColumnType::String {
+            min_length,
+            max_length,
+            allowed_values,
+            kind,
+            unchecked_ids,
+            ..
+        } => {
+            schema.insert("type".to_string(), json!("string"));
+
+            if let Some(min_length) = min_length {
+                schema.insert("minLength".to_string(), json!(min_length));
+            }
+
+            if let Some(max_length) = max_length {
+                schema.insert("maxLength".to_string(), json!(max_length));
+            }
+
+            if !allowed_values.is_empty() {
+                schema.insert("enum".to_string(), json!(allowed_values));
+            }
+
+            match kind.as_str() {
+                "url" => {
+                    schema.insert("format".to_string(), json!("uri"));
+                }
+                "uuid" => {
+                    schema.insert("format".to_string(), json!("uuid"));
+                }
+                _ if !unchecked_ids && SNOWFLAKE_KINDS.contains(&kind.as_str()) => {
+                    schema.insert("pattern".to_string(), json!("^[0-9]+$"));
+                }
+                _ => {}
+            }
+
+            if !kind.is_empty() && kind != "normal" {
+                schema.insert("x-kind".to_string(), json!(kind));
+            }
+        }
+        InnerColumnType::Integer {
+            min,
+            max,
+            allowed_values,
+        } => {
+            schema.insert("type".to_string(), json!("integer"));
+
+            if let Some(min) = min {
+                schema.insert("minimum".to_string(), json!(min));
+            }
+
+            if let Some(max) = max {
+                schema.insert("maximum".to_string(), json!(max));
+            }
+
+            if !allowed_values.is_empty() {
+                schema.insert("enum".to_string(), json!(allowed_values));
+            }
+        }
+        InnerColumnType::Float {
+            min,
+            max,
+            allowed_values,
+        } => {
+            schema.insert("type".to_string(), json!("number"));
+
+            if let Some(min) = min {
+                schema.insert("minimum".to_string(), json!(min));
+            }
+
+            if let Some(max) = max {
+                schema.insert("maximum".to_string(), json!(max));
+            }
+
+            if !allowed_values.is_empty() {
+                schema.insert("enum".to_string(), json!(allowed_values));
+            }
+        }
+        InnerColumnType::Decimal {
+            min,
+            max,
+            max_scale,
+        } => {
+            // Decimals are strings so that they round-trip without losing precision
+            schema.insert("type".to_string(), json!("string"));
+            schema.insert("pattern".to_string(), json!("^-?[0-9]+(\\.[0-9]+)?$"));
+            schema.insert("format".to_string(), json!("decimal"));
+
+            if let Some(min) = min {
+                schema.insert("x-minimum".to_string(), json!(min.to_string()));
+            }
+
+            if let Some(max) = max {
+                schema.insert("x-maximum".to_string(), json!(max.to_string()));
+            }
+
+            if let Some(max_scale) = max_scale {
+                schema.insert("x-max-scale".to_string(), json!(max_scale));
+            }
+        }
+        InnerColumnType::BitFlag { values, .. } => {
+            schema.insert("type".to_string(), json!("integer"));
+            schema.insert("x-flags".to_string(), json!(values));
+        }
+        InnerColumnType::Boolean { .. } => {
+            schema.insert("type".to_string(), json!("boolean"));
+        }
+        InnerColumnType::Json { kind, max_bytes } => {
+            // JSON columns accept any value
+            if let Some(max_bytes) = max_bytes {
+                schema.insert("x-max-bytes".to_string(), json!(max_bytes));
+            }
+
+            if !kind.is_empty() {
+                schema.insert("x-kind".to_string(), json!(kind));
+            }
+        }
+        InnerColumnType::Object { fields } => {
+            let properties = fields
+                .iter()
+                .map(|field| (field.id.to_string(), Value::Object(column_schema(field))))
+                .collect::<Map<String, Value>>();
+
+            let required = fields
+                .iter()
+                .filter(|field| !field.nullable && field.default.is_none())
+                .map(|field| field.id.to_string())
+                .collect::<Vec<_>>();
+
+            schema.insert("type".to_string(), json!("object"));
+            schema.insert("properties".to_string(), Value::Object(properties));
+            schema.insert("required".to_string(), json!(required));
+            schema.insert("additionalProperties".to_string(), json!(false));
+        }
+    }
+
+    schema
+}
+
+/// Allows null in a schema, schemas without a `type` (JSON columns) already accept null
+fn make_nullable(schema: &mut Map<String, Value>) {
+    if let Some(Value::String(ty)) = schema.get("type").cloned() {
+        schema.insert("type".to_string(), json!([ty, "null"]));
+    }
+
+    if let Some(Value::Array(values)) = schema.get_mut("enum") {
+        values.push(Value::Null);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ColumnBuilder, SettingBuilder};
+    use crate::common_columns;
+    use crate::memory::InMemorySettings;
+    use crate::types::{SettingOperations, Surface};
+
+    fn setting() -> Setting<()> {
+        let mut id = common_columns::integer("id", "ID", "The ID");
+        id.primary_key = true;
+
+        let layout = ColumnBuilder::new(
+            "layout",
+            "Layout",
+            ColumnType::new_scalar(InnerColumnType::Json {
+                kind: "normal".to_string(),
+                max_bytes: None,
+            }),
+        )
+        .nullable(true)
+        .surfaces(vec![Surface::Dashboard])
+        .build();
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id, layout])
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "id".to_string()
+            ])))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn dashboard_only_column_is_in_dashboard_schema() {
+        let schema = setting_to_json_schema(&setting().for_surface(Surface::Dashboard));
+
+        assert!(schema["properties"]["id"].is_object());
+        assert!(schema["properties"]["layout"].is_object());
+    }
+
+    #[test]
+    fn dashboard_only_column_is_not_in_other_schemas() {
+        for surface in [Surface::Command, Surface::Api] {
+            let schema = setting_to_json_schema(&setting().for_surface(surface));
+
+            assert!(schema["properties"]["id"].is_object());
+            assert!(schema["properties"].get("layout").is_none());
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use std::sync::Arc;
-
-use crate::types::{Column, ColumnType, InnerColumnType, OperationType, Setting};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::ui::truncate_with_ellipsis;
+use crate::cfg::parse_bool;
+use crate::types::{
+    Column, ColumnType, ContextMenuKind, InnerColumnType, OperationType, Setting, SortBy, Surface,
+};
 use serde_json::{Number, Value};
 use serenity::all::CommandOptionType;
 
@@ -31,6 +37,19 @@ fn parse_numeric_list<T: std::str::FromStr + Send + Sync>(
     Ok(list)
 }
 
+/// Parses a list of booleans separated by commas, semicolons or whitespace
+///
+/// Each token is parsed using the same rules as boolean columns (see `parse_bool`)
+fn parse_bool_list(s: &str, strict: bool) -> Result<Vec<bool>, crate::Error> {
+    s.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            parse_bool(token, strict)
+                .ok_or_else(|| format!("Expected a boolean but got `{}`", token).into())
+        })
+        .collect()
+}
+
 fn split_input_to_string(s: &str, separator: &str) -> Vec<String> {
     s.split(separator)
         .filter_map(|s| {
@@ -46,7 +65,7 @@ fn split_input_to_string(s: &str, separator: &str) -> Vec<String> {
 
 /// Given a set of bitflag values and an input, return the bitflag value
 #[inline]
-fn convert_bitflags_string_to_value(
+pub(crate) fn convert_bitflags_string_to_value(
     values: &indexmap::IndexMap<String, i64>,
     input: Option<String>,
 ) -> Value {
@@ -55,7 +74,7 @@ fn convert_bitflags_string_to_value(
             let mut bitflags = 0;
 
             for value in input.split(';') {
-                if let Some(value) = values.get(value) {
+                if let Some(value) = values.get(value.trim()) {
                     bitflags |= *value;
                 }
             }
@@ -103,7 +122,7 @@ fn serenity_resolvedvalue_to_value(
     // Get the inner column type and is_array status
     let (is_array, inner_column_type) = match column_type {
         ColumnType::Scalar { ref inner } => (false, inner),
-        ColumnType::Array { ref inner } => (true, inner),
+        ColumnType::Array { ref inner, .. } => (true, inner),
     };
 
     let pot_output = {
@@ -127,7 +146,7 @@ fn serenity_resolvedvalue_to_value(
     };
 
     match inner_column_type {
-        InnerColumnType::Integer {} => {
+        InnerColumnType::Integer { .. } => {
             if is_array {
                 // Handle integer list
                 let list = parse_numeric_list::<i64>(&pot_output, &[])?;
@@ -148,7 +167,7 @@ fn serenity_resolvedvalue_to_value(
                 }
             }
         }
-        InnerColumnType::Float {} => {
+        InnerColumnType::Float { .. } => {
             if is_array {
                 // Handle integer list
                 let list = parse_numeric_list::<f64>(&pot_output, &[])?;
@@ -173,10 +192,10 @@ fn serenity_resolvedvalue_to_value(
                 }
             }
         }
-        InnerColumnType::Boolean {} => {
+        InnerColumnType::Boolean { strict } => {
             if is_array {
-                // Handle integer list
-                let list = parse_numeric_list::<bool>(&pot_output, &[])?;
+                // Handle boolean list
+                let list = parse_bool_list(&pot_output, *strict)?;
 
                 let mut new_list = Vec::new();
 
@@ -194,7 +213,9 @@ fn serenity_resolvedvalue_to_value(
                 }
             }
         }
-        InnerColumnType::String { .. } => {
+        InnerColumnType::String {
+            ref channel_types, ..
+        } => {
             if !is_array {
                 match rv {
                     serenity::all::ResolvedValue::String(v) => {
@@ -204,15 +225,35 @@ fn serenity_resolvedvalue_to_value(
                         return Ok(Value::String(v.id.to_string()));
                     }
                     serenity::all::ResolvedValue::Channel(v) => {
+                        if !channel_types.is_empty()
+                            && !channel_types.iter().any(|t| t == v.kind.name())
+                        {
+                            return Err(format!(
+                                "Expected a channel of type {} but got a {} channel",
+                                channel_types.join(", "),
+                                v.kind.name()
+                            )
+                            .into());
+                        }
+
                         return Ok(Value::String(v.id.to_string()));
                     }
                     _ => return Err("Expected string, got something else".into()),
                 }
             }
         }
-        InnerColumnType::BitFlag { ref values } => {
+        InnerColumnType::BitFlag { ref values, .. } => {
             if is_array {
-                return Err("Array bitflags are not supported yet".into()); // TODO
+                // Handle bitflag list, each element is a `;` separated set of flag names
+                let list = split_input_to_string(&pot_output, ",");
+
+                let mut new_list = Vec::new();
+
+                for v in list {
+                    new_list.push(convert_bitflags_string_to_value(values, Some(v)));
+                }
+
+                return Ok(Value::Array(new_list));
             }
 
             match rv {
@@ -251,16 +292,105 @@ fn serenity_resolvedvalue_to_value(
 
 /// In order to provide state to the subcommand callback, we need to wrap it in a struct and then pass it through custom_data
 pub struct SubcommandCallbackWrapper<Data: Clone> {
+    /// The setting. Context-specific changes (e.g. per-guild allowed values through
+    /// `Setting::with_overrides`) must be applied here so that autocomplete and validation resolve the same
+    /// allowed values
     pub config_option: Setting<Data>,
     pub data: Arc<Data>,
     pub operation_type: OperationType,
+
+    /// Whether to collect fields through an interactive form (wizard) instead of only using the command
+    /// options
+    ///
+    /// Only applies to Create and Update operations
+    pub form: bool,
+
+    /// An optional cache for autocomplete results, rapid successive identical queries within the cache's TTL
+    /// reuse the computed choices
+    ///
+    /// The same cache should be shared across invocations (the wrapper is created per interaction)
+    pub autocomplete_cache: Option<Arc<AutocompleteCache>>,
 }
 
-/// Gets the values from a serenity ResolvedValue handling choices and all that garbage
-fn getvalues<Data: Clone>(
-    config_opt: &Setting<Data>,
+/// A short-lived cache of autocomplete choices keyed by guild, setting, column and query
+///
+/// Autocomplete fires on every keystroke, this avoids recomputing (potentially expensive) choices for
+/// identical queries made in quick succession
+pub struct AutocompleteCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedChoices>>,
+}
+
+/// The choices cached for a key along with when they were inserted
+type CachedChoices = (Instant, Vec<(String, String)>);
+
+impl AutocompleteCache {
+    /// Creates a new cache where entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached choices for the key if present and not expired
+    pub fn get(&self, key: &str) -> Option<Vec<(String, String)>> {
+        let entries = self.entries.lock().ok()?;
+
+        match entries.get(key) {
+            Some((inserted_at, choices)) if inserted_at.elapsed() < self.ttl => {
+                Some(choices.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Inserts choices into the cache, removing any expired entries
+    pub fn insert(&self, key: String, choices: Vec<(String, String)>) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+
+        entries.retain(|_, (inserted_at, _)| inserted_at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), choices));
+    }
+}
+
+/// Returns the cached choices for `key`, calling `resolve` to compute them on a cache miss (or if there is no
+/// cache)
+///
+/// `resolve` also returns whether its choices may be cached
+async fn cached_autocomplete_choices<F, Fut>(
+    cache: Option<&AutocompleteCache>,
+    key: String,
+    resolve: F,
+) -> Vec<(String, String)>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = (Vec<(String, String)>, bool)>,
+{
+    if let Some(choices) = cache.and_then(|cache| cache.get(&key)) {
+        return choices;
+    }
+
+    let (choices, cacheable) = resolve().await;
+
+    if cacheable {
+        if let Some(cache) = cache {
+            cache.insert(key, choices.clone());
+        }
+    }
+
+    choices
+}
+
+/// The name of the option of the view subcommand used to choose the order of entries
+const SORT_BY_OPTION: &str = "sort_by";
+
+/// Gets the options passed to the (first) subcommand of the interaction
+fn subcommand_options(
     interaction: &serenity::all::Interaction,
-) -> Result<indexmap::IndexMap<String, Value>, crate::Error> {
+) -> Result<Vec<serenity::all::ResolvedOption<'_>>, crate::Error> {
     let resolved_args = match interaction {
         serenity::all::Interaction::Command(interaction) => interaction.data.options(),
         serenity::all::Interaction::Autocomplete(interaction) => interaction.data.options(),
@@ -289,10 +419,21 @@ fn getvalues<Data: Clone>(
         return Err("Invalid interaction data [expected subcommand or subcommand group]".into());
     };
 
+    Ok(resolved_args)
+}
+
+/// Gets the values from a serenity ResolvedValue handling choices and all that garbage
+fn getvalues<Data: Clone>(
+    config_opt: &Setting<Data>,
+    interaction: &serenity::all::Interaction,
+) -> Result<indexmap::IndexMap<String, Value>, crate::Error> {
+    let resolved_args = subcommand_options(interaction)?;
+
     let mut map = indexmap::IndexMap::new();
 
     for column in config_opt.columns.iter() {
-        let Some(arg) = resolved_args.iter().find(|a| a.name == column.id) else {
+        let option_name = command_name(&column.id);
+        let Some(arg) = resolved_args.iter().find(|a| a.name == option_name) else {
             continue; // Skip if the column is not present
         };
 
@@ -305,6 +446,69 @@ fn getvalues<Data: Clone>(
     Ok(map)
 }
 
+/// Gets the sort order chosen through the `sort_by` option of the view subcommand, if any
+///
+/// Choices are the column id, prefixed with `-` for descending order
+fn getsort<Data: Clone>(
+    config_opt: &Setting<Data>,
+    interaction: &serenity::all::Interaction,
+) -> Result<Option<SortBy>, crate::Error> {
+    // A column named `sort_by` takes precedence over the option
+    if config_opt.column(SORT_BY_OPTION).is_some() {
+        return Ok(None);
+    }
+
+    let resolved_args = subcommand_options(interaction)?;
+
+    let Some(arg) = resolved_args.iter().find(|a| a.name == SORT_BY_OPTION) else {
+        return Ok(None);
+    };
+
+    let serenity::all::ResolvedValue::String(choice) = arg.value else {
+        return Err(format!("Option `{}` must be a string", SORT_BY_OPTION).into());
+    };
+
+    let sort = match choice.strip_prefix('-') {
+        Some(column) => SortBy {
+            column: column.to_string(),
+            descending: true,
+        },
+        None => SortBy {
+            column: choice.to_string(),
+            descending: false,
+        },
+    };
+
+    Ok(Some(sort))
+}
+
+/// Checks that the member invoking the command has the `required_permissions` of the setting
+fn check_required_permissions<Data: Clone>(
+    setting: &Setting<Data>,
+    cmd_interaction: &serenity::all::CommandInteraction,
+) -> Result<(), String> {
+    let Some(required_permissions) = setting.required_permissions else {
+        return Ok(());
+    };
+
+    let required_permissions = serenity::all::Permissions::from_bits_truncate(required_permissions);
+
+    let member_permissions = cmd_interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .unwrap_or_default();
+
+    if !member_permissions.contains(required_permissions) {
+        return Err(format!(
+            "You need the following permissions to use this command: {}",
+            required_permissions - member_permissions
+        ));
+    }
+
+    Ok(())
+}
+
 /// Subcommand callback
 pub async fn subcommand_command<Data: Clone>(
     ctx: &serenity::all::Context,
@@ -315,32 +519,146 @@ pub async fn subcommand_command<Data: Clone>(
         serenity::all::Interaction::Command(interaction) => interaction,
         _ => return Err("Invalid interaction type".into()),
     };
+
+    // User-facing errors (e.g. invalid input) are sent as the response instead of being returned
+    let send_error = |message: String| async move {
+        super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id))
+            .send_error(&message)
+            .await
+    };
+
+    // Default member permissions can be overridden by server admins, so check the required permissions here as well
+    if let Err(e) =
+        check_required_permissions(&subcommand_callback_wrapper.config_option, cmd_interaction)
+    {
+        return send_error(e).await;
+    }
+
     match subcommand_callback_wrapper.operation_type {
         OperationType::View => {
+            let filters = match getvalues(&subcommand_callback_wrapper.config_option, interaction) {
+                Ok(filters) => filters,
+                Err(e) => return send_error(e.to_string()).await,
+            };
+
+            let sort = match getsort(&subcommand_callback_wrapper.config_option, interaction) {
+                Ok(sort) => sort,
+                Err(e) => return send_error(e.to_string()).await,
+            };
+
             super::ui::settings_viewer(
                 super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
                 &subcommand_callback_wrapper.config_option,
                 &subcommand_callback_wrapper.data,
-                indexmap::IndexMap::new(), // TODO: Add filtering in the future
+                filters,
+                sort,
             )
             .await
         }
         OperationType::Create => {
-            let entry = getvalues(&subcommand_callback_wrapper.config_option, interaction)?;
+            let mut entry = match getvalues(&subcommand_callback_wrapper.config_option, interaction)
+            {
+                Ok(entry) => entry,
+                Err(e) => return send_error(e.to_string()).await,
+            };
+
+            if subcommand_callback_wrapper.form {
+                return super::ui::settings_form(
+                    super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
+                    &subcommand_callback_wrapper.config_option,
+                    &subcommand_callback_wrapper.data,
+                    OperationType::Create,
+                    entry,
+                )
+                .await;
+            }
+
+            let src = super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id));
+
+            let modal;
+            let src = match super::ui::collect_long_inputs(
+                &src,
+                &subcommand_callback_wrapper.config_option,
+                OperationType::Create,
+                &mut entry,
+            )
+            .await?
+            {
+                super::ui::LongInputs::NotNeeded => src,
+                super::ui::LongInputs::Submitted(item) => {
+                    modal = item;
+                    super::ui::Src::Modal((&modal, ctx, cmd_interaction.user.id))
+                }
+                super::ui::LongInputs::Cancelled => return Ok(()),
+            };
+
+            let component;
+            let src = match super::ui::collect_bitflag_inputs(
+                &src,
+                &subcommand_callback_wrapper.config_option,
+                OperationType::Create,
+                &mut entry,
+                None,
+            )
+            .await?
+            {
+                super::ui::BitFlagInputs::NotNeeded => src,
+                super::ui::BitFlagInputs::Submitted(item) => {
+                    component = item;
+                    super::ui::Src::Component((&component, ctx, cmd_interaction.user.id))
+                }
+                super::ui::BitFlagInputs::Cancelled => return Ok(()),
+            };
 
             super::ui::settings_creator(
-                super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
+                src,
                 &subcommand_callback_wrapper.config_option,
                 &subcommand_callback_wrapper.data,
                 entry,
             )
             .await
+            .map(|_| ())
         }
         OperationType::Update => {
-            let mut entry = getvalues(&subcommand_callback_wrapper.config_option, interaction)?;
+            let mut entry = match getvalues(&subcommand_callback_wrapper.config_option, interaction)
+            {
+                Ok(entry) => entry,
+                Err(e) => return send_error(e.to_string()).await,
+            };
+
+            if subcommand_callback_wrapper.form {
+                return super::ui::settings_form(
+                    super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
+                    &subcommand_callback_wrapper.config_option,
+                    &subcommand_callback_wrapper.data,
+                    OperationType::Update,
+                    entry,
+                )
+                .await;
+            }
+
+            let src = super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id));
+
+            // Long inputs are collected before autofill so that left-empty inputs keep their current value
+            let modal;
+            let src = match super::ui::collect_long_inputs(
+                &src,
+                &subcommand_callback_wrapper.config_option,
+                OperationType::Update,
+                &mut entry,
+            )
+            .await?
+            {
+                super::ui::LongInputs::NotNeeded => src,
+                super::ui::LongInputs::Submitted(item) => {
+                    modal = item;
+                    super::ui::Src::Modal((&modal, ctx, cmd_interaction.user.id))
+                }
+                super::ui::LongInputs::Cancelled => return Ok(()),
+            };
 
             // Attempt to autofill from created data if possible
-            let mut have_found_for_autofill = false;
+            let mut existing = None;
             if subcommand_callback_wrapper
                 .config_option
                 .operations
@@ -353,69 +671,89 @@ pub async fn subcommand_command<Data: Clone>(
                         if let Some(value) = entry.get(&column.id) {
                             pkey_state.insert(column.id.clone(), value.clone());
                         } else {
-                            return Err(format!(
-                                "An input for `{}` is required",
-                                column.id
-                            )
-                            .into());
+                            return src
+                                .send_error(&format!("An input for `{}` is required", column.id))
+                                .await;
                         }
                     }
                 }
 
-                let values = crate::cfg::settings_view(
+                // Only the entry being updated is fetched, executors ignoring the filters are handled
+                // by matching the primary key again below
+                let values = match crate::cfg::settings_view(
                     &subcommand_callback_wrapper.config_option,
                     &subcommand_callback_wrapper.data,
-                    indexmap::indexmap! {},
+                    pkey_state.clone(),
+                    None,
+                    Some(&cmd_interaction.user.id.to_string()),
                 )
                 .await
-                .map_err(|e| format!("Error fetching settings for autofill: {:?}", e))?;
-
-                // Find value with primary key that matches the update
-                for value in values {
-                    for (pkey_key, pkey_value) in pkey_state.iter() {
-                        if let Some(v) = value.get(pkey_key) {
-                            if v != pkey_value {
-                                continue;
-                            }
-                        } else {
-                            continue;
-                        }
+                {
+                    Ok(values) => values,
+                    Err(e) => {
+                        return src
+                            .send_error(&format!("Error fetching settings for autofill: {}", e))
+                            .await
                     }
+                };
 
-                    have_found_for_autofill = true;
-
-                    for (key, value) in value {
-                        if entry.contains_key(&key) {
-                            continue;
-                        }
+                existing = find_entry_by_primary_key(values, &pkey_state);
+            }
 
-                        entry.insert(key, value);
-                    }
-                    break;
+            let component;
+            let src = match super::ui::collect_bitflag_inputs(
+                &src,
+                &subcommand_callback_wrapper.config_option,
+                if existing.is_some() {
+                    OperationType::Update
+                } else {
+                    OperationType::Create
+                },
+                &mut entry,
+                existing.as_ref(),
+            )
+            .await?
+            {
+                super::ui::BitFlagInputs::NotNeeded => src,
+                super::ui::BitFlagInputs::Submitted(item) => {
+                    component = item;
+                    super::ui::Src::Component((&component, ctx, cmd_interaction.user.id))
                 }
-            }
+                super::ui::BitFlagInputs::Cancelled => return Ok(()),
+            };
 
-            if !have_found_for_autofill {
+            let Some(existing) = existing else {
                 // Switch to create impl
                 return super::ui::settings_creator(
-                    super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
+                    src,
                     &subcommand_callback_wrapper.config_option,
                     &subcommand_callback_wrapper.data,
                     entry,
                 )
-                .await;
-            }
+                .await
+                .map(|_| ());
+            };
+
+            autofill_update_entry(
+                &subcommand_callback_wrapper.config_option,
+                &mut entry,
+                existing,
+            );
 
             super::ui::settings_updater(
-                super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
+                src,
                 &subcommand_callback_wrapper.config_option,
                 &subcommand_callback_wrapper.data,
                 entry,
             )
             .await
+            .map(|_| ())
         }
         OperationType::Delete => {
-            let entry = getvalues(&subcommand_callback_wrapper.config_option, interaction)?;
+            let entry = match getvalues(&subcommand_callback_wrapper.config_option, interaction) {
+                Ok(entry) => entry,
+                Err(e) => return send_error(e.to_string()).await,
+            };
 
             super::ui::settings_deleter(
                 super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id)),
@@ -428,6 +766,221 @@ pub async fn subcommand_command<Data: Clone>(
     }
 }
 
+/// Returns the entry whose primary key values all equal those in `pkey_state`, if any
+///
+/// Executors may return more entries than were filtered for, so the entries are matched again here
+fn find_entry_by_primary_key(
+    entries: Vec<indexmap::IndexMap<String, Value>>,
+    pkey_state: &indexmap::IndexMap<String, Value>,
+) -> Option<indexmap::IndexMap<String, Value>> {
+    entries
+        .into_iter()
+        .find(|entry| crate::cfg::row_matches_state(entry, pkey_state))
+}
+
+/// Fills the fields of an update that were not provided from the existing entry
+///
+/// Secret values are masked in views and merging them would overwrite the real value, the version must
+/// be the one of the entry the user viewed as taking it from the current entry would defeat the conflict
+/// check. Neither is autofilled
+fn autofill_update_entry<Data: Clone>(
+    setting: &Setting<Data>,
+    entry: &mut indexmap::IndexMap<String, Value>,
+    existing: indexmap::IndexMap<String, Value>,
+) {
+    for (key, value) in existing {
+        if entry.contains_key(&key) {
+            continue;
+        }
+
+        if setting.column(&key).is_some_and(|c| c.secret) {
+            continue;
+        }
+
+        if setting.version_column.as_deref() == Some(key.as_str()) {
+            continue;
+        }
+
+        entry.insert(key, value);
+    }
+}
+
+/// How well an autocomplete candidate matches the query, lower is better
+///
+/// Prefix matches rank first (shorter candidates first so that exact matches win), then substring
+/// matches (earlier is better) and finally subsequence matches (fewer gaps is better)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchScore {
+    Prefix(usize),
+    Substring(usize),
+    Subsequence(usize),
+}
+
+/// Compares two characters case-insensitively without allocating
+fn chars_eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Returns whether `s` starts with `prefix`, case-insensitively
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    let mut chars = s.chars();
+    prefix
+        .chars()
+        .all(|p| chars.next().is_some_and(|c| chars_eq_ignore_case(c, p)))
+}
+
+/// Scores a candidate against the (case-insensitive) query, returning None if it does not match at all
+///
+/// A subsequence match is a fuzzy match where the characters of the query appear in order in the candidate,
+/// e.g. `mdlg` matches `mod_log`. This runs on every keystroke so it avoids allocating
+fn match_score(candidate: &str, query: &str) -> Option<MatchScore> {
+    // Nothing typed yet, keep the declaration order
+    if query.is_empty() {
+        return Some(MatchScore::Prefix(0));
+    }
+
+    if starts_with_ignore_case(candidate, query) {
+        return Some(MatchScore::Prefix(candidate.len()));
+    }
+
+    if let Some(pos) = candidate
+        .char_indices()
+        .position(|(idx, _)| starts_with_ignore_case(&candidate[idx..], query))
+    {
+        return Some(MatchScore::Substring(pos));
+    }
+
+    let mut query_chars = query.chars().peekable();
+    let mut gaps = 0;
+    let mut started = false;
+
+    for c in candidate.chars() {
+        let Some(&q) = query_chars.peek() else {
+            break;
+        };
+
+        if chars_eq_ignore_case(c, q) {
+            query_chars.next();
+            started = true;
+        } else if started {
+            gaps += 1;
+        }
+    }
+
+    if query_chars.peek().is_none() {
+        Some(MatchScore::Subsequence(gaps))
+    } else {
+        None
+    }
+}
+
+/// Sorts scored choices best match first, choices with equal scores keep their declaration order
+fn rank_choices(mut choices: Vec<(MatchScore, (String, String))>) -> Vec<(String, String)> {
+    choices.sort_by_key(|(score, _)| *score);
+    choices.into_iter().map(|(_, choice)| choice).collect()
+}
+
+/// Returns the numeric allowed values that start with the partial input, shortest (closest) first
+///
+/// Numbers are matched by prefix only as fuzzy matching digits gives confusing results
+fn numeric_autocomplete_choices(allowed_values: &[String], input: &str) -> Vec<(String, String)> {
+    let mut choices = allowed_values
+        .iter()
+        .filter(|value| value.starts_with(input))
+        .map(|value| (value.clone(), value.clone()))
+        .collect::<Vec<_>>();
+
+    // Stable, so values of the same length keep their declaration order
+    choices.sort_by_key(|(value, _)| value.len());
+    choices
+}
+
+/// Returns the choices for the partial input from the static allowed values of the column, falling back
+/// to its static suggestions if it has no allowed values
+fn static_autocomplete_choices(column: &Column, input: &str) -> Vec<(String, String)> {
+    if let ColumnType::Scalar {
+        inner: ref inner @ (InnerColumnType::Integer { .. } | InnerColumnType::Float { .. }),
+    } = column.column_type
+    {
+        let mut numeric_values = inner.allowed_values().into_owned();
+
+        if numeric_values.is_empty() {
+            numeric_values = column.suggestions.static_suggestions().to_vec();
+        }
+
+        return numeric_autocomplete_choices(&numeric_values, input);
+    }
+
+    let allowed = column.column_type.inner().allowed_values();
+    let allowed_values: &[String] = if allowed.is_empty() {
+        column.suggestions.static_suggestions()
+    } else {
+        &allowed
+    };
+
+    match &column.column_type {
+        ColumnType::Scalar { .. } => {
+            let mut choices = Vec::new();
+
+            for value in allowed_values {
+                if let Some(score) = match_score(value, input) {
+                    choices.push((score, (value.clone(), value.clone())));
+                }
+            }
+
+            rank_choices(choices)
+        }
+        ColumnType::Array { .. } => {
+            let mut choices = Vec::new();
+
+            let mut autocomp_values = split_input_to_string(input, ",");
+
+            // A trailing comma starts a new item, in which case all items typed so far are complete
+            let last_value = if input.ends_with(',') {
+                String::new()
+            } else {
+                autocomp_values.pop().unwrap_or_default()
+            };
+
+            for value in allowed_values {
+                if let Some(score) = match_score(value, &last_value) {
+                    let autocomplete_choice_value = if autocomp_values.is_empty() {
+                        value.clone()
+                    } else {
+                        format!("{},{}", autocomp_values.join(","), value)
+                    };
+
+                    choices.push((
+                        score,
+                        (autocomplete_choice_value.clone(), autocomplete_choice_value),
+                    ));
+                }
+            }
+
+            rank_choices(choices)
+        }
+    }
+}
+
+/// Converts the value of a choice to the type of the option, Integer and Number options need numeric values
+///
+/// Values that cannot be converted (e.g. returned by an autocomplete provider) are dropped
+fn autocomplete_choice_value(column: &Column, value: String) -> Option<Value> {
+    match column.column_type {
+        ColumnType::Scalar {
+            inner: InnerColumnType::Integer { .. },
+        } => value.parse::<i64>().ok().map(Value::from),
+        ColumnType::Scalar {
+            inner: InnerColumnType::Float { .. },
+        } => value
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        _ => Some(Value::String(value)),
+    }
+}
+
 /// An autocomplete callback
 pub async fn subcommand_autocomplete<Data: Clone>(
     ctx: &serenity::all::Context,
@@ -443,78 +996,76 @@ pub async fn subcommand_autocomplete<Data: Clone>(
         return Err("Invalid interaction data [expected autocomplete]".into());
     };
 
-    let columns = &subcommand_callback_wrapper.config_option.columns;
-    let Some(column) = columns.iter().find(|c| c.id == autocomplete_option.name) else {
+    let Some(column) = subcommand_callback_wrapper
+        .config_option
+        .columns
+        .iter()
+        .find(|c| command_name(&c.id) == autocomplete_option.name)
+    else {
         return Err("Invalid column".into());
     };
 
-    let options = match &column.column_type {
-        ColumnType::Scalar { inner } => match inner {
-            InnerColumnType::String { allowed_values, .. } => {
-                let mut choices = Vec::new();
-
-                for value in allowed_values {
-                    if value.contains(autocomplete_option.value) {
-                        choices.push(serenity::all::AutocompleteChoice::new(
-                            value.clone(),
-                            value.clone(),
-                        ));
-                    }
-                }
-
-                choices
-            }
-            _ => return Ok(()),
-        },
-        ColumnType::Array { inner } => match inner {
-            InnerColumnType::String { allowed_values, .. } => {
-                let mut choices = Vec::new();
-
-                let autocomp_values = split_input_to_string(autocomplete_option.value, ",");
-                let last_value = match autocomp_values.last() {
-                    Some(v) => v,
-                    None => &"".to_string(),
-                };
-
-                for value in allowed_values {
-                    if value.contains(last_value) {
-                        if autocomp_values.len() <= 1 {
-                            choices.push(serenity::all::AutocompleteChoice::new(
-                                value.clone(),
-                                value.clone(),
-                            ));
-                            continue;
-                        } else {
-                            let autocomplete_choice_value = format!(
-                                "{},{}",
-                                autocomp_values[..autocomp_values.len() - 1].join(","),
-                                value
-                            );
-                            choices.push(serenity::all::AutocompleteChoice::new(
-                                autocomplete_choice_value.clone(),
-                                autocomplete_choice_value,
-                            ));
-                        }
-                    }
-                }
+    // Surrounding whitespace is never meaningful to the match
+    let input = autocomplete_option.value.trim();
+
+    let cache_key = format!(
+        "{}/{}/{}/{}",
+        cmd_interaction
+            .guild_id
+            .map(|g| g.to_string())
+            .unwrap_or_default(),
+        subcommand_callback_wrapper.config_option.id,
+        column.id,
+        input
+    );
 
-                choices
-            }
-            _ => return Ok(()),
+    let options = cached_autocomplete_choices(
+        subcommand_callback_wrapper.autocomplete_cache.as_deref(),
+        cache_key,
+        || async {
+            let dynamic = match subcommand_callback_wrapper
+                .config_option
+                .operations
+                .autocompleter
+            {
+                Some(ref autocompleter) => match autocompleter
+                    .complete(&subcommand_callback_wrapper.data, &column.id, input)
+                    .await
+                {
+                    Ok(choices) => choices,
+                    // A failing provider should not fail the interaction, provider errors are not cached
+                    // so the next keystroke retries
+                    Err(_) => return (Vec::new(), false),
+                },
+                None => None,
+            };
+
+            let options = match dynamic {
+                Some(choices) => choices.into_iter().take(25).collect(),
+                None => static_autocomplete_choices(column, input),
+            };
+
+            (options, true)
         },
-    };
+    )
+    .await;
 
     cmd_interaction
         .create_response(
             &ctx.http,
             serenity::all::CreateInteractionResponse::Autocomplete(
-                serenity::all::CreateAutocompleteResponse::new().set_choices({
-                    if options.len() > 25 {
-                        options[..25].to_vec()
-                    } else {
-                        options
-                    }
-                }),
+                serenity::all::CreateAutocompleteResponse::new().set_choices(
+                    options
+                        .into_iter()
+                        .take(25)
+                        .filter_map(|(name, value)| {
+                            Some(serenity::all::AutocompleteChoice::new(
+                                name,
+                                autocomplete_choice_value(column, value)?,
+                            ))
+                        })
+                        .collect::<Vec<_>>(),
+                ),
             ),
         )
         .await?;
@@ -522,49 +1073,285 @@ pub async fn subcommand_autocomplete<Data: Clone>(
     Ok(())
 }
 
+/// Create a command from a setting, after checking that the setting's schema is valid and that Discord will
+/// accept the command (see `validate_command_limits`)
+///
+/// Prefer this over `create_commands_from_setting` when registering commands at startup so that
+/// misconfigured settings fail loudly instead of on first use
+pub fn try_create_commands_from_setting<'a, Data: Clone>(
+    setting: &Setting<Data>,
+) -> Result<serenity::all::CreateCommand<'a>, crate::Error> {
+    setting.validate_schema()?;
+    validate_command_limits(setting)?;
+    Ok(build_command(setting))
+}
+
+/// Create a command from a setting with a root command, after checking that the setting's schema is valid
+/// and that Discord will accept the command (see `validate_command_limits`)
+///
+/// See `create_commands_from_setting_with_root`
+pub fn try_create_commands_from_setting_with_root<'a, Data: Clone>(
+    setting: &Setting<Data>,
+    root: serenity::all::CreateCommand<'a>,
+) -> Result<serenity::all::CreateCommand<'a>, crate::Error> {
+    setting.validate_schema()?;
+    validate_command_limits(setting)?;
+    Ok(build_command_with_root(setting, root))
+}
+
 /// Create a command from a setting
+///
+/// # Panics
+///
+/// Panics with the error of `try_create_commands_from_setting` if the setting's schema is invalid or
+/// Discord would reject the command, use `try_create_commands_from_setting` to handle this instead
 pub fn create_commands_from_setting<'a, Data: Clone>(
     setting: &Setting<Data>,
 ) -> serenity::all::CreateCommand<'a> {
-    let cmd = serenity::all::CreateCommand::new(setting.id.to_string())
-        .description({
-            if setting.description.len() > 100 {
-                setting.description[..97].to_string() + "..."
-            } else {
-                setting.description.to_string()
-            }
-        })
-        .kind(serenity::all::CommandType::ChatInput)
+    match try_create_commands_from_setting(setting) {
+        Ok(cmd) => cmd,
+        Err(e) => panic!(
+            "Cannot create the command of setting `{}`: {}",
+            setting.id, e
+        ),
+    }
+}
+
+/// Builds the command of a setting without validating it
+fn build_command<'a, Data: Clone>(setting: &Setting<Data>) -> serenity::all::CreateCommand<'a> {
+    let mut cmd = serenity::all::CreateCommand::new(command_name(&setting.id))
+        .description(truncate_with_ellipsis(&setting.description, 100))
+        .kind(serenity::all::CommandType::ChatInput)
         .integration_types(vec![serenity::all::InstallationContext::Guild])
         .set_options(create_subcommands_from_setting(setting));
 
+    if let Some(required_permissions) = setting.required_permissions {
+        cmd = cmd.default_member_permissions(serenity::all::Permissions::from_bits_truncate(
+            required_permissions,
+        ));
+    }
+
+    for (locale, name) in setting.name_localizations.iter() {
+        cmd = cmd.name_localized(locale.to_string(), name.to_string());
+    }
+
+    for (locale, description) in setting.description_localizations.iter() {
+        cmd =
+            cmd.description_localized(locale.to_string(), truncate_with_ellipsis(description, 100));
+    }
+
     cmd
 }
 
+/// Create the context menu (User/Message) commands of a setting (see `Setting::context_menus`)
+///
+/// Interactions of these commands should be passed to `context_menu_command`
+pub fn create_context_menu_commands<'a, Data: Clone>(
+    setting: &Setting<Data>,
+) -> Vec<serenity::all::CreateCommand<'a>> {
+    setting
+        .context_menus
+        .iter()
+        .map(|context_menu| {
+            let mut cmd = serenity::all::CreateCommand::new(context_menu.name.to_string())
+                .kind(match context_menu.kind {
+                    ContextMenuKind::User => serenity::all::CommandType::User,
+                    ContextMenuKind::Message => serenity::all::CommandType::Message,
+                })
+                .integration_types(vec![serenity::all::InstallationContext::Guild]);
+
+            if let Some(required_permissions) = setting.required_permissions {
+                cmd = cmd.default_member_permissions(
+                    serenity::all::Permissions::from_bits_truncate(required_permissions),
+                );
+            }
+
+            cmd
+        })
+        .collect()
+}
+
+/// Context menu command callback, performs the operation of the context menu of the setting matching the
+/// invoked command on the targeted user or message
+///
+/// View shows the entries whose target column matches the target. Create and Update collect the other
+/// fields through a form, except that Update edits the existing entry through `settings_editor` when the
+/// target column is the only primary key. Delete deletes the entry with the target as its key
+pub async fn context_menu_command<Data: Clone>(
+    ctx: &serenity::all::Context,
+    interaction: &serenity::all::Interaction,
+    setting: &Setting<Data>,
+    data: &Data,
+) -> Result<(), crate::Error> {
+    let cmd_interaction = match interaction {
+        serenity::all::Interaction::Command(interaction) => interaction,
+        _ => return Err("Invalid interaction type".into()),
+    };
+
+    let kind = match cmd_interaction.data.kind {
+        serenity::all::CommandType::User => ContextMenuKind::User,
+        serenity::all::CommandType::Message => ContextMenuKind::Message,
+        _ => return Err("Invalid interaction data [expected context menu command]".into()),
+    };
+
+    let Some(context_menu) = setting
+        .context_menus
+        .iter()
+        .find(|c| c.kind == kind && c.name == cmd_interaction.data.name.as_str())
+    else {
+        return Err(format!("Unknown context menu: {}", cmd_interaction.data.name).into());
+    };
+
+    let Some(target_id) = cmd_interaction.data.target_id else {
+        return Err("Invalid interaction data [expected target]".into());
+    };
+
+    let src = super::ui::Src::Interaction((cmd_interaction, ctx, cmd_interaction.user.id));
+
+    if let Err(e) = check_required_permissions(setting, cmd_interaction) {
+        return src.send_error(&e).await;
+    }
+
+    let mut fields = indexmap::IndexMap::new();
+    fields.insert(
+        context_menu.target_column.to_string(),
+        Value::String(target_id.to_string()),
+    );
+
+    match context_menu.operation_type {
+        OperationType::View => super::ui::settings_viewer(src, setting, data, fields, None).await,
+        OperationType::Update
+            if setting
+                .columns
+                .iter()
+                .filter(|c| c.primary_key)
+                .all(|c| c.id == context_menu.target_column) =>
+        {
+            super::ui::settings_editor(src, setting, data, fields)
+                .await
+                .map(|_| ())
+        }
+        OperationType::Delete => super::ui::settings_deleter(src, setting, data, fields).await,
+        operation_type => {
+            super::ui::settings_form(src, setting, data, operation_type, fields).await
+        }
+    }
+}
+
 /// Create a command from a setting with a root command. This will use a subcommand group
 /// which contains the subcommands for adding, updating, deleting, and viewing the setting
+///
+/// Subcommand groups cannot have default member permissions, the permissions of the root command
+/// apply instead. `required_permissions` is still checked at runtime by the subcommand callbacks
+///
+/// # Panics
+///
+/// Panics with the error of `try_create_commands_from_setting_with_root` if the setting's schema is
+/// invalid or Discord would reject the command, use `try_create_commands_from_setting_with_root` to handle
+/// this instead
 pub fn create_commands_from_setting_with_root<'a, Data: Clone>(
     setting: &Setting<Data>,
     root: serenity::all::CreateCommand<'a>,
+) -> serenity::all::CreateCommand<'a> {
+    match try_create_commands_from_setting_with_root(setting, root) {
+        Ok(cmd) => cmd,
+        Err(e) => panic!(
+            "Cannot create the command of setting `{}`: {}",
+            setting.id, e
+        ),
+    }
+}
+
+/// Builds the subcommand group of a setting under a root command without validating it
+fn build_command_with_root<'a, Data: Clone>(
+    setting: &Setting<Data>,
+    root: serenity::all::CreateCommand<'a>,
 ) -> serenity::all::CreateCommand<'a> {
     let subcommands = create_subcommands_from_setting(setting);
 
-    let subcommand_group = serenity::all::CreateCommandOption::new(
+    let mut subcommand_group = serenity::all::CreateCommandOption::new(
         CommandOptionType::SubCommandGroup,
-        setting.id.to_string(),
-        {
-            if setting.description.len() > 50 {
-                setting.description[..47].to_string() + "..."
-            } else {
-                setting.description.to_string()
-            }
-        },
+        command_name(&setting.id),
+        truncate_with_ellipsis(&setting.description, 100),
     )
     .set_sub_options(subcommands);
 
+    for (locale, name) in setting.name_localizations.iter() {
+        subcommand_group = subcommand_group.name_localized(locale.to_string(), name.to_string());
+    }
+
+    for (locale, description) in setting.description_localizations.iter() {
+        subcommand_group = subcommand_group
+            .description_localized(locale.to_string(), truncate_with_ellipsis(description, 100));
+    }
+
     root.add_option(subcommand_group)
 }
 
+/// Create a single command grouping several related settings (e.g. all moderation settings) under a shared
+/// namespace, each setting becomes a subcommand group containing the subcommands for its operations
+///
+/// As with `create_commands_from_setting_with_root`, `required_permissions` of the settings are checked at
+/// runtime as subcommand groups cannot have default member permissions
+///
+/// `name` is sanitized like setting ids (see `command_name`)
+///
+/// Errors if a setting's schema is invalid or would be rejected by Discord, if a setting has no operations,
+/// if two settings share a command name or if there are more settings than Discord allows options on a
+/// command (25)
+pub fn try_create_grouped_command<'a, Data: Clone>(
+    name: &str,
+    description: &str,
+    settings: &[Setting<Data>],
+) -> Result<serenity::all::CreateCommand<'a>, crate::Error> {
+    if settings.is_empty() {
+        return Err(format!("Command `{}` must group at least one setting", name).into());
+    }
+
+    if settings.len() > 25 {
+        return Err(format!(
+            "Command `{}` groups {} settings but at most 25 are allowed",
+            name,
+            settings.len()
+        )
+        .into());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for setting in settings {
+        setting.validate_schema()?;
+        validate_command_limits(setting)?;
+
+        if !seen.insert(command_name(&setting.id)) {
+            return Err(format!(
+                "Command `{}`: setting `{}` has the same command name (`{}`) as another setting",
+                name,
+                setting.id,
+                command_name(&setting.id)
+            )
+            .into());
+        }
+
+        // Subcommand groups cannot be nested further, so every setting must have subcommands of its own
+        if create_subcommands_from_setting(setting).is_empty() {
+            return Err(format!(
+                "Command `{}`: setting `{}` has no operations",
+                name, setting.id
+            )
+            .into());
+        }
+    }
+
+    let root = serenity::all::CreateCommand::new(command_name(name))
+        .description(truncate_with_ellipsis(description, 100))
+        .kind(serenity::all::CommandType::ChatInput)
+        .integration_types(vec![serenity::all::InstallationContext::Guild]);
+
+    Ok(settings
+        .iter()
+        .fold(root, |root, setting| build_command_with_root(setting, root)))
+}
+
 fn create_subcommands_from_setting<'a, Data: Clone>(
     config_opt: &Setting<Data>,
 ) -> Vec<serenity::all::CreateCommandOption<'a>> {
@@ -599,97 +1386,264 @@ fn create_subcommands_from_setting<'a, Data: Clone>(
     sub_cmds
 }
 
+/// The channel types that can be used in `channel_types` of a String column
+const CHANNEL_TYPES: [serenity::all::ChannelType; 11] = [
+    serenity::all::ChannelType::Text,
+    serenity::all::ChannelType::Private,
+    serenity::all::ChannelType::Voice,
+    serenity::all::ChannelType::GroupDm,
+    serenity::all::ChannelType::Category,
+    serenity::all::ChannelType::News,
+    serenity::all::ChannelType::NewsThread,
+    serenity::all::ChannelType::PublicThread,
+    serenity::all::ChannelType::PrivateThread,
+    serenity::all::ChannelType::Stage,
+    serenity::all::ChannelType::Forum,
+];
+
 /// Get the choices from the column_type. Note that only string scalar columns can have choices
 fn get_string_choices_for_column(column: &Column) -> Option<Vec<String>> {
     // Get the choices from the column_type. Note that only string scalar columns can have choices
-    #[allow(clippy::collapsible_match)]
     match column.column_type {
-        ColumnType::Scalar { ref inner } => {
-            match inner {
-                InnerColumnType::String { allowed_values, .. } => {
-                    if allowed_values.is_empty() || allowed_values.len() > 25 {
-                        None
-                    } else {
-                        Some(allowed_values.clone())
-                    }
-                }
-                _ => None, // No other channel type can contain a scalar
+        ColumnType::Scalar {
+            inner: ref inner @ InnerColumnType::String { .. },
+        } => {
+            let allowed_values = inner.allowed_values();
+
+            if allowed_values.is_empty() || allowed_values.len() > 25 {
+                None
+            } else {
+                Some(allowed_values.into_owned())
             }
         }
         _ => None,
     }
 }
 
-fn is_column_required_for_operation_type(
-    column: &Column,
-    operation_type: OperationType,
-) -> bool {
-    if operation_type == OperationType::Update && !column.primary_key {
-        return false;
-    }
-
-    !column.nullable
-}
-
-fn create_command_for_operation_type<'a, Data: Clone>(
+/// Returns the columns that become options of the subcommand for the operation, required options first
+fn command_option_columns<Data: Clone>(
     config_opt: &Setting<Data>,
     operation_type: OperationType,
-) -> serenity::all::CreateCommandOption<'a> {
-    let mut args = serenity::all::CreateCommandOption::new(
-        serenity::all::CommandOptionType::SubCommand,
-        match operation_type {
-            OperationType::View => "view",
-            OperationType::Create => "create",
-            OperationType::Update => "update",
-            OperationType::Delete => "delete",
-        },
-        {
-            if config_opt.description.len() > 50 {
-                config_opt.description[..47].to_string() + "..."
-            } else {
-                config_opt.description.to_string()
-            }
-        },
-    );
-
-    if operation_type == OperationType::View {
-        return args; // View doesnt need any arguments
-    }
-
+) -> Vec<&Column> {
     // Sort the columns so required options come first
     let mut sort_idx = vec![];
 
     for (idx, column) in config_opt.columns.iter().enumerate() {
-        if operation_type == OperationType::Delete && !column.primary_key {
-            continue; // Skip if not the primary key
+        if operation_type == OperationType::Delete && !column.primary_key && !column.alternate_key {
+            continue; // Skip if not the primary key or alternate key
         }
 
-        if !is_column_required_for_operation_type(column, operation_type) {
+        if operation_type == OperationType::View && !column.primary_key && !column.indexed {
+            continue; // Only the primary key and indexed columns can be used as filters
+        }
+
+        if !config_opt.is_column_required_for_operation_type(column, operation_type) {
             sort_idx.push(idx);
         } else {
             sort_idx.insert(0, idx);
         }
     }
 
-    for idx in sort_idx {
-        let column = &config_opt.columns[idx];
+    sort_idx
+        .into_iter()
+        .map(|idx| &config_opt.columns[idx])
+        .filter(|column| {
+            // Check if we should ignore this column
+            !column.ignored_for.contains(&operation_type)
+                // Skip columns not exposed on commands (e.g. dashboard-only columns)
+                && column.is_exposed_on(Surface::Command)
+                // Filter-only columns are provided by the caller, never by the user
+                && !column.filter_only
+        })
+        .collect()
+}
+
+/// Converts a setting or column id into the name of the command, subcommand group or option generated for it
+///
+/// Discord requires names to be 1-32 lowercase letters, numbers, `-`, `_` or `'`. The id is lowercased, every
+/// other character (e.g. a space or `.`) is replaced with `_` and the result is cut to 32 characters, so a
+/// setting `Auto Mod.Rules` is invoked as `/auto_mod_rules`. Ids that are already valid names are used
+/// unchanged
+pub fn command_name(id: &str) -> String {
+    let name = id
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| if is_command_name_char(c) { c } else { '_' })
+        .take(32)
+        .collect::<String>();
+
+    if name.is_empty() {
+        "_".to_string()
+    } else {
+        name
+    }
+}
+
+/// Returns whether `c` may appear in a command name, these are the letters, numbers, `-`, `_` and `'` allowed
+/// by Discord except for uppercase letters
+///
+/// Both `command_name` and `is_valid_command_name` use this so that generated names are always valid
+fn is_command_name_char(c: char) -> bool {
+    (c.is_alphanumeric() || matches!(c, '-' | '_' | '\'')) && !c.is_uppercase()
+}
+
+/// Returns whether `name` is a valid name for a chat input command, subcommand group, subcommand or option
+///
+/// Discord requires 1-32 characters that are letters, numbers, `-`, `_` or `'`, and letters with a
+/// lowercase variant must be lowercase
+fn is_valid_command_name(name: &str) -> bool {
+    let len = name.chars().count();
+
+    (1..=32).contains(&len) && name.chars().all(is_command_name_char)
+}
+
+/// Checks a name and its localizations, `what` describes the name in the error (e.g. "setting `foo`")
+fn validate_command_name<'b>(
+    what: &str,
+    name: &str,
+    localizations: impl Iterator<Item = (&'b String, &'b String)>,
+) -> Result<(), crate::Error> {
+    if !is_valid_command_name(name) {
+        return Err(format!(
+            "{}: `{}` is not a valid command name, names must be 1-32 lowercase letters, numbers, `-`, `_` or `'`",
+            what, name
+        )
+        .into());
+    }
 
-        // Check if we should ignore this column
-        if column.ignored_for.contains(&operation_type) {
+    for (locale, name) in localizations {
+        if !is_valid_command_name(name) {
+            return Err(format!(
+                "{}: `{}` (locale {}) is not a valid command name, names must be 1-32 lowercase letters, numbers, `-`, `_` or `'`",
+                what, name, locale
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the commands generated for a setting are accepted by Discord
+///
+/// Discord only rejects invalid commands when they are registered, this catches localized names not matching
+/// Discord's naming rules, ids whose sanitized names (see `command_name`) collide, empty descriptions and
+/// subcommands with more than 25 options. Settings always produce at most two levels of nesting below the
+/// command (subcommand group and subcommand), which is Discord's limit
+pub fn validate_command_limits<Data: Clone>(setting: &Setting<Data>) -> Result<(), crate::Error> {
+    let what = format!("Setting `{}`", setting.id);
+
+    validate_command_name(
+        &what,
+        &command_name(&setting.id),
+        setting.name_localizations.iter(),
+    )?;
+
+    if setting.description.trim().is_empty() {
+        return Err(format!("{}: description must not be empty", what).into());
+    }
+
+    for (operation_type, subcommand) in [
+        (OperationType::View, setting.operations.view.is_some()),
+        (OperationType::Create, setting.operations.create.is_some()),
+        (OperationType::Update, setting.operations.update.is_some()),
+        (OperationType::Delete, setting.operations.delete.is_some()),
+    ] {
+        if !subcommand {
             continue;
         }
 
+        let columns = command_option_columns(setting, operation_type);
+
+        let mut options = columns.len();
+        if operation_type == OperationType::View && create_sort_by_option(setting).is_some() {
+            options += 1;
+        }
+
+        if options > 25 {
+            return Err(format!(
+                "{}: the {} subcommand has {} options but at most 25 are allowed",
+                what,
+                operation_type.command_name(),
+                options
+            )
+            .into());
+        }
+
+        // Distinct ids may sanitize to the same option name, the view subcommand also has the sort option
+        let mut option_names = std::collections::HashSet::new();
+        if operation_type == OperationType::View && create_sort_by_option(setting).is_some() {
+            option_names.insert(SORT_BY_OPTION.to_string());
+        }
+
+        for column in columns {
+            let what = format!("{}: column `{}`", what, column.id);
+            let option_name = command_name(&column.id);
+
+            validate_command_name(&what, &option_name, column.name_localizations.iter())?;
+
+            if !option_names.insert(option_name.clone()) {
+                return Err(format!(
+                    "{}: the option name `{}` is used more than once in the {} subcommand",
+                    what,
+                    option_name,
+                    operation_type.command_name()
+                )
+                .into());
+            }
+
+            if column.description.trim().is_empty() {
+                return Err(format!("{}: description must not be empty", what).into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn create_command_for_operation_type<'a, Data: Clone>(
+    config_opt: &Setting<Data>,
+    operation_type: OperationType,
+) -> serenity::all::CreateCommandOption<'a> {
+    let description = match config_opt.deprecated_operations.get(&operation_type) {
+        Some(note) => format!("(Deprecated: {}) {}", note, config_opt.description),
+        None => config_opt.description.to_string(),
+    };
+
+    let mut args = serenity::all::CreateCommandOption::new(
+        serenity::all::CommandOptionType::SubCommand,
+        operation_type.command_name(),
+        truncate_with_ellipsis(&description, 100),
+    );
+
+    for (locale, description) in config_opt.description_localizations.iter() {
+        let description = match config_opt.deprecated_operations.get(&operation_type) {
+            Some(note) => format!("(Deprecated: {}) {}", note, description),
+            None => description.to_string(),
+        };
+
+        args = args.description_localized(
+            locale.to_string(),
+            truncate_with_ellipsis(&description, 100),
+        );
+    }
+
+    for column in command_option_columns(config_opt, operation_type) {
+        let description = column.description_with_deprecation();
+
         // Add the new command parameter
         let arg = serenity::all::CreateCommandOption::new(
             {
                 match column.column_type {
                     ColumnType::Scalar { ref inner } => {
                         match inner {
-                            InnerColumnType::Integer {} => {
+                            InnerColumnType::Integer { .. } => {
                                 serenity::all::CommandOptionType::Integer
                             }
-                            InnerColumnType::Float {} => serenity::all::CommandOptionType::Number,
-                            InnerColumnType::Boolean {} => {
+                            InnerColumnType::Float { .. } => {
+                                serenity::all::CommandOptionType::Number
+                            }
+                            InnerColumnType::Boolean { .. } => {
                                 serenity::all::CommandOptionType::Boolean
                             }
                             InnerColumnType::String { kind, .. } => match kind.as_str() {
@@ -707,20 +1661,77 @@ fn create_command_for_operation_type<'a, Data: Clone>(
                     _ => serenity::all::CommandOptionType::String,
                 }
             },
-            column.id.to_string(),
-            {
-                if column.description.len() > 100 {
-                    column.description[..97].to_string() + "..."
-                } else {
-                    column.description.to_string()
-                }
-            },
+            command_name(&column.id),
+            truncate_with_ellipsis(&description, 100),
         )
-        .required(is_column_required_for_operation_type(
-            column,
-            operation_type,
-        ))
-        .set_autocomplete(field_supports_autocomplete(column));
+        .required(config_opt.is_column_required_for_operation_type(column, operation_type))
+        .set_autocomplete(field_supports_autocomplete(config_opt, column));
+
+        // Restrict the channel types that can be picked for channel columns
+        let arg = match column.column_type {
+            ColumnType::Scalar {
+                inner:
+                    InnerColumnType::String {
+                        ref kind,
+                        ref channel_types,
+                        ..
+                    },
+            } if kind == "channel" && !channel_types.is_empty() => arg.channel_types(
+                CHANNEL_TYPES
+                    .into_iter()
+                    .filter(|t| channel_types.iter().any(|name| name == t.name()))
+                    .collect::<Vec<_>>()
+                    .into(),
+            ),
+            _ => arg,
+        };
+
+        // Add localized names/descriptions, missing locales fall back to the above
+        let mut arg = arg;
+        for (locale, name) in column.name_localizations.iter() {
+            arg = arg.name_localized(locale.to_string(), name.to_string());
+        }
+
+        for (locale, description) in column.description_localizations.iter() {
+            let description = match column.deprecated {
+                Some(ref note) => format!("(Deprecated: {}) {}", note, description),
+                None => description.to_string(),
+            };
+
+            arg = arg.description_localized(
+                locale.to_string(),
+                truncate_with_ellipsis(&description, 100),
+            );
+        }
+
+        // Add numeric bounds so Discord can reject out of range input client-side
+        let arg = match column.column_type {
+            ColumnType::Scalar {
+                inner: InnerColumnType::Integer { min, max, .. },
+            } => {
+                let mut arg = arg;
+                if let Some(min) = min {
+                    arg = arg.min_int_value(min);
+                }
+                if let Some(max) = max {
+                    arg = arg.max_int_value(max);
+                }
+                arg
+            }
+            ColumnType::Scalar {
+                inner: InnerColumnType::Float { min, max, .. },
+            } => {
+                let mut arg = arg;
+                if let Some(min) = min {
+                    arg = arg.min_number_value(min);
+                }
+                if let Some(max) = max {
+                    arg = arg.max_number_value(max);
+                }
+                arg
+            }
+            _ => arg,
+        };
 
         // add string choice
         let arg = match get_string_choices_for_column(column) {
@@ -734,23 +1745,631 @@ fn create_command_for_operation_type<'a, Data: Clone>(
             None => arg,
         };
 
+        // add numeric choices
+        let arg = match column.column_type {
+            ColumnType::Scalar {
+                inner:
+                    InnerColumnType::Integer {
+                        ref allowed_values, ..
+                    },
+            } if !allowed_values.is_empty() && allowed_values.len() <= 25 => {
+                let mut arg = arg;
+                for choice in allowed_values {
+                    arg = arg.add_int_choice(choice.to_string(), *choice);
+                }
+                arg
+            }
+            ColumnType::Scalar {
+                inner:
+                    InnerColumnType::Float {
+                        ref allowed_values, ..
+                    },
+            } if !allowed_values.is_empty() && allowed_values.len() <= 25 => {
+                let mut arg = arg;
+                for choice in allowed_values {
+                    arg = arg.add_number_choice(choice.to_string(), *choice);
+                }
+                arg
+            }
+            _ => arg,
+        };
+
         args = args.add_sub_option(arg);
     }
 
+    if operation_type == OperationType::View {
+        if let Some(arg) = create_sort_by_option(config_opt) {
+            args = args.add_sub_option(arg);
+        }
+    }
+
     args
 }
 
-fn field_supports_autocomplete(field: &Column) -> bool {
+/// Creates the `sort_by` option of the view subcommand from the sortable columns of the setting
+///
+/// Returns None if no column is sortable or if a column is itself named `sort_by`
+fn create_sort_by_option<'a, Data: Clone>(
+    config_opt: &Setting<Data>,
+) -> Option<serenity::all::CreateCommandOption<'a>> {
+    if config_opt.column(SORT_BY_OPTION).is_some() {
+        return None;
+    }
+
+    let columns = config_opt
+        .columns
+        .iter()
+        .filter(|c| {
+            c.is_sortable()
+                && !c.ignored_for.contains(&OperationType::View)
+                && c.is_exposed_on(Surface::Command)
+        })
+        .collect::<Vec<_>>();
+
+    if columns.is_empty() {
+        return None;
+    }
+
+    let mut arg = serenity::all::CreateCommandOption::new(
+        serenity::all::CommandOptionType::String,
+        SORT_BY_OPTION,
+        "The order in which entries are shown",
+    );
+
+    // Discord allows at most 25 choices per option
+    for column in columns.into_iter().take(12) {
+        arg = arg
+            .add_string_choice(
+                truncate_with_ellipsis(&format!("{} (ascending)", column.name), 100),
+                column.id.to_string(),
+            )
+            .add_string_choice(
+                truncate_with_ellipsis(&format!("{} (descending)", column.name), 100),
+                format!("-{}", column.id),
+            );
+    }
+
+    Some(arg)
+}
+
+fn field_supports_autocomplete<Data: Clone>(setting: &Setting<Data>, field: &Column) -> bool {
+    let static_autocomplete = match &field.column_type {
+        ColumnType::Scalar { ref inner } => inner.allowed_values().len() > 25,
+        ColumnType::Array { inner, .. } => !inner.allowed_values().is_empty(), // Arrays do benefit from autocomplete
+    };
+
+    // Static suggestions are served the same way as allowed values, dynamic ones come from the autocompleter
+    static_autocomplete
+        || (supports_dynamic_autocomplete(field)
+            && (setting.operations.autocompleter.is_some()
+                || !field.suggestions.static_suggestions().is_empty()))
+}
+
+/// Returns whether the option of the column can use a dynamic autocomplete provider
+///
+/// Options with static choices cannot also autocomplete, and channel/user/role options use Discord's own
+/// pickers
+fn supports_dynamic_autocomplete(field: &Column) -> bool {
     match &field.column_type {
         ColumnType::Scalar { ref inner } => match inner {
-            InnerColumnType::String { allowed_values, .. } => allowed_values.len() > 25,
+            InnerColumnType::String {
+                kind,
+                allowed_values,
+                ..
+            } => !matches!(kind.as_str(), "channel" | "user" | "role") && allowed_values.is_empty(),
+            InnerColumnType::Integer { allowed_values, .. } => allowed_values.is_empty(),
+            InnerColumnType::Float { allowed_values, .. } => allowed_values.is_empty(),
             _ => false,
         },
-        ColumnType::Array { inner } => {
-            match inner {
-                InnerColumnType::String { allowed_values, .. } => !allowed_values.is_empty(), // Arrays do benefit from autocomplete
-                _ => false,
+        ColumnType::Array { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ColumnBuilder, SettingBuilder};
+    use crate::cfg::{settings_create, settings_delete, validate_value};
+    use crate::common_columns;
+    use crate::memory::InMemorySettings;
+    use crate::types::SettingOperations;
+    use serde_json::json;
+
+    fn string_column(id: &str) -> Column {
+        ColumnBuilder::new(
+            id,
+            id,
+            ColumnType::new_scalar(InnerColumnType::String {
+                min_length: None,
+                max_length: None,
+                allowed_values: vec![],
+                kind: "normal".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            }),
+        )
+        .description(format!("The {}", id))
+        .build()
+    }
+
+    /// A setting keyed by `guild_id` and `name` with a secret `token` and a `version` column
+    fn setting() -> Setting<()> {
+        let mut guild_id = string_column("guild_id");
+        guild_id.primary_key = true;
+
+        let mut name = string_column("name");
+        name.primary_key = true;
+
+        let mut token = string_column("token");
+        token.secret = true;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([guild_id, name, string_column("value"), token])
+            .column(common_columns::integer("version", "Version", "The version"))
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "guild_id".to_string(),
+                "name".to_string(),
+            ])))
+            .version_column("version")
+            .build()
+            .unwrap()
+    }
+
+    fn entry(guild_id: &str, name: &str, value: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "guild_id".to_string() => json!(guild_id),
+            "name".to_string() => json!(name),
+            "value".to_string() => json!(value),
+        }
+    }
+
+    fn pkey(guild_id: &str, name: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "guild_id".to_string() => json!(guild_id),
+            "name".to_string() => json!(name),
+        }
+    }
+
+    #[test]
+    fn find_entry_requires_all_primary_key_values() {
+        // Every entry shares a primary key value with the ones being looked for
+        let entries = vec![
+            entry("1", "b", "first"),
+            entry("2", "a", "second"),
+            entry("1", "a", "third"),
+            entry("2", "b", "fourth"),
+        ];
+
+        assert_eq!(
+            find_entry_by_primary_key(entries.clone(), &pkey("1", "a")),
+            Some(entry("1", "a", "third"))
+        );
+        assert_eq!(
+            find_entry_by_primary_key(entries.clone(), &pkey("2", "b")),
+            Some(entry("2", "b", "fourth"))
+        );
+        assert_eq!(find_entry_by_primary_key(entries, &pkey("3", "a")), None);
+    }
+
+    #[test]
+    fn find_entry_rejects_partial_matches() {
+        let entries = vec![entry("1", "b", "first"), entry("2", "a", "second")];
+
+        assert_eq!(find_entry_by_primary_key(entries, &pkey("1", "a")), None);
+        assert_eq!(find_entry_by_primary_key(vec![], &pkey("1", "a")), None);
+    }
+
+    #[test]
+    fn autofill_fills_missing_fields_only() {
+        let setting = setting();
+
+        let mut existing = entry("1", "a", "old");
+        existing.insert("token".to_string(), json!("••••"));
+        existing.insert("version".to_string(), json!(4));
+
+        let mut update = pkey("1", "a");
+        update.insert("version".to_string(), json!(3));
+        autofill_update_entry(&setting, &mut update, existing.clone());
+
+        assert_eq!(update.get("value"), Some(&json!("old")));
+        assert_eq!(update.get("version"), Some(&json!(3)));
+        assert!(!update.contains_key("token"));
+
+        let mut update = entry("1", "a", "new");
+        autofill_update_entry(&setting, &mut update, existing);
+
+        assert_eq!(update.get("value"), Some(&json!("new")));
+        // The version is never taken from the current entry
+        assert!(!update.contains_key("version"));
+    }
+
+    /// A setting with an `id` primary key and `columns` more (non-nullable) columns
+    fn setting_with_columns(columns: usize) -> Setting<()> {
+        let mut id = string_column("id");
+        id.primary_key = true;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .column(id)
+            .columns((0..columns).map(|idx| string_column(&format!("column_{}", idx))))
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "id".to_string()
+            ])))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn command_limits_allow_25_options() {
+        let setting = setting_with_columns(24);
+
+        assert!(validate_command_limits(&setting).is_ok());
+        assert!(try_create_commands_from_setting(&setting).is_ok());
+    }
+
+    #[test]
+    fn command_limits_reject_over_limit_setting() {
+        let setting = setting_with_columns(25);
+
+        let err = validate_command_limits(&setting).unwrap_err().to_string();
+        assert!(
+            err.contains("the create subcommand has 26 options"),
+            "{}",
+            err
+        );
+
+        assert!(try_create_commands_from_setting(&setting).is_err());
+        assert!(try_create_commands_from_setting_with_root(
+            &setting,
+            serenity::all::CreateCommand::new("root").description("Root")
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 25 are allowed")]
+    fn create_commands_panics_for_over_limit_setting() {
+        create_commands_from_setting(&setting_with_columns(25));
+    }
+
+    #[test]
+    fn command_name_is_always_valid() {
+        for id in [
+            "mod_log",
+            "Auto Mod.Rules",
+            "it's",
+            "",
+            "İstanbul",
+            "ǅemal",
+            "Straße",
+            "🎉 party",
+            // Uppercase without a lowercase variant
+            "𝐀lpha",
+            "a_very_long_setting_id_that_is_longer_than_32_chars",
+        ] {
+            let name = command_name(id);
+            assert!(is_valid_command_name(&name), "{:?} -> {:?}", id, name);
+        }
+
+        assert_eq!(command_name("Auto Mod.Rules"), "auto_mod_rules");
+        assert_eq!(command_name("it's"), "it's");
+        assert_eq!(command_name(""), "_");
+        assert_eq!(command_name("𝐀lpha"), "_lpha");
+        assert_eq!(command_name(&"a".repeat(40)), "a".repeat(32));
+    }
+
+    #[test]
+    fn invalid_command_names() {
+        for name in ["", "Upper", "has space", "dot.ted", &"a".repeat(33)] {
+            assert!(!is_valid_command_name(name), "{:?}", name);
+        }
+    }
+    #[tokio::test]
+    async fn identical_autocomplete_queries_resolve_once() {
+        let cache = AutocompleteCache::new(Duration::from_secs(60));
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        let resolve = || async {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            (vec![("A".to_string(), "a".to_string())], true)
+        };
+
+        let first =
+            cached_autocomplete_choices(Some(&cache), "1/test/name/a".to_string(), resolve).await;
+        let second =
+            cached_autocomplete_choices(Some(&cache), "1/test/name/a".to_string(), resolve).await;
+
+        assert_eq!(first, second);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A different query is resolved again
+        cached_autocomplete_choices(Some(&cache), "1/test/name/ab".to_string(), resolve).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn autocomplete_queries_resolve_every_time_without_caching() {
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        let resolve = |cacheable: bool| {
+            let calls = &calls;
+            move || async move {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                (vec![], cacheable)
             }
+        };
+
+        // No cache
+        for _ in 0..2 {
+            cached_autocomplete_choices(None, "key".to_string(), resolve(true)).await;
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Uncacheable results (e.g. provider errors)
+        let cache = AutocompleteCache::new(Duration::from_secs(60));
+        for _ in 0..2 {
+            cached_autocomplete_choices(Some(&cache), "key".to_string(), resolve(false)).await;
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+
+        // Expired entries
+        let cache = AutocompleteCache::new(Duration::ZERO);
+        for _ in 0..2 {
+            cached_autocomplete_choices(Some(&cache), "key".to_string(), resolve(true)).await;
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 6);
+    }
+    #[test]
+    fn dashboard_only_column_is_not_a_command_option() {
+        let mut layout = string_column("layout");
+        layout.nullable = true;
+        layout.surfaces = vec![Surface::Dashboard];
+
+        let mut setting = setting_with_columns(1);
+        let mut columns = setting.columns.to_vec();
+        columns.push(layout);
+        setting.columns = Arc::new(columns);
+
+        for operation_type in [OperationType::Create, OperationType::Update] {
+            let options = command_option_columns(&setting, operation_type)
+                .into_iter()
+                .map(|c| c.id.as_str())
+                .collect::<Vec<_>>();
+
+            assert_eq!(options.len(), 2);
+            assert!(!options.contains(&"layout"));
+        }
+    }
+    #[test]
+    fn filter_only_column_is_not_a_command_option() {
+        let mut guild_id = string_column("guild_id");
+        guild_id.primary_key = true;
+        guild_id.filter_only = true;
+
+        let mut setting = setting_with_columns(1);
+        let mut columns = setting.columns.to_vec();
+        columns.insert(0, guild_id);
+        setting.columns = Arc::new(columns);
+
+        for operation_type in [
+            OperationType::View,
+            OperationType::Create,
+            OperationType::Update,
+            OperationType::Delete,
+        ] {
+            assert!(!command_option_columns(&setting, operation_type)
+                .iter()
+                .any(|c| c.id == "guild_id"));
         }
     }
+    #[test]
+    fn parse_bool_list_parses_each_token() {
+        assert_eq!(
+            parse_bool_list("true,false,1,0", false).unwrap(),
+            [true, false, true, false]
+        );
+        assert_eq!(
+            parse_bool_list(" yes; NO  on,off ", false).unwrap(),
+            [true, false, true, false]
+        );
+        assert!(parse_bool_list("", false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_bool_list_rejects_invalid_tokens() {
+        let err = parse_bool_list("true,maybe", false).unwrap_err();
+        assert_eq!(err.to_string(), "Expected a boolean but got `maybe`");
+
+        // Strict columns only accept true/false
+        assert!(parse_bool_list("true,1", true).is_err());
+        assert_eq!(parse_bool_list("true,FALSE", true).unwrap(), [true, false]);
+    }
+    #[test]
+    fn deprecation_note_is_in_option_description() {
+        let mut old_name = string_column("old_name");
+        old_name.nullable = true;
+        old_name.deprecated = Some("use name instead".to_string());
+
+        let mut setting = setting_with_columns(1);
+        let mut columns = setting.columns.to_vec();
+        columns.push(old_name);
+        setting.columns = Arc::new(columns);
+
+        let subcommand = serde_json::to_value(create_command_for_operation_type(
+            &setting,
+            OperationType::Create,
+        ))
+        .unwrap();
+
+        let descriptions = subcommand["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|option| {
+                (
+                    option["name"].as_str().unwrap(),
+                    option["description"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert!(descriptions.contains(&("old_name", "(Deprecated: use name instead) The old_name")));
+        assert!(descriptions.contains(&("column_0", "The column_0")));
+    }
+
+    fn roles_column(array: bool) -> Column {
+        let inner = InnerColumnType::String {
+            min_length: None,
+            max_length: None,
+            allowed_values: vec![
+                "Admin".to_string(),
+                "Moderator".to_string(),
+                "Member".to_string(),
+            ],
+            kind: "normal".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
+        };
+
+        let column_type = if array {
+            ColumnType::new_array(inner)
+        } else {
+            ColumnType::new_scalar(inner)
+        };
+
+        ColumnBuilder::new("roles", "Roles", column_type).build()
+    }
+
+    fn choice_values(choices: Vec<(String, String)>) -> Vec<String> {
+        choices.into_iter().map(|(_, value)| value).collect()
+    }
+
+    #[test]
+    fn autocomplete_is_case_insensitive() {
+        let column = roles_column(false);
+
+        for input in ["ADMIN", "admin", "aDm"] {
+            assert_eq!(
+                choice_values(static_autocomplete_choices(&column, input)),
+                ["Admin"],
+                "{}",
+                input
+            );
+        }
+
+        // The original casing is kept, prefix matches come first
+        assert_eq!(
+            choice_values(static_autocomplete_choices(&column, "M")),
+            ["Member", "Moderator", "Admin"]
+        );
+    }
+
+    #[test]
+    fn array_autocomplete_continues_after_comma() {
+        let column = roles_column(true);
+
+        assert_eq!(
+            choice_values(static_autocomplete_choices(&column, "Member,ADM")),
+            ["Member,Admin"]
+        );
+
+        // Whitespace around items is ignored
+        assert_eq!(
+            choice_values(static_autocomplete_choices(&column, "Member , mod")),
+            ["Member,Moderator"]
+        );
+
+        // A trailing comma offers every value as the next item
+        assert_eq!(
+            choice_values(static_autocomplete_choices(&column, "Member,")),
+            ["Member,Admin", "Member,Moderator", "Member,Member"]
+        );
+    }
+
+    /// A setting with an `id` primary key and the given roles column
+    fn roles_setting(roles: Column) -> Setting<()> {
+        let mut id = common_columns::integer("id", "ID", "The ID");
+        id.primary_key = true;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id, roles])
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "id".to_string()
+            ])))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn autocomplete_choices_pass_validation() {
+        for array in [false, true] {
+            let column = roles_column(array);
+            let setting = roles_setting(column.clone());
+
+            let inputs = if array {
+                ["Member,ADM", "Member,", "mod"]
+            } else {
+                ["ADM", "M", "mod"]
+            };
+
+            for input in inputs {
+                for value in choice_values(static_autocomplete_choices(&column, input)) {
+                    // The choice is submitted as the option value, see `serenity_resolvedvalue_to_value`
+                    let roles = if array {
+                        Value::Array(
+                            split_input_to_string(&value, ",")
+                                .into_iter()
+                                .map(Value::String)
+                                .collect(),
+                        )
+                    } else {
+                        Value::String(value.clone())
+                    };
+
+                    let entry = indexmap::indexmap! {
+                        "id".to_string() => json!(1),
+                        "roles".to_string() => roles.clone(),
+                    };
+                    let created = settings_create(&setting, &(), entry, None).await;
+                    assert_eq!(created.unwrap()["roles"], roles, "{}", value);
+
+                    settings_delete(
+                        &setting,
+                        &(),
+                        indexmap::indexmap! { "id".to_string() => json!(1) },
+                        None,
+                    )
+                    .await
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_autocomplete_choice_fails_validation() {
+        let column = roles_column(false);
+        let choices = choice_values(static_autocomplete_choices(&column, "mod"));
+        assert_eq!(choices, ["Moderator"]);
+
+        // The allowed values changed after the choices were offered
+        let mut updated = column.clone();
+        if let ColumnType::Scalar {
+            inner:
+                InnerColumnType::String {
+                    ref mut allowed_values,
+                    ..
+                },
+        } = updated.column_type
+        {
+            allowed_values.retain(|v| v != "Moderator");
+        }
+        let setting = roles_setting(updated.clone());
+
+        let entry = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "roles".to_string() => json!(choices[0]),
+        };
+        assert!(settings_create(&setting, &(), entry, None).await.is_err());
+        assert!(validate_value(json!(choices[0]), &updated.column_type, "roles", false).is_err());
+    }
 }
@@ -1,25 +1,70 @@
-use crate::cfg::{settings_create, settings_delete, settings_update, settings_view};
-use crate::types::{ColumnType, InnerColumnType, Setting};
+use crate::cfg::{
+    edited_entry, settings_check_guild_id, settings_count, settings_create, settings_delete,
+    settings_update, settings_view_one, settings_view_page,
+};
+use crate::form::{parse_form_input, FormState};
+use crate::types::{
+    Column, ColumnSuggestion, ColumnType, InnerColumnType, OperationType, Setting, SortBy, Surface,
+    FIELD_VALUE_LIMIT,
+};
 use serde_json::Value;
 use serenity::all::CreateMessage;
 use serenity::futures::StreamExt;
 use std::time::Duration;
 
-fn _get_display_value(column_type: &ColumnType, value: &Value) -> String {
+/// Truncates a string to at most `max` characters, this never splits a character unlike byte slicing
+fn truncate_chars(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
+    }
+}
+
+/// Truncates `s` to at most `max` characters (not bytes, as Discord counts them), replacing the end with
+/// "..." if it had to be cut
+pub(crate) fn truncate_with_ellipsis(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+
+    if max < 3 {
+        return truncate_chars(s, max).to_string();
+    }
+
+    format!("{}...", truncate_chars(s, max - 3))
+}
+
+/// Wraps `v` in a code block of at most `max_length` chars, truncating its contents as needed
+///
+/// The fences count towards the limit so the closing fence is never cut off, and a backslash separated from
+/// the backtick it escaped by the truncation is dropped
+fn truncated_code_block(v: &str, max_length: usize) -> String {
+    // "```\n" + "...```"
+    let content = truncate_chars(v, max_length.saturating_sub(10));
+    let content = content.trim_end_matches('\\');
+
+    format!("```\n{}...```", content)
+}
+
+fn _get_display_value(column_type: &ColumnType, value: &Value, max_length: usize) -> String {
     match column_type {
         ColumnType::Scalar { inner } => match inner {
             InnerColumnType::String { kind, .. } => match kind.as_str() {
                 "channel" => format!("<#{}>", value.as_str().unwrap_or(&value.to_string())),
                 "role" => format!("<@&{}>", value.as_str().unwrap_or(&value.to_string())),
                 "user" => format!("<@{}>", value.as_str().unwrap_or(&value.to_string())),
+                "url" => {
+                    let v = value.as_str().unwrap_or(&value.to_string()).to_string();
+                    format!("[{}]({})", v, v)
+                }
                 _ => {
                     let v = value
                         .as_str()
                         .unwrap_or(&value.to_string())
                         .replace("`", "\\`");
 
-                    if v.len() > 1024 {
-                        format!("```{}```", &v[..1021])
+                    if v.chars().count() > max_length {
+                        truncated_code_block(&v, max_length)
                     } else if v.contains('\n') {
                         format!("```\n{}```", v)
                     } else {
@@ -27,7 +72,7 @@ fn _get_display_value(column_type: &ColumnType, value: &Value) -> String {
                     }
                 }
             },
-            InnerColumnType::BitFlag { values } => {
+            InnerColumnType::BitFlag { values, .. } => {
                 let v = match value {
                     Value::Number(v) => {
                         if let Some(v) = v.as_i64() {
@@ -56,15 +101,28 @@ fn _get_display_value(column_type: &ColumnType, value: &Value) -> String {
             }
             _ => value.to_string(),
         },
-        ColumnType::Array { inner } => {
+        ColumnType::Array { inner, .. } => {
             // Then the value must also be an array, check that or fallback to scalar _get_display_value
             match value {
                 Value::Array(values) => values
                     .iter()
-                    .map(|v| _get_display_value(&ColumnType::new_scalar(inner.clone()), v))
+                    .map(|v| {
+                        let display = _get_display_value(
+                            &ColumnType::new_scalar(inner.clone()),
+                            v,
+                            max_length,
+                        );
+
+                        // Each bitflag element may itself render as a list of flags
+                        if matches!(inner, InnerColumnType::BitFlag { .. }) {
+                            format!("[{}]", display)
+                        } else {
+                            display
+                        }
+                    })
                     .collect::<Vec<String>>()
                     .join(", "),
-                _ => _get_display_value(&ColumnType::new_scalar(inner.clone()), value),
+                _ => _get_display_value(&ColumnType::new_scalar(inner.clone()), value, max_length),
             }
         }
     }
@@ -85,6 +143,22 @@ pub enum Src<'a> {
             serenity::all::UserId,
         ),
     ),
+    /// A component interaction (e.g. a button press), responses replace the message the component is on
+    Component(
+        (
+            &'a serenity::all::ComponentInteraction,
+            &'a serenity::all::Context,
+            serenity::all::UserId,
+        ),
+    ),
+    /// A modal submission, responses are sent as a new (ephemeral) message
+    Modal(
+        (
+            &'a serenity::all::ModalInteraction,
+            &'a serenity::all::Context,
+            serenity::all::UserId,
+        ),
+    ),
 }
 
 pub enum SrcResponse<'a> {
@@ -95,6 +169,18 @@ pub enum SrcResponse<'a> {
             &'a serenity::all::Context,
         ),
     ),
+    Component(
+        (
+            &'a serenity::all::ComponentInteraction,
+            &'a serenity::all::Context,
+        ),
+    ),
+    Modal(
+        (
+            &'a serenity::all::ModalInteraction,
+            &'a serenity::all::Context,
+        ),
+    ),
 }
 
 impl<'a> SrcResponse<'a> {
@@ -102,6 +188,8 @@ impl<'a> SrcResponse<'a> {
         match self {
             Self::Message((_, ctx)) => ctx,
             Self::Interaction((_, ctx)) => ctx,
+            Self::Component((_, ctx)) => ctx,
+            Self::Modal((_, ctx)) => ctx,
         }
     }
 
@@ -113,7 +201,108 @@ impl<'a> SrcResponse<'a> {
 
                 Ok(msg)
             }
+            Self::Component((i, ctx)) => {
+                let msg = i.get_response(&ctx.http).await?;
+
+                Ok(msg)
+            }
+            Self::Modal((i, ctx)) => {
+                let msg = i.get_response(&ctx.http).await?;
+
+                Ok(msg)
+            }
+        }
+    }
+
+    /// Edits the response, replacing its embed and components
+    pub async fn edit(
+        &self,
+        embed: serenity::all::CreateEmbed<'_>,
+        components: Vec<serenity::all::CreateActionRow<'_>>,
+    ) -> Result<(), crate::Error> {
+        match self {
+            Self::Message((msg, ctx)) => {
+                msg.channel_id
+                    .edit_message(
+                        &ctx.http,
+                        msg.id,
+                        serenity::all::EditMessage::new()
+                            .embed(embed)
+                            .components(components),
+                    )
+                    .await?;
+            }
+            Self::Interaction((i, ctx)) => {
+                i.edit_response(
+                    &ctx.http,
+                    serenity::all::EditInteractionResponse::new()
+                        .embed(embed)
+                        .components(components),
+                )
+                .await?;
+            }
+            Self::Component((i, ctx)) => {
+                i.edit_response(
+                    &ctx.http,
+                    serenity::all::EditInteractionResponse::new()
+                        .embed(embed)
+                        .components(components),
+                )
+                .await?;
+            }
+            Self::Modal((i, ctx)) => {
+                i.edit_response(
+                    &ctx.http,
+                    serenity::all::EditInteractionResponse::new()
+                        .embed(embed)
+                        .components(components),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Edits the response, replacing only its components
+    pub async fn edit_components(
+        &self,
+        components: Vec<serenity::all::CreateActionRow<'_>>,
+    ) -> Result<(), crate::Error> {
+        match self {
+            Self::Message((msg, ctx)) => {
+                msg.channel_id
+                    .edit_message(
+                        &ctx.http,
+                        msg.id,
+                        serenity::all::EditMessage::new().components(components),
+                    )
+                    .await?;
+            }
+            Self::Interaction((i, ctx)) => {
+                i.edit_response(
+                    &ctx.http,
+                    serenity::all::EditInteractionResponse::new().components(components),
+                )
+                .await?;
+            }
+            Self::Component((i, ctx)) => {
+                i.edit_response(
+                    &ctx.http,
+                    serenity::all::EditInteractionResponse::new().components(components),
+                )
+                .await?;
+            }
+            Self::Modal((i, ctx)) => {
+                i.edit_response(
+                    &ctx.http,
+                    serenity::all::EditInteractionResponse::new().components(components),
+                )
+                .await?;
+            }
         }
+
+        Ok(())
     }
 }
 
@@ -122,6 +311,8 @@ impl<'a> Src<'a> {
         match self {
             Self::Interaction((_, ctx, _)) => ctx,
             Self::Message((_, ctx, _)) => ctx,
+            Self::Component((_, ctx, _)) => ctx,
+            Self::Modal((_, ctx, _)) => ctx,
         }
     }
 
@@ -129,28 +320,61 @@ impl<'a> Src<'a> {
         match self {
             Self::Interaction((_, _, author)) => *author,
             Self::Message((_, _, author)) => *author,
+            Self::Component((_, _, author)) => *author,
+            Self::Modal((_, _, author)) => *author,
+        }
+    }
+
+    pub fn guild_id(&self) -> Option<serenity::all::GuildId> {
+        match self {
+            Self::Interaction((interaction, _, _)) => interaction.guild_id,
+            Self::Message((message, _, _)) => message.guild_id,
+            Self::Component((interaction, _, _)) => interaction.guild_id,
+            Self::Modal((interaction, _, _)) => interaction.guild_id,
         }
     }
 
+    /// Sends a user-visible error embed as the initial response
+    ///
+    /// UI functions report user-facing errors (unsupported operations, validation errors, errors from the
+    /// executor) through this instead of returning them, so the user sees them regardless of the source
+    pub async fn send_error(&self, message: &str) -> Result<(), crate::Error> {
+        self.send_initial_response(
+            serenity::all::CreateEmbed::new()
+                .title("Error")
+                .description(truncate_chars(message, 4096).to_string())
+                .color(serenity::all::Colour::RED),
+            None,
+        )
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn send_initial_response(
         &self,
         embed: serenity::all::CreateEmbed<'a>,
         action_row: Option<serenity::all::CreateActionRow<'a>>,
+    ) -> Result<SrcResponse<'a>, crate::Error> {
+        self.send_initial_response_with_components(embed, action_row.into_iter().collect())
+            .await
+    }
+
+    /// Same as `send_initial_response` but allows sending multiple action rows
+    pub async fn send_initial_response_with_components(
+        &self,
+        embed: serenity::all::CreateEmbed<'a>,
+        components: Vec<serenity::all::CreateActionRow<'a>>,
     ) -> Result<SrcResponse<'a>, crate::Error> {
         match self {
             Self::Interaction((interaction, ctx, _)) => {
                 interaction
                     .create_response(&ctx.http, {
                         let cir = serenity::all::CreateInteractionResponse::Message({
-                            let mut cir = serenity::all::CreateInteractionResponseMessage::new()
+                            serenity::all::CreateInteractionResponseMessage::new()
                                 .ephemeral(true)
-                                .embed(embed);
-
-                            if let Some(action_row) = action_row {
-                                cir = cir.components(vec![action_row]);
-                            }
-
-                            cir
+                                .embed(embed)
+                                .components(components)
                         });
 
                         cir
@@ -163,25 +387,179 @@ impl<'a> Src<'a> {
                 let msg = message
                     .channel_id
                     .send_message(&ctx.http, {
-                        let mut cim = CreateMessage::new().embed(embed);
-
-                        if let Some(action_row) = action_row {
-                            cim = cim.components(vec![action_row]);
-                        }
-
-                        cim
+                        CreateMessage::new().embed(embed).components(components)
                     })
                     .await?;
 
                 Ok(SrcResponse::Message((msg, ctx)))
             }
+            Self::Component((interaction, ctx, _)) => {
+                interaction
+                    .create_response(
+                        &ctx.http,
+                        serenity::all::CreateInteractionResponse::UpdateMessage({
+                            serenity::all::CreateInteractionResponseMessage::new()
+                                .embed(embed)
+                                .components(components)
+                        }),
+                    )
+                    .await?;
+
+                Ok(SrcResponse::Component((interaction, ctx)))
+            }
+            Self::Modal((interaction, ctx, _)) => {
+                interaction
+                    .create_response(
+                        &ctx.http,
+                        serenity::all::CreateInteractionResponse::Message({
+                            serenity::all::CreateInteractionResponseMessage::new()
+                                .ephemeral(true)
+                                .embed(embed)
+                                .components(components)
+                        }),
+                    )
+                    .await?;
+
+                Ok(SrcResponse::Modal((interaction, ctx)))
+            }
+        }
+    }
+}
+
+/// What to do with an action after its confirmation prompt (see `confirm`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfirmOutcome {
+    /// The user pressed Confirm, the action should be executed
+    Execute,
+
+    /// The user pressed Cancel (or any other button), the action is aborted
+    Cancel,
+
+    /// No button was pressed in time, the action is aborted
+    Timeout,
+}
+
+/// Decides the outcome of a confirmation prompt from the custom id of the pressed button, None if no button
+/// was pressed before the timeout
+fn confirm_outcome(custom_id: Option<&str>) -> ConfirmOutcome {
+    match custom_id {
+        Some("confirm") => ConfirmOutcome::Execute,
+        Some(_) => ConfirmOutcome::Cancel,
+        None => ConfirmOutcome::Timeout,
+    }
+}
+
+/// Asks the user to confirm an action with Confirm/Cancel buttons
+///
+/// The prompt is sent as the initial response of `src`. If the user confirms, the button interaction is
+/// returned and should be used (through `Src::Component`) to respond with the result of the action.
+/// On cancel or timeout, the prompt is edited to say that nothing was changed and None is returned
+pub async fn confirm(
+    src: &Src<'_>,
+    summary: String,
+) -> Result<Option<serenity::all::ComponentInteraction>, crate::Error> {
+    let response = src
+        .send_initial_response(
+            serenity::all::CreateEmbed::new()
+                .title("Are you sure?")
+                .description(summary.chars().take(4096).collect::<String>()),
+            Some(serenity::all::CreateActionRow::Buttons(
+                vec![
+                    serenity::all::CreateButton::new("confirm")
+                        .style(serenity::all::ButtonStyle::Danger)
+                        .label("Confirm"),
+                    serenity::all::CreateButton::new("cancel")
+                        .style(serenity::all::ButtonStyle::Secondary)
+                        .label("Cancel"),
+                ]
+                .into(),
+            )),
+        )
+        .await?;
+
+    let msg = response.into_message().await?;
+
+    let item = msg
+        .id
+        .await_component_interactions(src.ctx().shard.clone())
+        .author_id(src.author())
+        .timeout(Duration::from_secs(180))
+        .next()
+        .await;
+
+    let outcome = confirm_outcome(item.as_ref().map(|item| item.data.custom_id.as_str()));
+
+    match (outcome, item) {
+        (ConfirmOutcome::Execute, Some(item)) => Ok(Some(item)),
+        (_, Some(item)) => {
+            item.defer(&src.ctx().http).await?;
+            item.edit_response(
+                &src.ctx().http,
+                serenity::all::EditInteractionResponse::new()
+                    .embed(
+                        serenity::all::CreateEmbed::new()
+                            .title("Cancelled")
+                            .description("No changes were made"),
+                    )
+                    .components(vec![]),
+            )
+            .await?;
+
+            Ok(None)
+        }
+        (_, None) => {
+            response
+                .edit(
+                    serenity::all::CreateEmbed::new()
+                        .title("Timed out")
+                        .description("No changes were made"),
+                    vec![],
+                )
+                .await?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Returns a summary of the given fields suitable for a confirmation prompt
+fn summarize_fields<Data: Clone>(
+    setting: &Setting<Data>,
+    fields: &indexmap::IndexMap<String, Value>,
+) -> String {
+    let mut summary = Vec::new();
+
+    for column in setting.columns.iter() {
+        if column.filter_only {
+            continue;
         }
+
+        let Some(value) = fields.get(column.id.as_str()) else {
+            continue;
+        };
+
+        if column.secret {
+            summary.push(format!("**{}**: (secret)", column.name));
+            continue;
+        }
+
+        summary.push(format!(
+            "**{}**: {}",
+            column.name,
+            _get_display_value(
+                &column.column_type,
+                value,
+                setting.ui_options.field_value_limit()
+            )
+        ));
     }
+
+    summary.join("\n")
 }
 
 fn create_embed<'a, Data: Clone>(
     setting: &Setting<Data>,
-    values: &'a [indexmap::IndexMap<String, Value>],
+    values: &[indexmap::IndexMap<String, Value>],
     index: usize,
     title: impl Fn() -> String,
 ) -> serenity::all::CreateEmbed<'a> {
@@ -189,86 +567,309 @@ fn create_embed<'a, Data: Clone>(
 
     embed = embed.title((title)());
 
-    for column in setting.columns.iter() {
-        let Some(value) = values[index].get(column.id.as_str()) else {
-            continue;
-        };
+    let max_length = setting.ui_options.field_value_limit();
+
+    let mut fields = Vec::new();
+    for (group, columns) in setting.columns_by_group() {
+        let mut group_fields = Vec::new();
+
+        for column in columns {
+            if column.filter_only {
+                continue;
+            }
+
+            let Some(value) = values[index].get(column.id.as_str()) else {
+                continue;
+            };
+
+            let display_value = truncate_with_ellipsis(
+                &_get_display_value(&column.column_type, value, max_length),
+                max_length,
+            );
+
+            group_fields.push((column.name.to_string(), display_value, true));
+        }
+
+        // A non-inline field acts as the heading of the group, Discord rejects empty field values
+        if let Some(group) = group {
+            if !group_fields.is_empty() {
+                fields.push((
+                    truncate_chars(group, 256).to_string(),
+                    "\u{200b}".to_string(),
+                    false,
+                ));
+            }
+        }
+
+        fields.extend(group_fields);
+    }
+
+    for (name, value, inline) in fields.into_iter().take(EMBED_FIELD_LIMIT) {
+        embed = embed.field(name, value, inline);
+    }
+
+    apply_embed_style(setting, embed)
+}
+
+/// Renders several entries into one embed, each entry as a single field titled using the title template
+///
+/// The character budget of the embed is shared between the entries, leaving room for the title and footer
+fn create_multi_row_embed<'a, Data: Clone>(
+    setting: &Setting<Data>,
+    values: &[indexmap::IndexMap<String, Value>],
+    title: String,
+) -> serenity::all::CreateEmbed<'a> {
+    let mut embed =
+        serenity::all::CreateEmbed::default().title(truncate_chars(&title, 256).to_string());
+
+    let max_length = setting.ui_options.field_value_limit();
+    let budget = (EMBED_CHAR_LIMIT - 256 - 2048) / usize::max(values.len(), 1);
+
+    for value in values.iter().take(EMBED_FIELD_LIMIT) {
+        let name = truncate_chars(&setting.render_title(value), 256).to_string();
 
-        let mut display_value = _get_display_value(&column.column_type, value);
+        let mut lines = Vec::new();
+        for column in setting.columns.iter() {
+            if column.filter_only {
+                continue;
+            }
+
+            let Some(v) = value.get(column.id.as_str()) else {
+                continue;
+            };
+
+            lines.push(format!(
+                "**{}:** {}",
+                column.name,
+                _get_display_value(&column.column_type, v, max_length)
+            ));
+        }
 
-        if display_value.len() > 1024 {
-            display_value = format!("{}...", &display_value[..1021]);
+        let limit = usize::min(max_length, budget.saturating_sub(name.chars().count()));
+        let mut display_value = truncate_with_ellipsis(&lines.join("\n"), limit);
+
+        // Discord rejects fields with empty values
+        if display_value.is_empty() {
+            display_value = "-".to_string();
         }
 
-        embed = embed.field(column.name.to_string(), display_value, true);
+        embed = embed.field(name, display_value, false);
+    }
+
+    apply_embed_style(setting, embed)
+}
+
+/// Applies the color and footer configured in the settings `UiOptions` to an embed
+fn apply_embed_style<'a, Data: Clone>(
+    setting: &Setting<Data>,
+    mut embed: serenity::all::CreateEmbed<'a>,
+) -> serenity::all::CreateEmbed<'a> {
+    if let Some(color) = setting.ui_options.embed_color {
+        embed = embed.color(color);
+    }
+
+    if let Some(ref footer) = setting.ui_options.embed_footer {
+        embed = embed.footer(serenity::all::CreateEmbedFooter::new(footer.to_string()));
     }
 
     embed
 }
 
-/// Settings viewer code for serenity, sends an embed, all that stuff
-pub async fn settings_viewer<Data: Clone>(
-    src: Src<'_>,
+impl<Data: Clone> Setting<Data> {
+    /// Renders a single (raw) entry to an embed using the same display logic as the viewer
+    ///
+    /// Secret columns are masked (or stripped, see `SecretDisplay`) before rendering, this means that rows
+    /// obtained from outside of `settings_view` (e.g. from a cache or webhook) can be rendered safely
+    pub fn render_entry<'a>(
+        &self,
+        row: &indexmap::IndexMap<String, Value>,
+        title: impl Into<String>,
+    ) -> serenity::all::CreateEmbed<'a> {
+        let row = self.mask_secrets(row);
+
+        let title = title.into();
+        create_embed(self, &[row], 0, || title.clone())
+    }
+}
+
+/// Adds the deprecation notes (if any) for the operation and the provided fields to the embed
+fn add_deprecation_notes<'a, Data: Clone>(
+    embed: serenity::all::CreateEmbed<'a>,
     setting: &Setting<Data>,
-    data: &Data,
-    filters: indexmap::IndexMap<String, Value>, // The filters to apply
-) -> Result<(), crate::Error> {
-    fn create_action_row<'a>(index: usize, total: usize) -> serenity::all::CreateActionRow<'a> {
+    operation_type: OperationType,
+    fields: &indexmap::IndexMap<String, Value>,
+) -> serenity::all::CreateEmbed<'a> {
+    let notes = setting.deprecation_notes(operation_type, fields);
+
+    if notes.is_empty() {
+        return embed;
+    }
+
+    embed.field(
+        "Deprecated",
+        truncate_chars(&notes.join("\n"), setting.ui_options.field_value_limit()).to_string(),
+        false,
+    )
+}
+
+/// Returns the primary key(s) of an entry in a form suitable for display (e.g. `ID: 1234`)
+fn primary_key_display<Data: Clone>(
+    setting: &Setting<Data>,
+    value: &indexmap::IndexMap<String, Value>,
+) -> Option<String> {
+    let mut pkeys = Vec::new();
+
+    for column in setting.columns.iter() {
+        if !column.primary_key || column.filter_only {
+            continue;
+        }
+
+        let Some(v) = value.get(column.id.as_str()) else {
+            continue;
+        };
+
+        pkeys.push(format!(
+            "{}: `{}`",
+            column.name,
+            v.as_str().unwrap_or(&v.to_string())
+        ));
+    }
+
+    if pkeys.is_empty() {
+        None
+    } else {
+        Some(pkeys.join(", "))
+    }
+}
+
+/// Shows the primary key(s) of the entry in the description of the embed if enabled through
+/// `UiOptions::show_primary_key`
+fn add_primary_key<'a, Data: Clone>(
+    embed: serenity::all::CreateEmbed<'a>,
+    setting: &Setting<Data>,
+    value: &indexmap::IndexMap<String, Value>,
+) -> serenity::all::CreateEmbed<'a> {
+    if !setting.ui_options.show_primary_key {
+        return embed;
+    }
+
+    match primary_key_display(setting, value) {
+        Some(pkey) => embed.description(pkey),
+        None => embed,
+    }
+}
+
+/// Returns a footer listing the supported operations of the setting other than view along with their commands
+fn operations_footer<Data: Clone>(setting: &Setting<Data>) -> Option<String> {
+    let operations = setting
+        .supported_operations()
+        .into_iter()
+        .filter(|op| *op != OperationType::View)
+        .map(|op| format!("/{} {}", setting.id, op.command_name()))
+        .collect::<Vec<String>>();
+
+    if operations.is_empty() {
+        None
+    } else {
+        Some(format!("Also available: {}", operations.join(", ")))
+    }
+}
+
+/// Creates the pagination buttons, if `expired` is set all buttons are disabled
+fn create_pagination_rows<'a>(
+    index: usize,
+    total: usize,
+    expired: bool,
+) -> Vec<serenity::all::CreateActionRow<'a>> {
+    let last = total.saturating_sub(1);
+
+    vec![
         serenity::all::CreateActionRow::Buttons(
             vec![
+                serenity::all::CreateButton::new("first")
+                    .style(serenity::all::ButtonStyle::Primary)
+                    .label("First")
+                    .disabled(expired || index == 0),
                 serenity::all::CreateButton::new("previous")
                     .style(serenity::all::ButtonStyle::Primary)
                     .label("Previous")
-                    .disabled(index == 0),
+                    .disabled(expired || index == 0),
                 serenity::all::CreateButton::new("next")
                     .style(serenity::all::ButtonStyle::Primary)
                     .label("Next")
-                    .disabled(index >= total - 1),
-                serenity::all::CreateButton::new("first")
+                    .disabled(expired || index >= last),
+                serenity::all::CreateButton::new("last")
                     .style(serenity::all::ButtonStyle::Primary)
-                    .label("First")
-                    .disabled(false),
+                    .label("Last")
+                    .disabled(expired || index >= last),
+            ]
+            .into(),
+        ),
+        serenity::all::CreateActionRow::Buttons(
+            vec![
+                serenity::all::CreateButton::new("jump")
+                    .style(serenity::all::ButtonStyle::Secondary)
+                    .label("Jump")
+                    .disabled(expired || total <= 1),
                 serenity::all::CreateButton::new("close")
                     .style(serenity::all::ButtonStyle::Danger)
                     .label("Close")
-                    .disabled(false),
+                    .disabled(expired),
             ]
             .into(),
-        )
-    }
-
-    if setting.operations.view.is_none() {
-        return Err("Unsupported operation (View) for setting".into());
-    };
+        ),
+    ]
+}
 
-    let values = settings_view(setting, data, filters)
-        .await
-        .map_err(|e| format!("Error fetching settings: {:?}", e))?;
+/// Clamps a page index to `0..total`, an empty paginator always stays on the first page
+fn clamp_page_index(index: usize, total: usize) -> usize {
+    usize::min(index, total.saturating_sub(1))
+}
 
-    if values.is_empty() {
-        return Ok(());
+/// Parses a (1-indexed) page number entered by the user into a page index, validating it against the total
+fn parse_page_number(input: &str, total: usize) -> Result<usize, String> {
+    match input.trim().parse::<usize>() {
+        Ok(page) if page >= 1 && page <= total => Ok(page - 1),
+        _ => Err(format!(
+            "Invalid page `{}`, expected a number between 1 and {}",
+            input.trim(),
+            total
+        )),
     }
+}
 
-    let total_count: usize = values.len();
-
+/// Sends the first page and then lets the user browse through `total_count` pages using buttons
+///
+/// `render` is called with the index of the page and the total number of pages whenever the user
+/// switches pages, this allows pages to be fetched lazily. Once `timeout` passes without any interaction,
+/// the buttons are disabled
+async fn paginate<'a, F, Fut>(
+    src: Src<'_>,
+    total_count: usize,
+    timeout: Duration,
+    first_page: serenity::all::CreateEmbed<'a>,
+    render: F,
+) -> Result<(), crate::Error>
+where
+    F: Fn(usize, usize) -> Fut,
+    Fut: std::future::Future<Output = Result<serenity::all::CreateEmbed<'a>, crate::Error>>,
+{
     let mut index = 0;
 
-    let msg = src
-        .send_initial_response(
-            create_embed(setting, &values, index, || {
-                format!("{} ({} of {})", setting.name, index + 1, total_count)
-            }),
-            Some(create_action_row(index, total_count)),
+    let response = src
+        .send_initial_response_with_components(
+            first_page,
+            create_pagination_rows(index, total_count, false),
         )
-        .await?
-        .into_message()
         .await?;
 
+    let msg = response.into_message().await?;
+
     let collector = msg
         .id
         .await_component_interactions(src.ctx().shard.clone())
         .author_id(src.author())
-        .timeout(Duration::from_secs(180));
+        .timeout(timeout);
 
     let mut collect_stream = collector.stream();
 
@@ -280,86 +881,576 @@ pub async fn settings_viewer<Data: Clone>(
                 index = index.saturating_sub(1);
             }
             "next" => {
-                index = usize::min(index + 1, total_count - 1);
+                index = clamp_page_index(index.saturating_add(1), total_count);
             }
             "first" => {
                 index = 0;
             }
-            "close" => {
-                item.defer(&src.ctx().http).await?;
-                item.delete_response(&src.ctx().http).await?;
-                break;
+            "last" => {
+                index = total_count.saturating_sub(1);
             }
-            _ => {}
-        }
+            "jump" => {
+                item.create_response(
+                    &src.ctx().http,
+                    serenity::all::CreateInteractionResponse::Modal(
+                        serenity::all::CreateModal::new("jump_modal", "Jump to page").components(
+                            vec![serenity::all::CreateActionRow::InputText(
+                                serenity::all::CreateInputText::new(
+                                    serenity::all::InputTextStyle::Short,
+                                    format!("Page (1-{})", total_count),
+                                    "jump_page",
+                                )
+                                .required(true),
+                            )],
+                        ),
+                    ),
+                )
+                .await?;
 
-        item.defer(&src.ctx().http).await?;
+                let Some(modal) =
+                    serenity::all::ModalInteractionCollector::new(src.ctx().shard.clone())
+                        .author_id(src.author())
+                        .filter(|m| m.data.custom_id.as_str() == "jump_modal")
+                        .timeout(Duration::from_secs(180))
+                        .next()
+                        .await
+                else {
+                    continue; // The user closed the modal, keep waiting on the buttons
+                };
+
+                let input = modal_input_value(&modal, "jump_page").unwrap_or_default();
+
+                modal.defer(&src.ctx().http).await?;
+
+                match parse_page_number(&input, total_count) {
+                    Ok(page) => index = clamp_page_index(page, total_count),
+                    Err(e) => {
+                        modal
+                            .create_followup(
+                                &src.ctx().http,
+                                serenity::all::CreateInteractionResponseFollowup::new()
+                                    .ephemeral(true)
+                                    .content(e),
+                            )
+                            .await?;
+                        continue;
+                    }
+                }
 
-        if index > total_count {
-            index = total_count - 1;
+                modal
+                    .edit_response(
+                        &src.ctx().http,
+                        serenity::all::EditInteractionResponse::new()
+                            .embed(render(index, total_count).await?)
+                            .components(create_pagination_rows(index, total_count, false)),
+                    )
+                    .await?;
+                continue;
+            }
+            "close" => {
+                item.defer(&src.ctx().http).await?;
+                item.delete_response(&src.ctx().http).await?;
+                return Ok(());
+            }
+            _ => {}
         }
 
+        index = clamp_page_index(index, total_count);
+
+        item.defer(&src.ctx().http).await?;
+
         item.edit_response(
             &src.ctx().http,
             serenity::all::EditInteractionResponse::new()
-                .embed(create_embed(setting, &values, index, || {
-                    format!("{} ({} of {})", setting.name, index + 1, total_count)
-                }))
-                .components(vec![create_action_row(index, total_count)]),
+                .embed(render(index, total_count).await?)
+                .components(create_pagination_rows(index, total_count, false)),
         )
         .await?;
     }
 
+    // The collector timed out, disable the buttons so the user knows the session has ended
+    response
+        .edit_components(create_pagination_rows(index, total_count, true))
+        .await?;
+
+    Ok(())
+}
+
+/// Settings viewer code for serenity, sends an embed, all that stuff
+pub async fn settings_viewer<Data: Clone>(
+    src: Src<'_>,
+    setting: &Setting<Data>,
+    data: &Data,
+    filters: indexmap::IndexMap<String, Value>, // The filters to apply
+    sort: Option<SortBy>,                       // The order to show the entries in
+) -> Result<(), crate::Error> {
+    fn create_view_embed<'a, Data: Clone>(
+        setting: &Setting<Data>,
+        values: &[indexmap::IndexMap<String, Value>],
+        index: usize,
+        total_pages: usize,
+    ) -> serenity::all::CreateEmbed<'a> {
+        let mut embed = if setting.ui_options.rows_per_page() == 1 {
+            create_embed(setting, values, 0, || {
+                format!(
+                    "{} ({} of {})",
+                    setting.render_title(&values[0]),
+                    index + 1,
+                    total_pages
+                )
+            })
+        } else {
+            create_multi_row_embed(
+                setting,
+                values,
+                format!("{} (page {} of {})", setting.name, index + 1, total_pages),
+            )
+        };
+
+        embed = add_deprecation_notes(
+            embed,
+            setting,
+            OperationType::View,
+            &indexmap::IndexMap::new(),
+        );
+
+        if setting.ui_options.show_operations_footer {
+            if let Some(footer) = operations_footer(setting) {
+                embed = embed.footer(serenity::all::CreateEmbedFooter::new(footer));
+            }
+        }
+
+        embed
+    }
+
+    if setting.operations.view.is_none() {
+        return src
+            .send_error("Unsupported operation (View) for setting")
+            .await;
+    };
+
+    let confirmation;
+    let src = if setting
+        .ui_options
+        .requires_confirmation_for(OperationType::View)
+    {
+        let Some(item) = confirm(&src, format!("View {}?", setting.name)).await? else {
+            return Ok(());
+        };
+
+        confirmation = item;
+        Src::Component((&confirmation, src.ctx(), src.author()))
+    } else {
+        src
+    };
+
+    let guild_id = src.guild_id();
+    let actor = src.author().to_string();
+
+    let rows_per_page = setting.ui_options.rows_per_page();
+
+    // Pages are fetched one at a time as the user switches pages
+    let fetch_page = |index: usize| {
+        let filters = filters.clone();
+        let sort = sort.clone();
+        let actor = actor.clone();
+        async move {
+            let (values, _) = settings_view_page(
+                setting,
+                data,
+                filters,
+                sort.as_ref(),
+                rows_per_page,
+                index * rows_per_page,
+                Some(&actor),
+            )
+            .await
+            .map_err(|e| format!("Error fetching settings: {}", e))?;
+
+            if setting.ui_options.check_guild_id {
+                if let Some(guild_id) = guild_id {
+                    for value in values.iter() {
+                        settings_check_guild_id(setting, value, &guild_id.to_string())?;
+                    }
+                }
+            }
+
+            Ok::<_, crate::Error>(values)
+        }
+    };
+
+    // The total is counted separately so that it is correct even though only a single page is fetched
+    let total_count = match settings_count(setting, data, filters.clone(), Some(&actor)).await {
+        Ok(total_count) => total_count,
+        Err(e) => {
+            return src
+                .send_error(&format!("Error counting settings: {}", e))
+                .await
+        }
+    };
+
+    let values = match fetch_page(0).await {
+        Ok(values) => values,
+        Err(e) => return src.send_error(&e.to_string()).await,
+    };
+
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    // An entry may have been created between counting and fetching
+    let total_pages = usize::max(total_count, 1).div_ceil(rows_per_page);
+
+    paginate(
+        src,
+        total_pages,
+        setting.ui_options.interaction_timeout(),
+        create_view_embed(setting, &values, 0, total_pages),
+        |index, total_pages| {
+            let page = fetch_page(index);
+            async move {
+                let values = page.await?;
+
+                // Entries may have been deleted since counting, keep the session alive so the user can
+                // still go back to an earlier page
+                if values.is_empty() {
+                    return Ok(serenity::all::CreateEmbed::new()
+                        .title(format!(
+                            "{} (page {} of {})",
+                            setting.name,
+                            index + 1,
+                            total_pages
+                        ))
+                        .description("This entry no longer exists")
+                        .color(serenity::all::Colour::RED));
+                }
+
+                Ok(create_view_embed(setting, &values, index, total_pages))
+            }
+        },
+    )
+    .await
+}
+
+/// Shows a single entry identified by its primary key (see `settings_view_one`), without pagination
+///
+/// Useful to show an entry right after it was changed
+pub async fn settings_single_viewer<Data: Clone>(
+    src: Src<'_>,
+    setting: &Setting<Data>,
+    data: &Data,
+    primary_key: indexmap::IndexMap<String, Value>,
+) -> Result<(), crate::Error> {
+    if setting.operations.view.is_none() {
+        return src
+            .send_error("Unsupported operation (View) for setting")
+            .await;
+    };
+
+    let value = match settings_view_one(setting, data, primary_key, Some(&src.author().to_string()))
+        .await
+    {
+        Ok(Some(value)) => value,
+        Ok(None) => return src.send_error("No entry found with the given key").await,
+        Err(e) => {
+            return src
+                .send_error(&format!("Error fetching settings: {}", e))
+                .await
+        }
+    };
+
+    if setting.ui_options.check_guild_id {
+        if let Some(guild_id) = src.guild_id() {
+            settings_check_guild_id(setting, &value, &guild_id.to_string())?;
+        }
+    }
+
+    let mut embed = create_embed(setting, std::slice::from_ref(&value), 0, || {
+        setting.render_title(&value)
+    });
+
+    embed = add_deprecation_notes(
+        embed,
+        setting,
+        OperationType::View,
+        &indexmap::IndexMap::new(),
+    );
+
+    embed = add_primary_key(embed, setting, &value);
+
+    src.send_initial_response(embed, None).await?;
+
     Ok(())
 }
 
+/// The maximum number of fields allowed in an embed by Discord
+const EMBED_FIELD_LIMIT: usize = 25;
+
+/// The maximum total number of characters allowed in an embed by Discord
+const EMBED_CHAR_LIMIT: usize = 6000;
+
+/// Renders the schema of a setting (each column, its type, when it is required and its allowed values)
+/// into one or more help embeds, each within Discord's field and character limits
+pub fn build_help_embeds<'a, Data: Clone>(
+    setting: &Setting<Data>,
+) -> Vec<serenity::all::CreateEmbed<'a>> {
+    let operations = setting
+        .supported_operations()
+        .into_iter()
+        .filter(|op| *op != OperationType::View)
+        .map(|op| (op, setting.required_fields(op), setting.optional_fields(op)))
+        .collect::<Vec<_>>();
+
+    let mut fields = Vec::new();
+    for column in setting.columns.iter() {
+        if column.filter_only || !column.is_exposed_on(Surface::Command) {
+            continue;
+        }
+
+        let mut lines = vec![
+            truncate_chars(&column.description_with_deprecation(), 256).to_string(),
+            format!("**Type:** {}", column.column_type.type_label()),
+        ];
+
+        let mut required_for = Vec::new();
+        let mut optional_for = Vec::new();
+        for (op, required, optional) in operations.iter() {
+            if required.contains(&column.id) {
+                required_for.push(op.to_string());
+            } else if optional.contains(&column.id) {
+                optional_for.push(op.to_string());
+            }
+        }
+
+        if !required_for.is_empty() {
+            lines.push(format!("**Required for:** {}", required_for.join(", ")));
+        }
+
+        if !optional_for.is_empty() {
+            lines.push(format!("**Optional for:** {}", optional_for.join(", ")));
+        }
+
+        let allowed_values = column.column_type.inner().allowed_values();
+        if !allowed_values.is_empty() {
+            lines.push(format!("**Allowed values:** {}", allowed_values.join(", ")));
+        }
+
+        match &column.suggestions {
+            ColumnSuggestion::Static { suggestions } if !suggestions.is_empty() => {
+                lines.push(format!("**Suggestions:** {}", suggestions.join(", ")));
+            }
+            ColumnSuggestion::Dynamic {} => {
+                lines.push("**Suggestions:** offered while typing".to_string());
+            }
+            _ => {}
+        }
+
+        // Only the name is truncated so that the column id is always shown
+        let id = format!(" ({})", column.id);
+        let name =
+            truncate_with_ellipsis(&column.name, 256usize.saturating_sub(id.chars().count()));
+        let value = truncate_with_ellipsis(&lines.join("\n"), FIELD_VALUE_LIMIT);
+
+        fields.push((format!("{}{}", name, id), value));
+    }
+
+    // Split the fields into pages, leaving some room for the title and description
+    let description = truncate_chars(&setting.description, 1024).to_string();
+    let budget = EMBED_CHAR_LIMIT - 256 - description.chars().count();
+
+    let mut pages: Vec<Vec<(String, String)>> = vec![Vec::new()];
+    let mut page_chars = 0;
+    for (name, value) in fields {
+        let chars = name.chars().count() + value.chars().count();
+        let page = pages.last_mut().expect("pages is never empty");
+
+        if !page.is_empty() && (page.len() >= EMBED_FIELD_LIMIT || page_chars + chars > budget) {
+            pages.push(Vec::new());
+            page_chars = 0;
+        }
+
+        page_chars += chars;
+        pages
+            .last_mut()
+            .expect("pages is never empty")
+            .push((name, value));
+    }
+
+    let total = pages.len();
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(idx, page)| {
+            let mut embed = serenity::all::CreateEmbed::new()
+                .title(truncate_with_ellipsis(
+                    &format!("{} help ({} of {})", setting.name, idx + 1, total),
+                    256,
+                ))
+                .description(description.clone());
+
+            for (name, value) in page {
+                embed = embed.field(name, value, false);
+            }
+
+            embed
+        })
+        .collect()
+}
+
+/// Shows the help pages for a setting (see `build_help_embeds`) using the pagination UI
+///
+/// The autogenerated commands only have subcommands for operations, so this is not reachable from them.
+/// Call it from your own command (e.g. a `/help <setting>` command looking the setting up in a
+/// `SettingsRegistry`) or component handler, passing the interaction as `Src`
+pub async fn settings_help<Data: Clone>(
+    src: Src<'_>,
+    setting: &Setting<Data>,
+) -> Result<(), crate::Error> {
+    let embeds = build_help_embeds(setting);
+
+    paginate(
+        src,
+        embeds.len(),
+        setting.ui_options.interaction_timeout(),
+        embeds[0].clone(),
+        |index, _| {
+            let embed = embeds[index].clone();
+            async move { Ok(embed) }
+        },
+    )
+    .await
+}
+
 /// Common settings creator for poise, sends an embed, all that stuff
+///
+/// Returns the created entry (e.g. to look it up again with `settings_view_one`), `None` if the operation was
+/// cancelled or failed (in which case the error has already been sent)
 pub async fn settings_creator<Data: Clone>(
     src: Src<'_>,
     setting: &Setting<Data>,
     data: &Data,
     fields: indexmap::IndexMap<String, Value>, // The filters to apply
-) -> Result<(), crate::Error> {
+) -> Result<Option<indexmap::IndexMap<String, Value>>, crate::Error> {
     if setting.operations.create.is_none() {
-        return Err("Unsupported operation (Create) for setting".into());
+        src.send_error("Unsupported operation (Create) for setting")
+            .await?;
+        return Ok(None);
     };
 
-    let value = settings_create(setting, data, fields)
-        .await
-        .map_err(|e| format!("Failed to create setting: {:?}", e))?;
+    let confirmation;
+    let src = if setting
+        .ui_options
+        .requires_confirmation_for(OperationType::Create)
+    {
+        let Some(item) = confirm(
+            &src,
+            format!(
+                "Create {} with the following values?\n\n{}",
+                setting.name,
+                summarize_fields(setting, &fields)
+            ),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        confirmation = item;
+        Src::Component((&confirmation, src.ctx(), src.author()))
+    } else {
+        src
+    };
+
+    let notes_fields = fields.clone();
+    let value = match settings_create(setting, data, fields, Some(&src.author().to_string())).await
+    {
+        Ok(value) => value,
+        Err(e) => {
+            src.send_error(&format!("Failed to create setting: {}", e))
+                .await?;
+            return Ok(None);
+        }
+    };
+
+    let mut embed = create_embed(setting, std::slice::from_ref(&value), 0, || {
+        format!("Created {}", setting.render_title(&value))
+    });
+
+    embed = add_deprecation_notes(embed, setting, OperationType::Create, &notes_fields);
+
+    embed = add_primary_key(embed, setting, &value);
 
     // Send message that we are creating the setting
-    src.send_initial_response(
-        create_embed(setting, &[value], 0, || format!("Created {}", setting.name)),
-        None,
-    )
-    .await?;
+    src.send_initial_response(embed, None).await?;
 
-    Ok(())
+    Ok(Some(value))
 }
 
 /// Common settings updater for poise, sends an embed, all that stuff
+///
+/// Returns the updated entry (e.g. to look it up again with `settings_view_one`), `None` if the operation was
+/// cancelled or failed (in which case the error has already been sent)
 pub async fn settings_updater<Data: Clone>(
     src: Src<'_>,
     setting: &Setting<Data>,
     data: &Data,
     fields: indexmap::IndexMap<String, Value>,
-) -> Result<(), crate::Error> {
+) -> Result<Option<indexmap::IndexMap<String, Value>>, crate::Error> {
     if setting.operations.update.is_none() {
-        return Err("Unsupported operation (Update) for setting".into());
+        src.send_error("Unsupported operation (Update) for setting")
+            .await?;
+        return Ok(None);
     };
 
-    let value = settings_update(setting, data, fields)
-        .await
-        .map_err(|e| format!("Failed to update setting: {:?}", e))?;
+    let confirmation;
+    let src = if setting
+        .ui_options
+        .requires_confirmation_for(OperationType::Update)
+    {
+        let Some(item) = confirm(
+            &src,
+            format!(
+                "Update {} with the following values?\n\n{}",
+                setting.name,
+                summarize_fields(setting, &fields)
+            ),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
 
-    src.send_initial_response(
-        create_embed(setting, &[value], 0, || format!("Updated {}", setting.name)),
-        None,
-    )
-    .await?;
+        confirmation = item;
+        Src::Component((&confirmation, src.ctx(), src.author()))
+    } else {
+        src
+    };
 
-    Ok(())
+    let notes_fields = fields.clone();
+    let value = match settings_update(setting, data, fields, Some(&src.author().to_string())).await
+    {
+        Ok(value) => value,
+        Err(e) => {
+            src.send_error(&format!("Failed to update setting: {}", e))
+                .await?;
+            return Ok(None);
+        }
+    };
+
+    // Unlike on create, the entry may contain secrets the user did not enter themselves
+    let masked = setting.mask_secrets(&value);
+
+    let mut embed = create_embed(setting, std::slice::from_ref(&masked), 0, || {
+        format!("Updated {}", setting.render_title(&masked))
+    });
+
+    embed = add_deprecation_notes(embed, setting, OperationType::Update, &notes_fields);
+
+    embed = add_primary_key(embed, setting, &masked);
+
+    src.send_initial_response(embed, None).await?;
+
+    Ok(Some(value))
 }
 
 /// Common settings deleter for poise, sends an embed, all that stuff
@@ -370,33 +1461,1373 @@ pub async fn settings_deleter<Data: Clone>(
     fields: indexmap::IndexMap<String, Value>,
 ) -> Result<(), crate::Error> {
     if setting.operations.delete.is_none() {
-        return Err("Unsupported operation (Delete) for setting".into());
+        return src
+            .send_error("Unsupported operation (Delete) for setting")
+            .await;
     }
 
     let mut pkey_str = Vec::new();
 
     for column in setting.columns.iter() {
-        if column.primary_key {
+        if (column.primary_key || column.alternate_key) && !column.filter_only {
             if let Some(value) = fields.get(column.id.as_str()) {
                 pkey_str.push(format!("{}: {}", column.name, value));
             }
         }
     }
 
-    settings_delete(setting, data, fields)
-        .await
-        .map_err(|e| format!("Error deleting setting: {:?}", e))?;
+    let confirmation;
+    let src = if setting
+        .ui_options
+        .requires_confirmation_for(OperationType::Delete)
+    {
+        let Some(item) = confirm(
+            &src,
+            format!("Delete {}: {}?", setting.name, pkey_str.join(", ")),
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+
+        confirmation = item;
+        Src::Component((&confirmation, src.ctx(), src.author()))
+    } else {
+        src
+    };
 
-    src.send_initial_response(
-        serenity::all::CreateEmbed::new()
+    let embed = add_deprecation_notes(
+        apply_embed_style(setting, serenity::all::CreateEmbed::new())
             .title(format!("Deleted {}", setting.name))
             .description(format!(
                 "Deleted {}: {}",
                 setting.name, pkey_str.join(", ")
             )),
-        None,
-    )
-    .await?;
+        setting,
+        OperationType::Delete,
+        &fields,
+    );
+
+    if let Err(e) = settings_delete(setting, data, fields, Some(&src.author().to_string())).await {
+        return src
+            .send_error(&format!("Error deleting setting: {}", e))
+            .await;
+    }
+
+    src.send_initial_response(embed, None).await?;
 
     Ok(())
 }
+
+/// Returns the value of the text input with the given custom id from a modal submission
+fn modal_input_value(modal: &serenity::all::ModalInteraction, custom_id: &str) -> Option<String> {
+    for row in modal.data.components.iter() {
+        for component in row.components.iter() {
+            if let serenity::all::ActionRowComponent::InputText(input) = component {
+                if input.custom_id.as_str() == custom_id {
+                    return input.value.as_ref().map(|v| v.to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// The maximum number of text inputs allowed in a modal by Discord
+const MODAL_INPUT_LIMIT: usize = 5;
+
+/// The outcome of `collect_long_inputs`
+pub enum LongInputs {
+    /// There were no long (textarea/json) columns to collect, `src` should be used as-is
+    NotNeeded,
+    /// The user submitted the modal, responses should be sent through `Src::Modal`
+    Submitted(serenity::all::ModalInteraction),
+    /// The user closed the modal or it timed out, the operation should be aborted
+    Cancelled,
+}
+
+/// Returns the long input (textarea/json) columns of an operation that are not already present in `fields`
+fn long_input_columns<'b, Data: Clone>(
+    setting: &'b Setting<Data>,
+    operation_type: OperationType,
+    fields: &indexmap::IndexMap<String, Value>,
+) -> Vec<&'b Column> {
+    setting
+        .columns
+        .iter()
+        .filter(|column| {
+            if column.ignored_for.contains(&operation_type)
+                || column.filter_only
+                || !column.is_exposed_on(Surface::Command)
+                || fields.contains_key(&column.id)
+            {
+                return false;
+            }
+
+            match column.column_type {
+                ColumnType::Scalar {
+                    inner: InnerColumnType::String { ref kind, .. },
+                } => kind == "textarea",
+                ColumnType::Scalar {
+                    inner: InnerColumnType::Json { .. } | InnerColumnType::Object { .. },
+                } => true,
+                _ => false,
+            }
+        })
+        .take(MODAL_INPUT_LIMIT)
+        .collect()
+}
+
+/// Collects long (textarea/json) columns that were not provided as command options through a modal
+///
+/// Slash command options are limited in length and cannot contain newlines, so such columns are better
+/// entered through a modal. The submitted values are merged into `fields`. Modals can only be opened in
+/// response to an interaction, for other sources `LongInputs::NotNeeded` is returned
+pub async fn collect_long_inputs<Data: Clone>(
+    src: &Src<'_>,
+    setting: &Setting<Data>,
+    operation_type: OperationType,
+    fields: &mut indexmap::IndexMap<String, Value>,
+) -> Result<LongInputs, crate::Error> {
+    let Src::Interaction((interaction, ctx, author)) = src else {
+        return Ok(LongInputs::NotNeeded);
+    };
+
+    let columns = long_input_columns(setting, operation_type, fields);
+
+    if columns.is_empty() {
+        return Ok(LongInputs::NotNeeded);
+    }
+
+    let inputs = columns
+        .iter()
+        .map(|column| {
+            serenity::all::CreateActionRow::InputText(
+                serenity::all::CreateInputText::new(
+                    serenity::all::InputTextStyle::Paragraph,
+                    column.name.chars().take(45).collect::<String>(),
+                    column.id.to_string(),
+                )
+                .required(setting.is_column_required_for_operation_type(column, operation_type)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::all::CreateInteractionResponse::Modal(
+                serenity::all::CreateModal::new(
+                    "settings_long_inputs",
+                    format!("{} {}", operation_type, setting.name)
+                        .chars()
+                        .take(45)
+                        .collect::<String>(),
+                )
+                .components(inputs),
+            ),
+        )
+        .await?;
+
+    let Some(modal) = serenity::all::ModalInteractionCollector::new(ctx.shard.clone())
+        .author_id(*author)
+        .filter(|m| m.data.custom_id.as_str() == "settings_long_inputs")
+        .timeout(Duration::from_secs(600))
+        .next()
+        .await
+    else {
+        // The modal was closed (Discord does not notify us of this) or timed out
+        return Ok(LongInputs::Cancelled);
+    };
+
+    for column in columns {
+        let Some(input) = modal_input_value(&modal, &column.id) else {
+            continue;
+        };
+
+        if input.trim().is_empty() {
+            continue;
+        }
+
+        fields.insert(
+            column.id.to_string(),
+            parse_form_input(&column.column_type, &input),
+        );
+    }
+
+    Ok(LongInputs::Submitted(modal))
+}
+
+/// The maximum length of the value of a text input in a modal
+const TEXT_INPUT_VALUE_LIMIT: usize = 4000;
+
+/// Returns the current value of a column as text that `parse_form_input` turns back into the same value
+///
+/// Returns `None` if the value cannot be edited as text, e.g. arrays of strings containing commas (the
+/// array separator) or values too long for a text input
+fn edit_input_text(column_type: &ColumnType, value: &Value) -> Option<String> {
+    fn scalar_text(value: &Value) -> Option<String> {
+        match value {
+            Value::Null => Some(String::new()),
+            Value::String(s) => Some(s.to_string()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    let text = match column_type {
+        ColumnType::Scalar {
+            inner: InnerColumnType::Json { .. } | InnerColumnType::Object { .. },
+        } => match value {
+            Value::Null => String::new(),
+            _ => serde_json::to_string_pretty(value).ok()?,
+        },
+        ColumnType::Scalar { .. } => scalar_text(value)?,
+        ColumnType::Array { .. } => match value {
+            Value::Null => String::new(),
+            Value::Array(values) => values
+                .iter()
+                .map(|v| scalar_text(v).filter(|s| !s.contains(',')))
+                .collect::<Option<Vec<_>>>()?
+                .join(", "),
+            _ => return None,
+        },
+    };
+
+    if text.chars().count() > TEXT_INPUT_VALUE_LIMIT {
+        return None;
+    }
+
+    Some(text)
+}
+
+/// Lets the user edit an existing entry through a modal pre-filled with its current values
+///
+/// The entry is looked up by its primary key (see `settings_view_one`). Only the first five editable columns
+/// fit in a modal, primary key and secret columns and values that cannot be edited as text (see
+/// `edit_input_text`) are not offered. The fields the user changed are applied on top of the stored entry
+/// (see `edited_entry`), so secret columns and columns hidden from View keep their values, and the result is
+/// passed on to `settings_updater`, whose result is returned. Modals can only be opened in response to a
+/// command or component interaction
+pub async fn settings_editor<Data: Clone>(
+    src: Src<'_>,
+    setting: &Setting<Data>,
+    data: &Data,
+    primary_key: indexmap::IndexMap<String, Value>,
+) -> Result<Option<indexmap::IndexMap<String, Value>>, crate::Error> {
+    if setting.operations.update.is_none() || setting.operations.view.is_none() {
+        src.send_error("Unsupported operation (Update) for setting")
+            .await?;
+        return Ok(None);
+    };
+
+    let existing = match settings_view_one(
+        setting,
+        data,
+        primary_key,
+        Some(&src.author().to_string()),
+    )
+    .await
+    {
+        Ok(Some(existing)) => existing,
+        Ok(None) => {
+            src.send_error("No entry found with the given key").await?;
+            return Ok(None);
+        }
+        Err(e) => {
+            src.send_error(&format!("Error fetching settings: {}", e))
+                .await?;
+            return Ok(None);
+        }
+    };
+
+    let columns = setting
+        .columns
+        .iter()
+        .filter(|column| {
+            !column.primary_key
+                && !column.secret
+                && !column.filter_only
+                && !column.ignored_for.contains(&OperationType::Update)
+                && column.is_exposed_on(Surface::Command)
+        })
+        .filter_map(|column| {
+            let current = existing.get(&column.id).unwrap_or(&Value::Null);
+            let text = edit_input_text(&column.column_type, current)?;
+            Some((column, text))
+        })
+        .take(MODAL_INPUT_LIMIT)
+        .collect::<Vec<_>>();
+
+    if columns.is_empty() {
+        src.send_error("This setting has no fields that can be edited here")
+            .await?;
+        return Ok(None);
+    }
+
+    let inputs = columns
+        .iter()
+        .map(|(column, text)| {
+            let style = if text.contains('\n')
+                || matches!(
+                    column.column_type,
+                    ColumnType::Scalar {
+                        inner: InnerColumnType::String { ref kind, .. },
+                    } if kind == "textarea"
+                )
+                || matches!(
+                    column.column_type,
+                    ColumnType::Scalar {
+                        inner: InnerColumnType::Json { .. } | InnerColumnType::Object { .. },
+                    }
+                ) {
+                serenity::all::InputTextStyle::Paragraph
+            } else {
+                serenity::all::InputTextStyle::Short
+            };
+
+            serenity::all::CreateActionRow::InputText(
+                serenity::all::CreateInputText::new(
+                    style,
+                    column.name.chars().take(45).collect::<String>(),
+                    column.id.to_string(),
+                )
+                .value(text.to_string())
+                .required(
+                    setting.is_column_required_for_operation_type(column, OperationType::Update),
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let modal = serenity::all::CreateInteractionResponse::Modal(
+        serenity::all::CreateModal::new(
+            "settings_editor",
+            format!("Edit {}", setting.render_title(&existing))
+                .chars()
+                .take(45)
+                .collect::<String>(),
+        )
+        .components(inputs),
+    );
+
+    let (ctx, author) = match &src {
+        Src::Interaction((interaction, ctx, author)) => {
+            interaction.create_response(&ctx.http, modal).await?;
+            (*ctx, *author)
+        }
+        Src::Component((interaction, ctx, author)) => {
+            interaction.create_response(&ctx.http, modal).await?;
+            (*ctx, *author)
+        }
+        _ => {
+            src.send_error("Editing is only available from commands and buttons")
+                .await?;
+            return Ok(None);
+        }
+    };
+
+    let Some(submission) = serenity::all::ModalInteractionCollector::new(ctx.shard.clone())
+        .author_id(author)
+        .filter(|m| m.data.custom_id.as_str() == "settings_editor")
+        .timeout(Duration::from_secs(600))
+        .next()
+        .await
+    else {
+        // The modal was closed (Discord does not notify us of this) or timed out
+        return Ok(None);
+    };
+
+    let src = Src::Modal((&submission, ctx, author));
+
+    let mut changes = indexmap::IndexMap::new();
+    for (column, text) in columns {
+        let Some(input) = modal_input_value(&submission, &column.id) else {
+            continue;
+        };
+
+        // Untouched inputs keep the original (typed) value
+        if input.trim() == text.trim() {
+            continue;
+        }
+
+        changes.insert(
+            column.id.to_string(),
+            parse_form_input(&column.column_type, &input),
+        );
+    }
+
+    if changes.is_empty() {
+        src.send_error("Nothing was changed").await?;
+        return Ok(None);
+    }
+
+    // Views mask secret values and drop columns hidden from View, so the stored entry is used instead
+    let primary_key = setting
+        .columns
+        .iter()
+        .filter(|column| column.primary_key)
+        .filter_map(|column| Some((column.id.to_string(), existing.get(&column.id)?.clone())))
+        .collect::<indexmap::IndexMap<_, _>>();
+
+    let fields = match edited_entry(setting, data, primary_key, changes).await {
+        Ok(fields) => fields,
+        Err(e) => {
+            src.send_error(&format!("Error fetching settings: {}", e))
+                .await?;
+            return Ok(None);
+        }
+    };
+
+    settings_updater(src, setting, data, fields).await
+}
+
+/// The maximum number of bitflag select menus shown at once (one action row is needed for the buttons)
+const BITFLAG_MENU_LIMIT: usize = 4;
+
+/// The maximum number of options in a select menu
+const SELECT_MENU_OPTION_LIMIT: usize = 25;
+
+/// The outcome of `collect_bitflag_inputs`
+pub enum BitFlagInputs {
+    /// There were no bitflag columns to collect, `src` should be used as-is
+    NotNeeded,
+    /// The user pressed Continue, responses should be sent through `Src::Component`
+    Submitted(serenity::all::ComponentInteraction),
+    /// The user cancelled or the prompt timed out, the operation should be aborted
+    Cancelled,
+}
+
+/// Returns the names of the flags set in a bitflag value
+fn selected_bitflags(
+    values: &indexmap::IndexMap<String, i64>,
+    value: Option<&Value>,
+) -> Vec<String> {
+    let bits = match value {
+        Some(Value::Number(n)) => n.as_i64().unwrap_or_default(),
+        Some(Value::String(s)) => s.parse::<i64>().unwrap_or_default(),
+        _ => 0,
+    };
+
+    values
+        .iter()
+        .filter(|(_, flag)| **flag != 0 && bits & **flag == **flag)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Prompts for bitflag columns that were not provided as command options using select menus
+///
+/// Each bitflag column is shown as a multi-select menu with one option per flag, on update the flags
+/// currently set in `current` are pre-selected. The chosen flags are converted to the bitflag value and
+/// merged into `fields` once the user presses Continue
+pub async fn collect_bitflag_inputs<Data: Clone>(
+    src: &Src<'_>,
+    setting: &Setting<Data>,
+    operation_type: OperationType,
+    fields: &mut indexmap::IndexMap<String, Value>,
+    current: Option<&indexmap::IndexMap<String, Value>>,
+) -> Result<BitFlagInputs, crate::Error> {
+    let columns = setting
+        .columns
+        .iter()
+        .filter_map(|column| {
+            if column.ignored_for.contains(&operation_type)
+                || column.filter_only
+                || !column.is_exposed_on(Surface::Command)
+                || fields.contains_key(&column.id)
+            {
+                return None;
+            }
+
+            match column.column_type {
+                ColumnType::Scalar {
+                    inner: InnerColumnType::BitFlag { ref values, .. },
+                } if !values.is_empty() && values.len() <= SELECT_MENU_OPTION_LIMIT => {
+                    Some((column, values))
+                }
+                _ => None,
+            }
+        })
+        .take(BITFLAG_MENU_LIMIT)
+        .collect::<Vec<_>>();
+
+    if columns.is_empty() {
+        return Ok(BitFlagInputs::NotNeeded);
+    }
+
+    let mut selected = indexmap::IndexMap::new();
+    let mut components = Vec::new();
+
+    for (column, values) in columns.iter() {
+        let current_flags = selected_bitflags(values, current.and_then(|c| c.get(&column.id)));
+
+        let options = values
+            .keys()
+            .map(|name| {
+                serenity::all::CreateSelectMenuOption::new(name.to_string(), name.to_string())
+                    .default_selection(current_flags.contains(name))
+            })
+            .collect::<Vec<_>>();
+
+        components.push(serenity::all::CreateActionRow::SelectMenu(
+            serenity::all::CreateSelectMenu::new(
+                format!("bitflag:{}", column.id),
+                serenity::all::CreateSelectMenuKind::String {
+                    options: options.into(),
+                },
+            )
+            .placeholder(column.name.chars().take(150).collect::<String>())
+            .min_values(0)
+            .max_values(values.len() as u8),
+        ));
+
+        selected.insert(column.id.to_string(), current_flags);
+    }
+
+    components.push(serenity::all::CreateActionRow::Buttons(
+        vec![
+            serenity::all::CreateButton::new("bitflag_continue")
+                .style(serenity::all::ButtonStyle::Primary)
+                .label("Continue"),
+            serenity::all::CreateButton::new("bitflag_cancel")
+                .style(serenity::all::ButtonStyle::Secondary)
+                .label("Cancel"),
+        ]
+        .into(),
+    ));
+
+    let response = src
+        .send_initial_response_with_components(
+            serenity::all::CreateEmbed::new()
+                .title(format!("{} {}", operation_type, setting.name))
+                .description(
+                    "Select the flags to set for each of the below fields and press Continue",
+                ),
+            components,
+        )
+        .await?;
+
+    let msg = response.into_message().await?;
+
+    let collector = msg
+        .id
+        .await_component_interactions(src.ctx().shard.clone())
+        .author_id(src.author())
+        .timeout(Duration::from_secs(180));
+
+    let mut collect_stream = collector.stream();
+
+    while let Some(item) = collect_stream.next().await {
+        match item.data.custom_id.as_str() {
+            "bitflag_continue" => {
+                for (column, values) in columns.iter() {
+                    let flags = selected.get(&column.id).cloned().unwrap_or_default();
+
+                    if flags.is_empty() && current.and_then(|c| c.get(&column.id)).is_none() {
+                        continue;
+                    }
+
+                    fields.insert(
+                        column.id.to_string(),
+                        super::autogen::convert_bitflags_string_to_value(
+                            values,
+                            Some(flags.join(";")),
+                        ),
+                    );
+                }
+
+                return Ok(BitFlagInputs::Submitted(item));
+            }
+            "bitflag_cancel" => {
+                item.defer(&src.ctx().http).await?;
+                item.edit_response(
+                    &src.ctx().http,
+                    serenity::all::EditInteractionResponse::new()
+                        .embed(
+                            serenity::all::CreateEmbed::new()
+                                .title("Cancelled")
+                                .description("No changes were made"),
+                        )
+                        .components(vec![]),
+                )
+                .await?;
+
+                return Ok(BitFlagInputs::Cancelled);
+            }
+            custom_id => {
+                if let (
+                    Some(column_id),
+                    serenity::all::ComponentInteractionDataKind::StringSelect { values },
+                ) = (custom_id.strip_prefix("bitflag:"), &item.data.kind)
+                {
+                    selected.insert(
+                        column_id.to_string(),
+                        values.iter().map(|v| v.to_string()).collect(),
+                    );
+                }
+
+                item.defer(&src.ctx().http).await?;
+            }
+        }
+    }
+
+    response
+        .edit(
+            serenity::all::CreateEmbed::new()
+                .title("Timed out")
+                .description("No changes were made"),
+            vec![],
+        )
+        .await?;
+
+    Ok(BitFlagInputs::Cancelled)
+}
+
+/// Interactive form (wizard) for create/update, prompts for each field in turn before executing the operation
+///
+/// `fields` are treated as already filled in and will not be prompted for
+pub async fn settings_form<Data: Clone>(
+    src: Src<'_>,
+    setting: &Setting<Data>,
+    data: &Data,
+    operation_type: OperationType,
+    fields: indexmap::IndexMap<String, Value>,
+) -> Result<(), crate::Error> {
+    fn create_form_embed<'a, Data: Clone>(
+        setting: &Setting<Data>,
+        form: &FormState,
+        error: Option<&str>,
+    ) -> serenity::all::CreateEmbed<'a> {
+        let (step, total) = form.position();
+
+        let mut embed = serenity::all::CreateEmbed::new().title(format!(
+            "{} {} ({} of {})",
+            form.operation_type(),
+            setting.name,
+            step,
+            total
+        ));
+
+        let Some(column) = form.current() else {
+            return embed;
+        };
+
+        embed = embed.description(format!(
+            "**{}**\n{}{}",
+            column.name,
+            column.description,
+            if form.can_skip() {
+                "\n\n*This field is optional*"
+            } else {
+                ""
+            }
+        ));
+
+        let suggestions = column.suggestions.static_suggestions().join(", ");
+        if !suggestions.is_empty() {
+            embed = embed.field(
+                "Suggestions",
+                truncate_with_ellipsis(&suggestions, FIELD_VALUE_LIMIT),
+                false,
+            );
+        }
+
+        if let Some(value) = form.current_value() {
+            embed = embed.field(
+                "Current Value",
+                _get_display_value(
+                    &column.column_type,
+                    value,
+                    setting.ui_options.field_value_limit(),
+                ),
+                false,
+            );
+        }
+
+        if let Some(error) = error {
+            embed = embed.field("Error", error.to_string(), false);
+        }
+
+        embed
+    }
+
+    fn create_form_action_row<'a>(form: &FormState) -> serenity::all::CreateActionRow<'a> {
+        serenity::all::CreateActionRow::Buttons(
+            vec![
+                serenity::all::CreateButton::new("form_input")
+                    .style(serenity::all::ButtonStyle::Primary)
+                    .label("Enter Value"),
+                serenity::all::CreateButton::new("form_skip")
+                    .style(serenity::all::ButtonStyle::Secondary)
+                    .label("Skip")
+                    .disabled(!form.can_skip()),
+                serenity::all::CreateButton::new("form_back")
+                    .style(serenity::all::ButtonStyle::Secondary)
+                    .label("Back")
+                    .disabled(!form.can_go_back()),
+                serenity::all::CreateButton::new("form_cancel")
+                    .style(serenity::all::ButtonStyle::Danger)
+                    .label("Cancel"),
+            ]
+            .into(),
+        )
+    }
+
+    match operation_type {
+        OperationType::Create => {
+            if setting.operations.create.is_none() {
+                return src
+                    .send_error("Unsupported operation (Create) for setting")
+                    .await;
+            }
+        }
+        OperationType::Update => {
+            if setting.operations.update.is_none() {
+                return src
+                    .send_error("Unsupported operation (Update) for setting")
+                    .await;
+            }
+        }
+        _ => {
+            return src
+                .send_error(&format!(
+                    "Forms are not supported for operation {}",
+                    operation_type
+                ))
+                .await;
+        }
+    }
+
+    let mut form = FormState::new(setting, operation_type, fields);
+
+    // Nothing to prompt for, just execute the operation
+    if form.is_complete() {
+        return match operation_type {
+            OperationType::Update => settings_updater(src, setting, data, form.into_values()).await,
+            _ => settings_creator(src, setting, data, form.into_values()).await,
+        }
+        .map(|_| ());
+    }
+
+    let msg = src
+        .send_initial_response(
+            create_form_embed(setting, &form, None),
+            Some(create_form_action_row(&form)),
+        )
+        .await?
+        .into_message()
+        .await?;
+
+    let collector = msg
+        .id
+        .await_component_interactions(src.ctx().shard.clone())
+        .author_id(src.author())
+        .timeout(Duration::from_secs(180));
+
+    let mut collect_stream = collector.stream();
+
+    while let Some(item) = collect_stream.next().await {
+        let mut error = None;
+
+        match item.data.custom_id.as_str() {
+            "form_input" => {
+                let Some(column) = form.current() else {
+                    continue;
+                };
+
+                let mut input = serenity::all::CreateInputText::new(
+                    serenity::all::InputTextStyle::Paragraph,
+                    column.name.chars().take(45).collect::<String>(),
+                    "form_value",
+                )
+                .required(!form.can_skip());
+
+                if let Some(value) = form.current_value() {
+                    input = input.value(match value {
+                        Value::String(s) => s.clone(),
+                        _ => value.to_string(),
+                    });
+                }
+
+                item.create_response(
+                    &src.ctx().http,
+                    serenity::all::CreateInteractionResponse::Modal(
+                        serenity::all::CreateModal::new(
+                            "form_modal",
+                            setting.name.chars().take(45).collect::<String>(),
+                        )
+                        .components(vec![serenity::all::CreateActionRow::InputText(input)]),
+                    ),
+                )
+                .await?;
+
+                let Some(modal) =
+                    serenity::all::ModalInteractionCollector::new(src.ctx().shard.clone())
+                        .author_id(src.author())
+                        .filter(|m| m.data.custom_id.as_str() == "form_modal")
+                        .timeout(Duration::from_secs(180))
+                        .next()
+                        .await
+                else {
+                    continue; // The user closed the modal, keep waiting on the buttons
+                };
+
+                let input = modal_input_value(&modal, "form_value").unwrap_or_default();
+                let column_type = column.column_type.clone();
+
+                if let Err(e) = form.submit(parse_form_input(&column_type, &input)) {
+                    error = Some(e.to_string());
+                }
+
+                modal.defer(&src.ctx().http).await?;
+
+                if !form.is_complete() {
+                    modal
+                        .edit_response(
+                            &src.ctx().http,
+                            serenity::all::EditInteractionResponse::new()
+                                .embed(create_form_embed(setting, &form, error.as_deref()))
+                                .components(vec![create_form_action_row(&form)]),
+                        )
+                        .await?;
+                    continue;
+                }
+
+                let embed = execute_form(setting, data, &form, &src.author().to_string()).await;
+                modal
+                    .edit_response(
+                        &src.ctx().http,
+                        serenity::all::EditInteractionResponse::new()
+                            .embed(embed)
+                            .components(vec![]),
+                    )
+                    .await?;
+                break;
+            }
+            "form_skip" => {
+                if let Err(e) = form.skip() {
+                    error = Some(e.to_string());
+                }
+            }
+            "form_back" => {
+                form.back();
+            }
+            "form_cancel" => {
+                item.defer(&src.ctx().http).await?;
+                item.edit_response(
+                    &src.ctx().http,
+                    serenity::all::EditInteractionResponse::new()
+                        .embed(
+                            serenity::all::CreateEmbed::new()
+                                .title(format!("Cancelled {} {}", operation_type, setting.name))
+                                .description("No changes were made"),
+                        )
+                        .components(vec![]),
+                )
+                .await?;
+                break;
+            }
+            _ => {}
+        }
+
+        item.defer(&src.ctx().http).await?;
+
+        if !form.is_complete() {
+            item.edit_response(
+                &src.ctx().http,
+                serenity::all::EditInteractionResponse::new()
+                    .embed(create_form_embed(setting, &form, error.as_deref()))
+                    .components(vec![create_form_action_row(&form)]),
+            )
+            .await?;
+            continue;
+        }
+
+        let embed = execute_form(setting, data, &form, &src.author().to_string()).await;
+        item.edit_response(
+            &src.ctx().http,
+            serenity::all::EditInteractionResponse::new()
+                .embed(embed)
+                .components(vec![]),
+        )
+        .await?;
+        break;
+    }
+
+    Ok(())
+}
+
+/// Executes the operation of a completed form, returning the embed to display
+async fn execute_form<'a, Data: Clone>(
+    setting: &Setting<Data>,
+    data: &Data,
+    form: &FormState,
+    actor: &str,
+) -> serenity::all::CreateEmbed<'a> {
+    let result = match form.operation_type() {
+        OperationType::Update => settings_update(setting, data, form.values().clone(), Some(actor))
+            .await
+            .map(|v| {
+                // Unlike on create, the entry may contain secrets the user did not enter themselves
+                let v = setting.mask_secrets(&v);
+                let title = format!("Updated {}", setting.render_title(&v));
+                (v, title)
+            }),
+        _ => settings_create(setting, data, form.values().clone(), Some(actor))
+            .await
+            .map(|v| {
+                let title = format!("Created {}", setting.render_title(&v));
+                (v, title)
+            }),
+    };
+
+    match result {
+        Ok((value, title)) => add_primary_key(
+            create_embed(setting, std::slice::from_ref(&value), 0, || title.clone()),
+            setting,
+            &value,
+        ),
+        Err(e) => serenity::all::CreateEmbed::new()
+            .title(format!(
+                "Failed to {} {}",
+                form.operation_type(),
+                setting.name
+            ))
+            .description(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ColumnBuilder, SettingBuilder};
+    use crate::common_columns;
+    use crate::memory::InMemorySettings;
+    use crate::types::{SettingOperations, UiOptions};
+    use serde_json::json;
+
+    fn string_column(id: &str) -> Column {
+        ColumnBuilder::new(
+            id,
+            id,
+            ColumnType::new_scalar(InnerColumnType::String {
+                min_length: None,
+                max_length: None,
+                allowed_values: vec![],
+                kind: "normal".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            }),
+        )
+        .build()
+    }
+
+    /// A setting with an `id` primary key and a `name` column
+    fn setting(ui_options: UiOptions) -> Setting<()> {
+        let mut id = common_columns::integer("id", "ID", "The ID");
+        id.primary_key = true;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id, string_column("name")])
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "id".to_string()
+            ])))
+            .ui_options(ui_options)
+            .build()
+            .unwrap()
+    }
+
+    fn row(id: i64, name: &str) -> indexmap::IndexMap<String, Value> {
+        indexmap::indexmap! {
+            "id".to_string() => json!(id),
+            "name".to_string() => json!(name),
+        }
+    }
+
+    #[test]
+    fn confirmation_summary_hides_secrets() {
+        let mut token = string_column("token");
+        token.secret = true;
+
+        let mut setting = setting(UiOptions::default());
+        let mut columns = setting.columns.to_vec();
+        columns.push(token);
+        setting.columns = std::sync::Arc::new(columns);
+
+        let fields = indexmap::indexmap! {
+            "name".to_string() => json!("a"),
+            "token".to_string() => json!("hunter2"),
+        };
+
+        let summary = summarize_fields(&setting, &fields);
+        assert_eq!(summary, "**name**: ``a``\n**token**: (secret)");
+    }
+
+    #[test]
+    fn operations_footer_lists_other_supported_operations() {
+        let mut setting = setting(UiOptions::default());
+
+        assert_eq!(
+            operations_footer(&setting).as_deref(),
+            Some("Also available: /test create, /test update, /test delete")
+        );
+
+        setting.operations.create = None;
+        assert_eq!(
+            operations_footer(&setting).as_deref(),
+            Some("Also available: /test update, /test delete")
+        );
+
+        // Nothing to list if view is the only operation
+        setting.operations.update = None;
+        setting.operations.delete = None;
+        assert_eq!(operations_footer(&setting), None);
+    }
+
+    #[test]
+    fn custom_field_value_limit_is_honored() {
+        let setting = setting(UiOptions {
+            field_value_limit: Some(30),
+            ..Default::default()
+        });
+
+        let fields = indexmap::indexmap! { "name".to_string() => json!("é".repeat(100)) };
+        let summary = summarize_fields(&setting, &fields);
+        let value = summary.strip_prefix("**name**: ").unwrap();
+
+        assert_eq!(value, format!("```\n{}...```", "é".repeat(20)));
+
+        // Values within the limit are kept as is
+        let fields = indexmap::indexmap! { "name".to_string() => json!("é".repeat(26)) };
+        assert_eq!(
+            summarize_fields(&setting, &fields),
+            format!("**name**: ``{}``", "é".repeat(26))
+        );
+    }
+
+    #[test]
+    fn render_entry_renders_hand_built_row() {
+        let mut token = string_column("token");
+        token.secret = true;
+
+        let mut setting = setting(UiOptions::default());
+        let mut columns = setting.columns.to_vec();
+        columns.push(token);
+        setting.columns = std::sync::Arc::new(columns);
+
+        let mut row = row(1, "a");
+        row.insert("token".to_string(), json!("hunter2"));
+
+        let embed = serde_json::to_value(setting.render_entry(&row, "Entry")).unwrap();
+
+        assert_eq!(embed["title"], json!("Entry"));
+        assert_eq!(
+            embed["fields"],
+            json!([
+                { "name": "ID", "value": "1", "inline": true },
+                { "name": "name", "value": "``a``", "inline": true },
+                { "name": "token", "value": "``••••``", "inline": true },
+            ])
+        );
+    }
+
+    #[test]
+    fn filter_only_column_is_not_displayed() {
+        let mut guild_id = string_column("guild_id");
+        guild_id.primary_key = true;
+        guild_id.filter_only = true;
+
+        let mut setting = setting(UiOptions {
+            show_primary_key: true,
+            ..Default::default()
+        });
+        let mut columns = setting.columns.to_vec();
+        columns.insert(0, guild_id);
+        setting.columns = std::sync::Arc::new(columns);
+
+        let mut row = row(1, "a");
+        row.insert("guild_id".to_string(), json!("1234"));
+
+        let embed = serde_json::to_value(setting.render_entry(&row, "Entry")).unwrap();
+        let names = embed["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ID", "name"]);
+
+        assert_eq!(
+            primary_key_display(&setting, &row).as_deref(),
+            Some("ID: `1`")
+        );
+        assert!(!summarize_fields(&setting, &row).contains("guild_id"));
+    }
+
+    #[test]
+    fn primary_key_display_lists_primary_keys() {
+        let setting = setting(UiOptions::default());
+
+        assert_eq!(
+            primary_key_display(&setting, &row(1234, "a")).as_deref(),
+            Some("ID: `1234`")
+        );
+        assert_eq!(
+            primary_key_display(&setting, &indexmap::IndexMap::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn create_embed_shows_primary_key() {
+        let value = row(1234, "a");
+
+        let created_embed = |setting: &Setting<()>| {
+            let embed = add_primary_key(
+                create_embed(setting, std::slice::from_ref(&value), 0, || {
+                    "Created".to_string()
+                }),
+                setting,
+                &value,
+            );
+            serde_json::to_value(&embed).unwrap()
+        };
+
+        let embed = created_embed(&setting(UiOptions {
+            show_primary_key: true,
+            ..Default::default()
+        }));
+        assert_eq!(embed["description"], json!("ID: `1234`"));
+
+        // Disabled by default
+        let embed = created_embed(&setting(UiOptions::default()));
+        assert!(embed.get("description").is_none());
+    }
+
+    #[test]
+    fn confirm_outcome_only_executes_on_confirm() {
+        assert_eq!(confirm_outcome(Some("confirm")), ConfirmOutcome::Execute);
+        assert_eq!(confirm_outcome(Some("cancel")), ConfirmOutcome::Cancel);
+        assert_eq!(confirm_outcome(Some("other")), ConfirmOutcome::Cancel);
+        assert_eq!(confirm_outcome(None), ConfirmOutcome::Timeout);
+    }
+
+    #[tokio::test]
+    async fn executor_is_only_called_after_confirm() {
+        let executor = InMemorySettings::new(vec!["id".to_string()]);
+        let mut id = common_columns::integer("id", "ID", "The ID");
+        id.primary_key = true;
+        let confirmed_setting = SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id, string_column("name")])
+            .operations(SettingOperations::from(executor.clone()))
+            .build()
+            .unwrap();
+
+        // The same gate as the creator/updater/deleter: the operation only runs once `confirm` returns the
+        // button interaction, which it only does for `ConfirmOutcome::Execute`
+        let press = |custom_id: Option<&'static str>| {
+            let setting = &confirmed_setting;
+            async move {
+                if confirm_outcome(custom_id) == ConfirmOutcome::Execute {
+                    settings_create(setting, &(), row(1, "a"), None)
+                        .await
+                        .unwrap();
+                }
+            }
+        };
+
+        for custom_id in [None, Some("cancel"), Some("other")] {
+            press(custom_id).await;
+            assert!(executor.rows().is_empty(), "{:?}", custom_id);
+        }
+
+        press(Some("confirm")).await;
+        assert_eq!(executor.rows(), vec![row(1, "a")]);
+    }
+
+    #[test]
+    fn help_embeds_stay_within_discord_limits() {
+        let columns = (0..40)
+            .map(|i| {
+                ColumnBuilder::new(
+                    format!("column_{}", i),
+                    "N".repeat(300),
+                    ColumnType::new_scalar(InnerColumnType::String {
+                        min_length: None,
+                        max_length: None,
+                        allowed_values: vec!["a".repeat(200); 5],
+                        kind: "normal".to_string(),
+                        unchecked_ids: false,
+                        channel_types: vec![],
+                    }),
+                )
+                .description("D".repeat(500))
+                .primary_key(i == 0)
+                .build()
+            })
+            .collect::<Vec<_>>();
+
+        let setting: Setting<()> = SettingBuilder::new("test", "T".repeat(300), "S".repeat(2000))
+            .columns(columns)
+            .operations(SettingOperations::from(InMemorySettings::new(vec![
+                "column_0".to_string(),
+            ])))
+            .build()
+            .unwrap();
+
+        let embeds = build_help_embeds(&setting)
+            .into_iter()
+            .map(|embed| serde_json::to_value(&embed).unwrap())
+            .collect::<Vec<_>>();
+        assert!(embeds.len() > 1);
+
+        let mut names = Vec::new();
+        for embed in embeds.iter() {
+            let title = embed["title"].as_str().unwrap();
+            let description = embed["description"].as_str().unwrap();
+            let fields = embed["fields"].as_array().unwrap();
+            assert!(title.chars().count() <= 256);
+            assert!(fields.len() <= EMBED_FIELD_LIMIT);
+
+            let mut chars = title.chars().count() + description.chars().count();
+            for field in fields {
+                let name = field["name"].as_str().unwrap();
+                let value = field["value"].as_str().unwrap();
+                assert!(name.chars().count() <= 256);
+                assert!(value.chars().count() <= FIELD_VALUE_LIMIT);
+
+                chars += name.chars().count() + value.chars().count();
+                names.push(name.to_string());
+            }
+            assert!(chars <= EMBED_CHAR_LIMIT, "{}", chars);
+        }
+
+        assert_eq!(names.len(), 40);
+        for (i, name) in names.iter().enumerate() {
+            assert!(name.contains(&format!("(column_{})", i)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn truncate_chars_keeps_multibyte_characters_at_the_cut() {
+        for max in [100, 97, 50, 47] {
+            for c in ['é', 'ñ', '🦀', '👍'] {
+                let s = format!("{}{}{}", "a".repeat(max - 1), c, "b".repeat(10));
+
+                let truncated = truncate_chars(&s, max);
+                assert_eq!(truncated.chars().count(), max);
+                assert!(truncated.ends_with(c));
+            }
+        }
+
+        assert_eq!(truncate_chars("héllo", 10), "héllo");
+    }
+
+    #[test]
+    fn truncate_with_ellipsis_handles_multibyte_characters_at_the_cut() {
+        for max in [100, 50] {
+            for c in ['é', 'ñ', '🦀', '👍'] {
+                // The character right before the ellipsis, the first one replaced by it and the last one kept
+                // without truncation
+                for position in [max - 4, max - 3, max - 1] {
+                    let mut s: Vec<char> = "a".repeat(max + 10).chars().collect();
+                    s[position] = c;
+                    let s: String = s.into_iter().collect();
+
+                    let truncated = truncate_with_ellipsis(&s, max);
+                    assert_eq!(truncated.chars().count(), max);
+                    assert!(truncated.ends_with("..."));
+                    assert_eq!(truncated.contains(c), position < max - 3);
+                }
+
+                // Strings of exactly `max` characters are kept as is
+                let s = c.to_string().repeat(max);
+                assert_eq!(truncate_with_ellipsis(&s, max), s);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_code_block_handles_multibyte_characters_at_the_cut() {
+        for filler in ["é", "🦀", "日本"] {
+            for offset in 35..45 {
+                let v = format!("{}{}", "a".repeat(offset), filler.repeat(30));
+                let block = truncated_code_block(&v, 50);
+
+                assert!(block.chars().count() <= 50, "{}", block);
+                assert!(block.starts_with("```\n"), "{}", block);
+                assert!(block.ends_with("...```"), "{}", block);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_code_block_drops_dangling_backslash() {
+        // The backslash escaping the backtick ends up right at the cut
+        let v = format!("{}\\`{}", "a".repeat(39), "b".repeat(30));
+        let block = truncated_code_block(&v, 50);
+
+        assert_eq!(block, format!("```\n{}...```", "a".repeat(39)));
+
+        let v = "\\\\\\`".repeat(30);
+        let block = truncated_code_block(&v, 50);
+
+        assert!(block.chars().count() <= 50, "{}", block);
+        assert!(
+            !block.trim_end_matches("...```").ends_with('\\'),
+            "{}",
+            block
+        );
+        assert!(block.ends_with("```"), "{}", block);
+    }
+    #[test]
+    fn clamp_page_index_stays_in_bounds() {
+        // No rows, the paginator stays on the first page
+        assert_eq!(clamp_page_index(0, 0), 0);
+        assert_eq!(clamp_page_index(1, 0), 0);
+        assert_eq!(clamp_page_index(usize::MAX, 0), 0);
+
+        // A single row
+        assert_eq!(clamp_page_index(0, 1), 0);
+        assert_eq!(clamp_page_index(1, 1), 0);
+
+        // Many rows
+        assert_eq!(clamp_page_index(0, 10), 0);
+        assert_eq!(clamp_page_index(9, 10), 9);
+        assert_eq!(clamp_page_index(10, 10), 9);
+        assert_eq!(clamp_page_index(usize::MAX, 10), 9);
+    }
+
+    #[test]
+    fn navigating_past_the_edges_is_clamped() {
+        for total in [0usize, 1, 10] {
+            // "next" on the last page
+            let last = total.saturating_sub(1);
+            assert_eq!(clamp_page_index(last.saturating_add(1), total), last);
+
+            // "previous" on the first page
+            assert_eq!(clamp_page_index(0usize.saturating_sub(1), total), 0);
+        }
+    }
+
+    #[test]
+    fn parse_page_number_validates_the_range() {
+        assert_eq!(parse_page_number("1", 10), Ok(0));
+        assert_eq!(parse_page_number(" 10 ", 10), Ok(9));
+        assert_eq!(parse_page_number("1", 1), Ok(0));
+
+        assert!(parse_page_number("0", 10).is_err());
+        assert!(parse_page_number("11", 10).is_err());
+        assert!(parse_page_number("2", 1).is_err());
+        assert!(parse_page_number("-1", 10).is_err());
+        assert!(parse_page_number("abc", 10).is_err());
+        assert!(parse_page_number("", 10).is_err());
+
+        // There are no pages to jump to
+        assert!(parse_page_number("0", 0).is_err());
+        assert!(parse_page_number("1", 0).is_err());
+    }
+}
@@ -1,5 +1,8 @@
+use crate::cfg::validate_value;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>; // This is constant and should be copy pasted
@@ -16,6 +19,22 @@ pub enum ColumnType {
     Array {
         /// The inner type of the array
         inner: InnerColumnType,
+
+        /// The minimum number of items in the array, if any
+        #[serde(default)]
+        min_items: Option<usize>,
+
+        /// The maximum number of items in the array, if any
+        #[serde(default)]
+        max_items: Option<usize>,
+
+        /// Whether the array must not contain duplicate items
+        ///
+        /// Items are compared after validation, so e.g. a Float given as `"1.5"` and `1.5` are duplicates.
+        /// Floats are compared numerically (`0.0` and `-0.0` are equal) and JSON values structurally
+        /// (the order of keys in objects does not matter)
+        #[serde(default)]
+        unique: bool,
     },
 }
 
@@ -32,12 +51,33 @@ impl ColumnType {
         matches!(self, ColumnType::Scalar { .. })
     }
 
+    /// Returns the inner column type
+    pub fn inner(&self) -> &InnerColumnType {
+        match self {
+            ColumnType::Scalar { inner } => inner,
+            ColumnType::Array { inner, .. } => inner,
+        }
+    }
+
+    /// Returns a human readable label for the column type (e.g. `List of Integer`)
+    pub fn type_label(&self) -> String {
+        match self {
+            ColumnType::Scalar { inner } => inner.type_label(),
+            ColumnType::Array { inner, .. } => format!("List of {}", inner.type_label()),
+        }
+    }
+
     pub fn new_scalar(inner: InnerColumnType) -> Self {
         ColumnType::Scalar { inner }
     }
 
     pub fn new_array(inner: InnerColumnType) -> Self {
-        ColumnType::Array { inner }
+        ColumnType::Array {
+            inner,
+            min_items: None,
+            max_items: None,
+            unique: false,
+        }
     }
 }
 
@@ -48,27 +88,210 @@ pub enum InnerColumnType {
         min_length: Option<usize>,
         max_length: Option<usize>,
         allowed_values: Vec<String>, // If empty, all values are allowed
-        kind: String, // e.g. uuid, textarea, channel, user, role, interval, timestamp etc.
+        kind: String, // e.g. uuid, textarea, channel, user, role, interval, timestamp, url etc.
+
+        /// Do not check that `channel`/`user`/`role`/`guild_id` kind values are valid Discord IDs
+        ///
+        /// This is useful for bots that store placeholder values in such columns
+        #[serde(default)]
+        unchecked_ids: bool,
+
+        /// For `channel` kind columns, the names of the allowed channel types (e.g. `text`, `voice`, `forum`)
+        ///
+        /// If empty, all channel types are allowed
+        #[serde(default)]
+        channel_types: Vec<String>,
+    },
+    Integer {
+        /// The minimum (inclusive) allowed value
+        #[serde(default)]
+        min: Option<i64>,
+
+        /// The maximum (inclusive) allowed value
+        #[serde(default)]
+        max: Option<i64>,
+
+        /// The allowed values, if empty all values (in range) are allowed
+        ///
+        /// If there are 25 or less allowed values, they are offered as choices in commands, otherwise they are
+        /// offered through autocomplete
+        #[serde(default)]
+        allowed_values: Vec<i64>,
+    },
+    Float {
+        /// The minimum (inclusive) allowed value
+        #[serde(default)]
+        min: Option<f64>,
+
+        /// The maximum (inclusive) allowed value
+        #[serde(default)]
+        max: Option<f64>,
+
+        /// The allowed values, if empty all values (in range) are allowed
+        ///
+        /// If there are 25 or less allowed values, they are offered as choices in commands, otherwise they are
+        /// offered through autocomplete
+        #[serde(default)]
+        allowed_values: Vec<f64>,
+    },
+    /// An exact decimal number (e.g. a currency amount or tax rate), backed by `rust_decimal::Decimal`
+    ///
+    /// Values are represented as JSON strings (e.g. `"12.50"`) so that round-trips are lossless. Numbers are
+    /// accepted as input as well but may already have lost precision when they were parsed as floats
+    Decimal {
+        /// The minimum (inclusive) allowed value
+        #[serde(default)]
+        min: Option<rust_decimal::Decimal>,
+
+        /// The maximum (inclusive) allowed value
+        #[serde(default)]
+        max: Option<rust_decimal::Decimal>,
+
+        /// The maximum number of digits after the decimal point (e.g. 2 for most currencies)
+        #[serde(default)]
+        max_scale: Option<u32>,
     },
-    Integer {},
-    Float {},
     BitFlag {
         /// The bit flag values
         values: indexmap::IndexMap<String, i64>,
+
+        /// Reject values containing bits not present in `values` instead of silently discarding them
+        #[serde(default)]
+        strict: bool,
+    },
+    Boolean {
+        /// Only accept `true`/`false` when parsing from a string, otherwise common spellings
+        /// such as `yes`/`no`, `on`/`off` and `1`/`0` are accepted as well (case-insensitive)
+        #[serde(default)]
+        strict: bool,
     },
-    Boolean {},
     Json {
         kind: String, // e.g. templateref etc.
         max_bytes: Option<usize>,
     },
+    /// A structured value (JSON object) whose fields are validated against sub-columns
+    Object {
+        /// The fields of the object, keyed by the column id
+        ///
+        /// Only the id, column_type, nullable and default of the sub-columns are used for validation
+        fields: Vec<Column>,
+    },
 }
 
+impl InnerColumnType {
+    /// Returns a human readable label for the inner column type (e.g. `String (channel)`)
+    pub fn type_label(&self) -> String {
+        match self {
+            InnerColumnType::String { kind, .. } => {
+                if kind.is_empty() || kind == "normal" {
+                    "String".to_string()
+                } else {
+                    format!("String ({})", kind)
+                }
+            }
+            InnerColumnType::Integer { .. } => "Integer".to_string(),
+            InnerColumnType::Float { .. } => "Float".to_string(),
+            InnerColumnType::Decimal { .. } => "Decimal".to_string(),
+            InnerColumnType::BitFlag { .. } => "Bit Flags".to_string(),
+            InnerColumnType::Boolean { .. } => "Boolean".to_string(),
+            InnerColumnType::Json { kind, .. } => {
+                if kind.is_empty() {
+                    "JSON".to_string()
+                } else {
+                    format!("JSON ({})", kind)
+                }
+            }
+            InnerColumnType::Object { .. } => "Object".to_string(),
+        }
+    }
+
+    /// Returns the values allowed for the column type as strings, an empty slice allows all values
+    ///
+    /// Integer and Float allowed values are formatted with `to_string`. Validation, command choices,
+    /// autocomplete and help must all go through this so that a value offered to the user is always
+    /// one that passes validation
+    pub fn allowed_values(&self) -> Cow<'_, [String]> {
+        match self {
+            InnerColumnType::String { allowed_values, .. } => Cow::Borrowed(allowed_values),
+            InnerColumnType::Integer { allowed_values, .. } => {
+                Cow::Owned(allowed_values.iter().map(|v| v.to_string()).collect())
+            }
+            InnerColumnType::Float { allowed_values, .. } => {
+                Cow::Owned(allowed_values.iter().map(|v| v.to_string()).collect())
+            }
+            _ => Cow::Borrowed(&[]),
+        }
+    }
+}
+
+/// Suggested values for a column
+///
+/// Unlike the allowed values of a column, suggestions do not restrict what may be entered
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ColumnSuggestion {
-    Static { suggestions: Vec<String> },
+    /// A fixed list of suggestions, offered as autocomplete choices and listed in the help and form embeds
+    Static {
+        suggestions: Vec<String>,
+    },
+    /// Suggestions fetched at runtime from the `autocompleter` of the setting, these are only offered as
+    /// autocomplete choices while typing
+    Dynamic {},
     None {},
 }
 
+impl ColumnSuggestion {
+    /// Returns the static suggestions, an empty slice for dynamic or no suggestions
+    pub fn static_suggestions(&self) -> &[String] {
+        match self {
+            ColumnSuggestion::Static { suggestions } => suggestions,
+            _ => &[],
+        }
+    }
+}
+
+/// How the value of a secret column is displayed in views
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SecretDisplay {
+    /// The value is replaced with a placeholder (`••••`) so that users can see that it is set
+    #[default]
+    Masked,
+    /// Like `Masked` but the last 4 characters of values longer than 8 characters are shown (e.g. `••••abcd`)
+    LastFour,
+    /// The column is removed entirely, for cases where even the presence of a value is sensitive
+    Omit,
+}
+
+/// The placeholder shown in place of (masked) secret values
+pub const SECRET_PLACEHOLDER: &str = "••••";
+
+impl SecretDisplay {
+    /// Returns the value to display in place of a secret value, or None if the column should be omitted
+    ///
+    /// Null values are kept as-is so that unset secrets can be told apart from set ones
+    pub fn mask(&self, value: &Value) -> Option<Value> {
+        if *self == SecretDisplay::Omit {
+            return None;
+        }
+
+        let value = match value {
+            Value::Null => return Some(Value::Null),
+            Value::String(s) => s.to_string(),
+            v => v.to_string(),
+        };
+
+        let len = value.chars().count();
+
+        match self {
+            SecretDisplay::LastFour if len > 8 => Some(Value::String(format!(
+                "{}{}",
+                SECRET_PLACEHOLDER,
+                value.chars().skip(len - 4).collect::<String>()
+            ))),
+            _ => Some(Value::String(SECRET_PLACEHOLDER.to_string())),
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Column {
     /// The ID of the column on the database
@@ -86,21 +309,260 @@ pub struct Column {
     /// Whether or not the column is a primary key
     pub primary_key: bool,
 
+    /// Whether or not the column is part of the alternate key
+    ///
+    /// An alternate key is a set of (unique) non-primary key columns that can be used to delete an entry
+    /// without knowing its primary key. Entries are resolved to their primary key through the view operation
+    #[serde(default)]
+    pub alternate_key: bool,
+
     /// Whether or not the column is nullable
     ///
     /// Note that the point where nullability is checked may vary but will occur after pre_checks are executed
     pub nullable: bool,
 
-    /// Suggestions to display
+    /// Suggested values, see `ColumnSuggestion`
     pub suggestions: ColumnSuggestion,
 
     /// A secret field that is not shown to the user
     pub secret: bool,
 
+    /// How the value of a secret column is displayed in views, ignored for non-secret columns
+    ///
+    /// Values returned by create/update are never masked as they are the user's own input
+    #[serde(default)]
+    pub secret_display: SecretDisplay,
+
     /// For which operations should the field be ignored for (essentially, read only)
     ///
     /// Semantics are defined by the Executor
     pub ignored_for: Vec<OperationType>,
+
+    /// The surfaces the column is exposed on. If empty, the column is exposed on all surfaces
+    #[serde(default)]
+    pub surfaces: Vec<Surface>,
+
+    /// Whether or not the column is only used to scope queries (e.g. a partition key)
+    ///
+    /// Filter-only columns are never displayed or offered as command options but are still
+    /// accepted in the filters passed to view and in primary key matching
+    #[serde(default)]
+    pub filter_only: bool,
+
+    /// If set, the column is deprecated. This should be a message pointing users to the replacement
+    ///
+    /// Deprecated columns still function as normal
+    #[serde(default)]
+    pub deprecated: Option<String>,
+
+    /// The value to use on create when no value (null) is provided for the column
+    ///
+    /// The default is validated against the column type like any other value
+    #[serde(default)]
+    pub default: Option<Value>,
+
+    /// Whether or not the column is indexed by the executor
+    ///
+    /// Primary key and indexed columns can be used (optionally) to filter views
+    #[serde(default)]
+    pub indexed: bool,
+
+    /// Localized names of the command option generated for the column, keyed by Discord locale (e.g. `de`)
+    #[serde(default)]
+    pub name_localizations: HashMap<String, String>,
+
+    /// Localized descriptions of the command option generated for the column, keyed by Discord locale
+    #[serde(default)]
+    pub description_localizations: HashMap<String, String>,
+
+    /// The group the column belongs to, if any
+    ///
+    /// Front-ends show the columns of a group together under a heading with the group's name, see
+    /// `Setting::columns_by_group`
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+impl Column {
+    /// Applies an override to the constraints of this column
+    ///
+    /// Errors if the override targets a constraint the column type does not have (e.g. `max_length` on an
+    /// Integer column) or if an overridden value does not fit the column type
+    pub fn apply_override(&mut self, column_override: &ColumnOverride) -> Result<(), Error> {
+        let inner = match self.column_type {
+            ColumnType::Scalar { ref mut inner } => inner,
+            ColumnType::Array { ref mut inner, .. } => inner,
+        };
+
+        let has_length =
+            column_override.min_length.is_some() || column_override.max_length.is_some();
+        let has_range = column_override.min.is_some() || column_override.max.is_some();
+
+        let unsupported = |field: &str, inner: &InnerColumnType| -> Error {
+            format!(
+                "Column `{}`: {} cannot be overridden for {} columns",
+                self.id,
+                field,
+                inner.type_label()
+            )
+            .into()
+        };
+
+        match inner {
+            InnerColumnType::String {
+                min_length,
+                max_length,
+                allowed_values,
+                ..
+            } => {
+                if has_range {
+                    return Err(unsupported("min/max", inner));
+                }
+
+                if let Some(ref v) = column_override.allowed_values {
+                    *allowed_values = v.clone();
+                }
+
+                if let Some(v) = column_override.min_length {
+                    *min_length = Some(v);
+                }
+
+                if let Some(v) = column_override.max_length {
+                    *max_length = Some(v);
+                }
+            }
+            InnerColumnType::Integer {
+                min,
+                max,
+                allowed_values,
+            } => {
+                if has_length {
+                    return Err(unsupported("min_length/max_length", inner));
+                }
+
+                let as_integer = |v: &serde_json::Number| {
+                    v.as_i64().ok_or_else(|| {
+                        format!("Column `{}`: expected an integer but got {}", self.id, v)
+                    })
+                };
+
+                if let Some(ref v) = column_override.allowed_values {
+                    *allowed_values = v
+                        .iter()
+                        .map(|v| {
+                            v.parse::<i64>().map_err(|_| {
+                                format!("Column `{}`: expected an integer but got {}", self.id, v)
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+
+                if let Some(ref v) = column_override.min {
+                    *min = Some(as_integer(v)?);
+                }
+
+                if let Some(ref v) = column_override.max {
+                    *max = Some(as_integer(v)?);
+                }
+            }
+            InnerColumnType::Float {
+                min,
+                max,
+                allowed_values,
+            } => {
+                if has_length {
+                    return Err(unsupported("min_length/max_length", inner));
+                }
+
+                if let Some(ref v) = column_override.allowed_values {
+                    *allowed_values = v
+                        .iter()
+                        .map(|v| {
+                            v.parse::<f64>().map_err(|_| {
+                                format!("Column `{}`: expected a number but got {}", self.id, v)
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+
+                if let Some(ref v) = column_override.min {
+                    *min = v.as_f64();
+                }
+
+                if let Some(ref v) = column_override.max {
+                    *max = v.as_f64();
+                }
+            }
+            _ => {
+                if column_override.allowed_values.is_some() {
+                    return Err(unsupported("allowed_values", inner));
+                }
+
+                if has_length {
+                    return Err(unsupported("min_length/max_length", inner));
+                }
+
+                if has_range {
+                    return Err(unsupported("min/max", inner));
+                }
+            }
+        }
+
+        if let Some(nullable) = column_override.nullable {
+            self.nullable = nullable;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the description of the column, prefixed with the deprecation notice if any
+    ///
+    /// The notice is prefixed (and not appended) so that it survives truncation
+    pub fn description_with_deprecation(&self) -> String {
+        match self.deprecated {
+            Some(ref note) => format!("(Deprecated: {}) {}", note, self.description),
+            None => self.description.to_string(),
+        }
+    }
+
+    /// Returns whether the column is exposed on the given surface
+    pub fn is_exposed_on(&self, surface: Surface) -> bool {
+        self.surfaces.is_empty() || self.surfaces.contains(&surface)
+    }
+
+    /// Returns whether the column must be provided for the given operation
+    ///
+    /// For updates, only primary keys are required as the rest of the entry may be autofilled. For deletes,
+    /// only primary keys are ever required
+    ///
+    /// Note that a setting with an alternate key does not require the primary key for deletes, use
+    /// `Setting::is_column_required_for_operation_type` to account for this
+    pub fn is_required_for_operation_type(&self, operation_type: OperationType) -> bool {
+        // View filters are always optional
+        if operation_type == OperationType::View {
+            return false;
+        }
+
+        if (operation_type == OperationType::Update || operation_type == OperationType::Delete)
+            && !self.primary_key
+        {
+            return false;
+        }
+
+        if operation_type == OperationType::Create && self.default.is_some() {
+            return false;
+        }
+
+        !self.nullable
+    }
+
+    /// Returns whether views may be sorted by the column
+    ///
+    /// Only primary key and indexed columns are sortable, secret columns are never sortable as the
+    /// ordering would leak information about their values
+    pub fn is_sortable(&self) -> bool {
+        (self.primary_key || self.indexed) && !self.secret
+    }
 }
 
 impl PartialEq for Column {
@@ -109,6 +571,49 @@ impl PartialEq for Column {
     }
 }
 
+/// A surface (front-end) through which a column may be exposed
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Surface {
+    /// Generated (slash) commands
+    Command,
+    /// Web dashboards
+    Dashboard,
+    /// Programmatic API consumers
+    Api,
+}
+
+/// Overrides for the constraints of a column, used to customize a setting per guild (e.g. a higher max for premium guilds)
+///
+/// Only the following are overridable, fields left as None keep the value from the column:
+/// - `allowed_values` (String, Integer and Float columns)
+/// - `min_length` and `max_length` (String columns)
+/// - `min` and `max` (Integer and Float columns)
+/// - `nullable`
+///
+/// Overriding a constraint the column type does not have is an error
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ColumnOverride {
+    /// The allowed values of the column, an empty list allows all values
+    ///
+    /// For Integer and Float columns, each value must parse as a number of the column's type
+    pub allowed_values: Option<Vec<String>>,
+
+    /// The minimum length of the column
+    pub min_length: Option<usize>,
+
+    /// The maximum length of the column
+    pub max_length: Option<usize>,
+
+    /// The minimum (inclusive) value of the column
+    pub min: Option<serde_json::Number>,
+
+    /// The maximum (inclusive) value of the column
+    pub max: Option<serde_json::Number>,
+
+    /// Whether or not the column is nullable
+    pub nullable: Option<bool>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, serde::Serialize, serde::Deserialize)]
 #[allow(dead_code)]
 pub enum OperationType {
@@ -118,6 +623,18 @@ pub enum OperationType {
     Delete,
 }
 
+impl OperationType {
+    /// Returns the name of the (sub)command generated for the operation
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            OperationType::View => "view",
+            OperationType::Create => "create",
+            OperationType::Update => "update",
+            OperationType::Delete => "delete",
+        }
+    }
+}
+
 impl std::fmt::Display for OperationType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -140,7 +657,10 @@ pub struct Setting<SettingsData: Clone> {
     /// The description of the option
     pub description: String,
 
-    /// Title template, used for the title of the embed
+    /// Title template, used for the title of the embeds of an entry
+    ///
+    /// Column values are interpolated with `{column_id}` placeholders (see `Setting::render_title`),
+    /// an empty template uses the name of the setting
     pub title_template: String,
 
     /// The columns for this option
@@ -149,6 +669,193 @@ pub struct Setting<SettingsData: Clone> {
     /// The supported operations for this option
     #[serde(skip_deserializing)]
     pub operations: SettingOperations<SettingsData>,
+
+    /// Options controlling how the setting is displayed by front-ends
+    #[serde(default)]
+    pub ui_options: UiOptions,
+
+    /// The id of an integer column used for optimistic concurrency control, if any
+    ///
+    /// When set, updates must supply the version of the entry they were based on (as returned by view)
+    /// and fail if the entry has since been modified. The version is incremented on every update
+    #[serde(default)]
+    pub version_column: Option<String>,
+
+    /// Deprecated operations of the setting along with a message pointing users to the replacement
+    ///
+    /// Deprecated operations still function as normal
+    #[serde(default)]
+    pub deprecated_operations: HashMap<OperationType, String>,
+
+    /// Whether view should error if the executor does not return a non-nullable column at all
+    ///
+    /// By default, missing columns are treated as null. Strict mode helps catch query/schema drift
+    #[serde(default)]
+    pub strict_columns: bool,
+
+    /// Localized names of the command generated for the setting, keyed by Discord locale (e.g. `de`)
+    ///
+    /// Locales that are missing fall back to the setting's id
+    #[serde(default)]
+    pub name_localizations: HashMap<String, String>,
+
+    /// Localized descriptions of the command generated for the setting, keyed by Discord locale
+    ///
+    /// Locales that are missing fall back to the setting's description
+    #[serde(default)]
+    pub description_localizations: HashMap<String, String>,
+
+    /// The (Discord) permission bits a member needs to use the setting's commands, if any
+    ///
+    /// This is enforced at two layers:
+    /// - as the default member permissions of the generated command. This only hides the command by
+    ///   default and can be overridden by server admins (and does not apply to commands created with a root)
+    /// - as a runtime check in the subcommand callbacks before any operation is executed, this
+    ///   cannot be overridden
+    #[serde(default)]
+    pub required_permissions: Option<u64>,
+
+    /// Context menu (right click) commands generated for the setting, see `ContextMenu`
+    #[serde(default)]
+    pub context_menus: Vec<ContextMenu>,
+}
+
+/// The target of a context menu command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextMenuKind {
+    /// Shown when right clicking a user
+    User,
+    /// Shown when right clicking a message
+    Message,
+}
+
+/// A context menu (right click) command bound to a setting
+///
+/// The id of the targeted user or message is put into `target_column` and the operation is then performed
+/// with that field already filled in, e.g. a Message context menu creating an auto-response for the message
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ContextMenu {
+    /// Whether the command targets users or messages
+    pub kind: ContextMenuKind,
+
+    /// The name of the command as shown in the menu, unlike chat input commands this may contain spaces
+    /// and uppercase letters (1-32 characters)
+    pub name: String,
+
+    /// The id of the (scalar string) column the id of the target is put in
+    pub target_column: String,
+
+    /// The operation performed on the target
+    pub operation_type: OperationType,
+}
+
+/// The channel type names accepted in the `channel_types` of a String column
+pub const CHANNEL_TYPE_NAMES: [&str; 11] = [
+    "text",
+    "private",
+    "voice",
+    "group_dm",
+    "category",
+    "news",
+    "news_thread",
+    "public_thread",
+    "private_thread",
+    "stage",
+    "forum",
+];
+
+/// The maximum length of an embed field value allowed by Discord
+pub const FIELD_VALUE_LIMIT: usize = 1024;
+
+/// Options controlling how a setting is displayed by front-ends
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UiOptions {
+    /// Whether to prominently show the primary key (e.g. `ID: X`) of the entry after a create/update
+    ///
+    /// This is especially useful for settings with auto-generated ids
+    #[serde(default)]
+    pub show_primary_key: bool,
+
+    /// The operations that must be confirmed by the user (Confirm/Cancel) before being executed
+    ///
+    /// Deletes are always confirmed unless `skip_delete_confirmation` is set
+    #[serde(default)]
+    pub requires_confirmation: Vec<OperationType>,
+
+    /// Whether deletes should be executed immediately without asking the user for confirmation
+    #[serde(default)]
+    pub skip_delete_confirmation: bool,
+
+    /// Whether to check that `guild_id` kind columns of viewed entries match the invoking guild
+    #[serde(default)]
+    pub check_guild_id: bool,
+
+    /// Whether to list the other supported operations (and their commands) in the footer of the viewer
+    #[serde(default)]
+    pub show_operations_footer: bool,
+
+    /// The length (in characters) at which displayed field values are truncated, defaults to (and is capped at)
+    /// `FIELD_VALUE_LIMIT`
+    #[serde(default)]
+    pub field_value_limit: Option<usize>,
+
+    /// How long (in seconds) interactive UIs such as the viewer wait for the user before expiring,
+    /// defaults to 180 seconds
+    #[serde(default)]
+    pub interaction_timeout: Option<u64>,
+
+    /// The color (as a RGB integer, e.g. `0x5865F2`) of embeds generated for the setting
+    #[serde(default)]
+    pub embed_color: Option<u32>,
+
+    /// The footer text of embeds generated for the setting
+    #[serde(default)]
+    pub embed_footer: Option<String>,
+
+    /// The number of entries shown on each page of the viewer, defaults to 1 and is capped at `MAX_ROWS_PER_PAGE`
+    ///
+    /// When more than one entry is shown, each entry is rendered as a single field titled using the title template
+    #[serde(default)]
+    pub rows_per_page: Option<usize>,
+}
+
+/// The maximum number of entries shown on a page of the viewer, Discord allows at most 25 fields per embed
+pub const MAX_ROWS_PER_PAGE: usize = 25;
+
+/// The default time interactive UIs wait for the user before expiring
+pub const DEFAULT_INTERACTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(180);
+
+impl UiOptions {
+    /// Returns whether the given operation must be confirmed by the user before being executed
+    pub fn requires_confirmation_for(&self, operation_type: OperationType) -> bool {
+        if operation_type == OperationType::Delete && !self.skip_delete_confirmation {
+            return true;
+        }
+
+        self.requires_confirmation.contains(&operation_type)
+    }
+
+    /// Returns the length (in characters) at which displayed field values should be truncated
+    pub fn field_value_limit(&self) -> usize {
+        match self.field_value_limit {
+            Some(limit) => usize::min(limit, FIELD_VALUE_LIMIT),
+            None => FIELD_VALUE_LIMIT,
+        }
+    }
+
+    /// Returns the number of entries shown on each page of the viewer
+    pub fn rows_per_page(&self) -> usize {
+        self.rows_per_page.unwrap_or(1).clamp(1, MAX_ROWS_PER_PAGE)
+    }
+
+    /// Returns how long interactive UIs should wait for the user before expiring
+    pub fn interaction_timeout(&self) -> std::time::Duration {
+        match self.interaction_timeout {
+            Some(secs) => std::time::Duration::from_secs(secs),
+            None => DEFAULT_INTERACTION_TIMEOUT,
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -164,6 +871,18 @@ pub struct SettingOperations<SettingsData: Clone> {
 
     /// How to delete this setting
     pub delete: Option<Arc<dyn SettingDeleter<SettingsData>>>,
+
+    /// Cross-column validation, ran after the columns have been validated but before create/update/delete
+    pub validator: Option<Arc<dyn SettingValidator<SettingsData>>>,
+
+    /// Authorization, ran before any operation is executed
+    pub authorizer: Option<Arc<dyn SettingAuthorizer<SettingsData>>>,
+
+    /// Hooks ran after a successful create/update/delete (e.g. for audit logging)
+    pub hooks: Option<Arc<dyn SettingHooks<SettingsData>>>,
+
+    /// Provides autocomplete choices fetched at runtime, falling back to the static allowed values of the column
+    pub autocompleter: Option<Arc<dyn SettingAutocompleter<SettingsData>>>,
 }
 
 impl<SettingsData: Clone> std::fmt::Debug for SettingOperations<SettingsData> {
@@ -177,6 +896,13 @@ impl<SettingsData: Clone> serde::Serialize for SettingOperations<SettingsData> {
     where
         S: serde::Serializer,
     {
+        self.supported_operations().serialize(serializer)
+    }
+}
+
+impl<SettingsData: Clone> SettingOperations<SettingsData> {
+    /// Returns the operations that are supported
+    pub fn supported_operations(&self) -> Vec<OperationType> {
         let mut supported_operations = Vec::new();
 
         if let Some(_v) = &self.view {
@@ -195,7 +921,7 @@ impl<SettingsData: Clone> serde::Serialize for SettingOperations<SettingsData> {
             supported_operations.push(OperationType::Delete);
         }
 
-        supported_operations.serialize(serializer)
+        supported_operations
     }
 }
 
@@ -205,6 +931,496 @@ impl<SettingsData: Clone> PartialEq for Setting<SettingsData> {
     }
 }
 
+impl<SettingsData: Clone> Setting<SettingsData> {
+    /// Checks that the setting's schema is internally consistent, returning an error describing the first problem found
+    pub fn validate_schema(&self) -> Result<(), Error> {
+        let supported_operations = self.supported_operations();
+
+        if supported_operations.is_empty() {
+            return Err(format!("Setting `{}`: no operations are supported", self.id).into());
+        }
+
+        let mut ids = std::collections::HashSet::new();
+
+        for column in self.columns.iter() {
+            if !ids.insert(column.id.as_str()) {
+                return Err(
+                    format!("Setting `{}`: duplicate column id `{}`", self.id, column.id).into(),
+                );
+            }
+        }
+
+        // Update and delete find the entry to act on through the primary key
+        for operation_type in [OperationType::Update, OperationType::Delete] {
+            if supported_operations.contains(&operation_type)
+                && !self.columns.iter().any(|c| c.primary_key)
+            {
+                return Err(format!(
+                    "Setting `{}`: {} is supported but there is no primary key column",
+                    self.id, operation_type
+                )
+                .into());
+            }
+        }
+
+        for placeholder in template_placeholders(&self.title_template) {
+            if self.column(&placeholder).is_none() {
+                return Err(format!(
+                    "Setting `{}`: title template references unknown column `{}`",
+                    self.id, placeholder
+                )
+                .into());
+            }
+        }
+
+        for column in self.columns.iter() {
+            // A primary key that is ignored for update/delete makes those operations impossible
+            if column.primary_key {
+                for operation_type in [OperationType::Update, OperationType::Delete] {
+                    if supported_operations.contains(&operation_type)
+                        && column.ignored_for.contains(&operation_type)
+                    {
+                        return Err(format!(
+                            "Setting `{}`: primary key column `{}` cannot be ignored for {}",
+                            self.id, column.id, operation_type
+                        )
+                        .into());
+                    }
+                }
+            }
+
+            // Same for alternate keys and deletes
+            if column.alternate_key
+                && supported_operations.contains(&OperationType::Delete)
+                && column.ignored_for.contains(&OperationType::Delete)
+            {
+                return Err(format!(
+                    "Setting `{}`: alternate key column `{}` cannot be ignored for {}",
+                    self.id,
+                    column.id,
+                    OperationType::Delete
+                )
+                .into());
+            }
+
+            // Secret columns are never displayed, so a secret column that cannot be written either is useless
+            if column.secret
+                && column.ignored_for.contains(&OperationType::Create)
+                && column.ignored_for.contains(&OperationType::Update)
+            {
+                return Err(format!(
+                    "Setting `{}`: secret column `{}` cannot be ignored for both {} and {}",
+                    self.id,
+                    column.id,
+                    OperationType::Create,
+                    OperationType::Update
+                )
+                .into());
+            }
+        }
+
+        for column in self.columns.iter() {
+            if let InnerColumnType::String {
+                ref channel_types, ..
+            } = column.column_type.inner()
+            {
+                if let Some(name) = channel_types
+                    .iter()
+                    .find(|name| !CHANNEL_TYPE_NAMES.contains(&name.as_str()))
+                {
+                    return Err(format!(
+                        "Setting `{}`: column `{}` has unknown channel type `{}`",
+                        self.id, column.id, name
+                    )
+                    .into());
+                }
+            }
+
+            if let ColumnType::Array {
+                min_items: Some(min_items),
+                max_items: Some(max_items),
+                ..
+            } = column.column_type
+            {
+                if min_items > max_items {
+                    return Err(format!(
+                        "Setting `{}`: column `{}` has min_items ({}) greater than max_items ({})",
+                        self.id, column.id, min_items, max_items
+                    )
+                    .into());
+                }
+            }
+
+            // Catch misconfigured defaults early rather than on the first create
+            if let Some(ref default) = column.default {
+                validate_value(
+                    default.clone(),
+                    &column.column_type,
+                    &column.id,
+                    column.nullable,
+                )
+                .map_err(|e| {
+                    format!(
+                        "Setting `{}`: invalid default for column `{}`: {}",
+                        self.id, column.id, e
+                    )
+                })?;
+            }
+        }
+
+        let mut context_menus = std::collections::HashSet::new();
+        for context_menu in self.context_menus.iter() {
+            if !(1..=32).contains(&context_menu.name.chars().count()) {
+                return Err(format!(
+                    "Setting `{}`: context menu name `{}` must be 1-32 characters",
+                    self.id, context_menu.name
+                )
+                .into());
+            }
+
+            if !context_menus.insert((context_menu.kind, context_menu.name.as_str())) {
+                return Err(format!(
+                    "Setting `{}`: duplicate context menu `{}`",
+                    self.id, context_menu.name
+                )
+                .into());
+            }
+
+            let Some(column) = self.column(&context_menu.target_column) else {
+                return Err(format!(
+                    "Setting `{}`: context menu `{}` targets unknown column `{}`",
+                    self.id, context_menu.name, context_menu.target_column
+                )
+                .into());
+            };
+
+            if !matches!(
+                column.column_type,
+                ColumnType::Scalar {
+                    inner: InnerColumnType::String { .. }
+                }
+            ) {
+                return Err(format!(
+                    "Setting `{}`: context menu `{}` target column `{}` must be a scalar string",
+                    self.id, context_menu.name, column.id
+                )
+                .into());
+            }
+
+            if !supported_operations.contains(&context_menu.operation_type) {
+                return Err(format!(
+                    "Setting `{}`: context menu `{}` uses {} which is not supported",
+                    self.id, context_menu.name, context_menu.operation_type
+                )
+                .into());
+            }
+        }
+
+        if let Some(ref version_column) = self.version_column {
+            let Some(column) = self.column(version_column) else {
+                return Err(format!(
+                    "Setting `{}`: version column `{}` does not exist",
+                    self.id, version_column
+                )
+                .into());
+            };
+
+            if !matches!(column.column_type.inner(), InnerColumnType::Integer { .. })
+                || column.column_type.is_array()
+            {
+                return Err(format!(
+                    "Setting `{}`: version column `{}` must be a scalar integer",
+                    self.id, version_column
+                )
+                .into());
+            }
+
+            if column.ignored_for.contains(&OperationType::Update) {
+                return Err(format!(
+                    "Setting `{}`: version column `{}` cannot be ignored for {}",
+                    self.id,
+                    version_column,
+                    OperationType::Update
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the deprecation notes applicable to an operation given the fields that were provided
+    ///
+    /// This includes the note for the operation itself and for any deprecated columns present in `fields`
+    pub fn deprecation_notes(
+        &self,
+        operation_type: OperationType,
+        fields: &indexmap::IndexMap<String, Value>,
+    ) -> Vec<String> {
+        let mut notes = Vec::new();
+
+        if let Some(note) = self.deprecated_operations.get(&operation_type) {
+            notes.push(format!("{} is deprecated: {}", operation_type, note));
+        }
+
+        for column in self.columns.iter() {
+            let Some(ref note) = column.deprecated else {
+                continue;
+            };
+
+            if fields.contains_key(&column.id) {
+                notes.push(format!("{} is deprecated: {}", column.name, note));
+            }
+        }
+
+        notes
+    }
+
+    /// Renders the title template for an entry, e.g. `Config for {name}`
+    ///
+    /// Placeholders are column ids in braces, use `{{` and `}}` for literal braces. Missing, null and secret
+    /// values render as empty. Falls back to the name of the setting if the rendered title is empty
+    pub fn render_title(&self, row: &indexmap::IndexMap<String, Value>) -> String {
+        let title = render_template(&self.title_template, |key| {
+            if self.column(key).is_some_and(|c| c.secret) {
+                return String::new();
+            }
+
+            match row.get(key) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(s)) => s.to_string(),
+                Some(v) => v.to_string(),
+            }
+        });
+
+        let title = title.trim();
+
+        if title.is_empty() {
+            self.name.to_string()
+        } else {
+            title.to_string()
+        }
+    }
+
+    /// Returns the column with the given id
+    pub fn column(&self, id: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.id == id)
+    }
+
+    /// Returns the columns grouped by their `group`, ungrouped columns come first followed by the groups in
+    /// the order they first appear. Columns keep their declaration order within a group
+    pub fn columns_by_group(&self) -> Vec<(Option<&str>, Vec<&Column>)> {
+        let mut groups: indexmap::IndexMap<Option<&str>, Vec<&Column>> = indexmap::IndexMap::new();
+        groups.insert(None, Vec::new());
+
+        for column in self.columns.iter() {
+            groups
+                .entry(column.group.as_deref())
+                .or_default()
+                .push(column);
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, columns)| !columns.is_empty())
+            .collect()
+    }
+
+    /// Returns whether the setting has a column with the given id
+    ///
+    /// This should be used to check keys when building filter/state maps by hand
+    pub fn has_column(&self, id: &str) -> bool {
+        self.column(id).is_some()
+    }
+
+    /// Returns a copy of the entry with its secret columns masked (or removed) as set by their `SecretDisplay`
+    ///
+    /// This should be used before displaying entries that did not come from view (which already masks
+    /// secrets), e.g. the entry returned by an update
+    pub fn mask_secrets(
+        &self,
+        row: &indexmap::IndexMap<String, Value>,
+    ) -> indexmap::IndexMap<String, Value> {
+        row.iter()
+            .filter_map(|(key, value)| match self.column(key) {
+                Some(column) if column.secret => column
+                    .secret_display
+                    .mask(value)
+                    .map(|value| (key.clone(), value)),
+                _ => Some((key.clone(), value.clone())),
+            })
+            .collect()
+    }
+
+    /// Returns the operations supported by the setting
+    pub fn supported_operations(&self) -> Vec<OperationType> {
+        self.operations.supported_operations()
+    }
+
+    /// Returns a copy of the setting with the given column overrides (keyed by column id) applied
+    ///
+    /// This should be resolved per guild and the result used for both validation (the `cfg` functions)
+    /// and command generation so both agree on the constraints
+    ///
+    /// Errors if an override targets a column that does not exist or a constraint its column does not have
+    pub fn with_overrides(
+        &self,
+        overrides: &indexmap::IndexMap<String, ColumnOverride>,
+    ) -> Result<Self, Error> {
+        let mut setting = self.clone();
+
+        if overrides.is_empty() {
+            return Ok(setting);
+        }
+
+        if let Some(id) = overrides.keys().find(|id| !self.has_column(id)) {
+            return Err(format!(
+                "Setting `{}`: override for unknown column `{}`",
+                self.id, id
+            )
+            .into());
+        }
+
+        let mut columns = (*self.columns).clone();
+        for column in columns.iter_mut() {
+            if let Some(column_override) = overrides.get(&column.id) {
+                column.apply_override(column_override)?;
+            }
+        }
+
+        setting.columns = Arc::new(columns);
+        Ok(setting)
+    }
+
+    /// Returns a copy of the setting containing only the columns exposed on the given surface
+    ///
+    /// This should be used when exporting the schema of a setting to a specific front-end
+    pub fn for_surface(&self, surface: Surface) -> Self {
+        let mut setting = self.clone();
+        setting.columns = Arc::new(
+            self.columns
+                .iter()
+                .filter(|column| column.is_exposed_on(surface))
+                .cloned()
+                .collect(),
+        );
+        setting
+    }
+
+    /// Returns the columns that are accepted as input for the given operation
+    ///
+    /// View only accepts the primary key and indexed columns (as filters) and Delete only accepts the primary
+    /// key and alternate key columns
+    fn input_columns(&self, operation_type: OperationType) -> impl Iterator<Item = &Column> {
+        self.columns.iter().filter(move |column| {
+            if column.ignored_for.contains(&operation_type) {
+                return false;
+            }
+
+            match operation_type {
+                OperationType::View => column.primary_key || column.indexed,
+                OperationType::Delete => column.primary_key || column.alternate_key,
+                _ => true,
+            }
+        })
+    }
+
+    /// Returns whether the column must be provided for the given operation
+    ///
+    /// This is the same as `Column::is_required_for_operation_type` except that deletes on a setting
+    /// with an alternate key accept either the primary key or the alternate key, so neither is required,
+    /// and that updates always require the version column (the version of the entry the update is based on)
+    pub fn is_column_required_for_operation_type(
+        &self,
+        column: &Column,
+        operation_type: OperationType,
+    ) -> bool {
+        if operation_type == OperationType::Delete && self.columns.iter().any(|c| c.alternate_key) {
+            return false;
+        }
+
+        if operation_type == OperationType::Update
+            && self.version_column.as_deref() == Some(column.id.as_str())
+        {
+            return true;
+        }
+
+        column.is_required_for_operation_type(operation_type)
+    }
+
+    /// Returns the ids of the columns that must be provided for the given operation
+    pub fn required_fields(&self, operation_type: OperationType) -> Vec<String> {
+        self.input_columns(operation_type)
+            .filter(|column| self.is_column_required_for_operation_type(column, operation_type))
+            .map(|column| column.id.to_string())
+            .collect()
+    }
+
+    /// Returns the ids of the columns that may optionally be provided for the given operation
+    pub fn optional_fields(&self, operation_type: OperationType) -> Vec<String> {
+        self.input_columns(operation_type)
+            .filter(|column| !self.is_column_required_for_operation_type(column, operation_type))
+            .map(|column| column.id.to_string())
+            .collect()
+    }
+}
+
+/// Renders a template, replacing each `{column}` placeholder with the result of `value_of`
+///
+/// `{{` and `}}` are escapes for literal braces. An unclosed `{` is kept as-is
+pub(crate) fn render_template(template: &str, mut value_of: impl FnMut(&str) -> String) -> String {
+    let mut rendered = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                let mut closed = false;
+
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+
+                    placeholder.push(c);
+                }
+
+                if closed {
+                    rendered.push_str(&value_of(placeholder.trim()));
+                } else {
+                    rendered.push('{');
+                    rendered.push_str(&placeholder);
+                }
+            }
+            c => rendered.push(c),
+        }
+    }
+
+    rendered
+}
+
+/// Returns the column ids referenced by `{column}` placeholders in a template
+pub(crate) fn template_placeholders(template: &str) -> Vec<String> {
+    let mut placeholders = Vec::new();
+
+    render_template(template, |placeholder| {
+        placeholders.push(placeholder.to_string());
+        String::new()
+    });
+
+    placeholders
+}
+
 /// Wraps `v` in the currently used wrapper
 ///
 /// Currently, this is an Arc for now
@@ -212,16 +1428,133 @@ pub fn settings_wrap<T>(v: T) -> Arc<T> {
     Arc::new(v)
 }
 
+/// The order in which the entries of a view should be returned
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SortBy {
+    /// The id of the (sortable) column to sort by
+    pub column: String,
+
+    /// Whether to sort in descending order, entries are sorted in ascending order by default
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// A filter on a column of a view, an operator along with its operand
+///
+/// Which operators make sense depends on the column:
+/// - `Eq`/`Ne`: any column
+/// - `Gt`/`Lt`: Integer, Float and Decimal columns as well as `timestamp` kind String columns
+/// - `In`: any scalar column, the operand is a list of values
+/// - `Contains`: Array columns (the array contains the element) and String columns (case-sensitive substring)
+///
+/// Equality filter maps (as accepted by `settings_view`) can be converted with `Filter::from_equality`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum Filter {
+    Eq(Value),
+    Ne(Value),
+    Gt(Value),
+    Lt(Value),
+    In(Vec<Value>),
+    Contains(Value),
+}
+
+impl Filter {
+    /// Returns the name of the operator, e.g. `gt`
+    pub fn operator(&self) -> &'static str {
+        match self {
+            Filter::Eq(_) => "eq",
+            Filter::Ne(_) => "ne",
+            Filter::Gt(_) => "gt",
+            Filter::Lt(_) => "lt",
+            Filter::In(_) => "in",
+            Filter::Contains(_) => "contains",
+        }
+    }
+
+    /// Converts an equality filter map into a map of `Filter::Eq` filters
+    pub fn from_equality(
+        filters: indexmap::IndexMap<String, Value>,
+    ) -> indexmap::IndexMap<String, Filter> {
+        filters
+            .into_iter()
+            .map(|(key, value)| (key, Filter::Eq(value)))
+            .collect()
+    }
+}
+
+impl From<Value> for Filter {
+    fn from(value: Value) -> Self {
+        Filter::Eq(value)
+    }
+}
+
 #[async_trait]
 pub trait SettingView<SettingsData: Clone>: Send + Sync {
     /// View the settings data
     ///
-    /// All Executors should return an __count value containing the total count of the total number of entries
+    /// Executors that can cheaply count entries should also implement `count`
     async fn view<'a>(
         &self,
         context: &SettingsData,
         filters: indexmap::IndexMap<String, Value>,
     ) -> Result<Vec<indexmap::IndexMap<String, Value>>, Error>;
+
+    /// View a single page of the settings data, returning the page and the total number of entries
+    ///
+    /// Executors backed by large tables should implement this. The default implementation returns
+    /// None, in which case all entries are fetched through `view` and the requested page is sliced out
+    async fn view_page<'a>(
+        &self,
+        _context: &SettingsData,
+        _filters: indexmap::IndexMap<String, Value>,
+        _limit: usize,
+        _offset: usize,
+    ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+        Ok(None)
+    }
+
+    /// View a single page of the settings data ordered by `sort`, returning the page and the total number
+    /// of entries
+    ///
+    /// Ordering is ultimately the executor's responsibility, executors backed by large tables should
+    /// implement this (e.g. using `ORDER BY`). The default implementation returns None, in which case all
+    /// entries are fetched through `view` and sorted in memory before the requested page is sliced out
+    async fn view_sorted_page<'a>(
+        &self,
+        _context: &SettingsData,
+        _filters: indexmap::IndexMap<String, Value>,
+        _sort: &SortBy,
+        _limit: usize,
+        _offset: usize,
+    ) -> Result<Option<(Vec<indexmap::IndexMap<String, Value>>, usize)>, Error> {
+        Ok(None)
+    }
+
+    /// View the settings data matching filters with operators (see `Filter`)
+    ///
+    /// Executors that can evaluate operators themselves (e.g. in SQL) should implement this. The default
+    /// implementation returns None, in which case the equality filters are passed to `view` and the other
+    /// filters are evaluated in memory
+    async fn view_filtered<'a>(
+        &self,
+        _context: &SettingsData,
+        _filters: indexmap::IndexMap<String, Filter>,
+    ) -> Result<Option<Vec<indexmap::IndexMap<String, Value>>>, Error> {
+        Ok(None)
+    }
+
+    /// Count the entries matching the filters without fetching them
+    ///
+    /// The default implementation returns None, in which case the entries are fetched through `view`
+    /// and counted
+    async fn count<'a>(
+        &self,
+        _context: &SettingsData,
+        _filters: indexmap::IndexMap<String, Value>,
+    ) -> Result<Option<usize>, Error> {
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -234,6 +1567,10 @@ pub trait SettingCreator<SettingsData: Clone>: Send + Sync {
     ) -> Result<indexmap::IndexMap<String, Value>, Error>;
 }
 
+/// The error returned when a versioned update is based on an outdated version of the entry
+pub const VERSION_CONFLICT_ERROR: &str =
+    "This entry has been modified by someone else since it was viewed, please view it again and retry";
+
 #[async_trait]
 pub trait SettingUpdater<SettingsData: Clone>: Send + Sync {
     /// Updates the setting
@@ -242,6 +1579,23 @@ pub trait SettingUpdater<SettingsData: Clone>: Send + Sync {
         context: &SettingsData,
         state: indexmap::IndexMap<String, Value>,
     ) -> Result<indexmap::IndexMap<String, Value>, Error>;
+
+    /// Updates the setting only if its version column still holds `expected_version`, used for settings
+    /// with a version column. `state` already contains the incremented version
+    ///
+    /// Executors should implement this as a single conditional write (e.g. `UPDATE .. WHERE version = $1`)
+    /// failing with `VERSION_CONFLICT_ERROR` if the version does not match. The default implementation
+    /// returns None, in which case `update` is called, relying only on the check `settings_update` does
+    /// beforehand which can race with concurrent updates
+    async fn update_versioned<'a>(
+        &self,
+        _context: &SettingsData,
+        _state: indexmap::IndexMap<String, Value>,
+        _version_column: &'a str,
+        _expected_version: i64,
+    ) -> Result<Option<indexmap::IndexMap<String, Value>>, Error> {
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -250,7 +1604,112 @@ pub trait SettingDeleter<SettingsData: Clone>: Send + Sync {
     async fn delete<'a>(&self, context: &SettingsData, state: indexmap::IndexMap<String, Value>) -> Result<(), Error>;
 }
 
+#[async_trait]
+pub trait SettingValidator<SettingsData: Clone>: Send + Sync {
+    /// Validates constraints spanning multiple columns (e.g. `end_time` must be after `start_time`)
+    ///
+    /// `state` has already been validated column by column
+    async fn validate<'a>(
+        &self,
+        context: &SettingsData,
+        state: &indexmap::IndexMap<String, Value>,
+        operation_type: OperationType,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait]
+pub trait SettingAuthorizer<SettingsData: Clone>: Send + Sync {
+    /// Returns an error if `actor` is not allowed to perform the operation
+    ///
+    /// `actor` is the id of the user performing the operation (e.g. the Discord user id) or None if
+    /// the operation is not performed on behalf of a user
+    async fn authorize<'a>(
+        &self,
+        context: &SettingsData,
+        operation_type: OperationType,
+        actor: Option<&'a str>,
+    ) -> Result<(), Error>;
+}
+
+/// Hooks that are called after an operation has been successfully executed, e.g. for audit logging
+///
+/// Hooks run after the change has already been committed by the executor. An error returned by a
+/// hook is surfaced to the caller but does **not** roll back the change
+#[async_trait]
+pub trait SettingHooks<SettingsData: Clone>: Send + Sync {
+    /// Called after an entry has been created with the state returned by the creator
+    async fn on_create<'a>(
+        &self,
+        _context: &SettingsData,
+        _state: &indexmap::IndexMap<String, Value>,
+        _actor: Option<&'a str>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called after an entry has been updated with the state returned by the updater
+    async fn on_update<'a>(
+        &self,
+        _context: &SettingsData,
+        _state: &indexmap::IndexMap<String, Value>,
+        _actor: Option<&'a str>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called after an entry has been deleted with the primary key of the deleted entry
+    async fn on_delete<'a>(
+        &self,
+        _context: &SettingsData,
+        _state: &indexmap::IndexMap<String, Value>,
+        _actor: Option<&'a str>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Provides autocomplete choices for the columns of a setting at runtime (e.g. picking an existing tag)
+#[async_trait]
+pub trait SettingAutocompleter<SettingsData: Clone>: Send + Sync {
+    /// Returns the (label, value) choices for the partial input of the given column
+    ///
+    /// Returning None falls back to the static allowed values of the column. At most 25 choices are shown
+    async fn complete<'a>(
+        &self,
+        context: &SettingsData,
+        column_id: &'a str,
+        partial: &'a str,
+    ) -> Result<Option<Vec<(String, String)>>, Error>;
+}
+
 impl<SettingsData: Clone> SettingOperations<SettingsData> {
+    /// Sets the cross-column validator of the setting
+    pub fn with_validator<T: SettingValidator<SettingsData> + 'static>(mut self, v: T) -> Self {
+        self.validator = Some(settings_wrap(v));
+        self
+    }
+
+    /// Sets the authorizer of the setting
+    pub fn with_authorizer<T: SettingAuthorizer<SettingsData> + 'static>(mut self, v: T) -> Self {
+        self.authorizer = Some(settings_wrap(v));
+        self
+    }
+
+    /// Sets the post-operation hooks of the setting
+    pub fn with_hooks<T: SettingHooks<SettingsData> + 'static>(mut self, v: T) -> Self {
+        self.hooks = Some(settings_wrap(v));
+        self
+    }
+
+    /// Sets the autocomplete provider of the setting
+    pub fn with_autocompleter<T: SettingAutocompleter<SettingsData> + 'static>(
+        mut self,
+        v: T,
+    ) -> Self {
+        self.autocompleter = Some(settings_wrap(v));
+        self
+    }
+
     pub fn from<U>(v: U) -> Self
     where
         U: SettingView<SettingsData>
@@ -265,6 +1724,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: Some(settings_wrap(v.clone())),
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 }
@@ -277,6 +1740,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: None,
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -286,6 +1753,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v)),
             update: None,
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -295,6 +1766,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: Some(settings_wrap(v)),
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -304,6 +1779,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: None,
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -317,6 +1796,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v)),
             update: None,
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -330,6 +1813,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: Some(settings_wrap(v)),
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -343,6 +1830,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: None,
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -356,6 +1847,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: Some(settings_wrap(v)),
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -369,6 +1864,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: None,
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -382,6 +1881,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: Some(settings_wrap(v.clone())),
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -399,6 +1902,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: Some(settings_wrap(v)),
             delete: None,
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -416,6 +1923,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: None,
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -433,6 +1944,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: None,
             update: Some(settings_wrap(v.clone())),
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -450,6 +1965,10 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: Some(settings_wrap(v.clone())),
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 
@@ -468,6 +1987,426 @@ impl<SettingsData: Clone> SettingOperations<SettingsData> {
             create: Some(settings_wrap(v.clone())),
             update: Some(settings_wrap(v.clone())),
             delete: Some(settings_wrap(v)),
+            validator: None,
+            authorizer: None,
+            hooks: None,
+            autocompleter: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ColumnBuilder, SettingBuilder};
+    use crate::common_columns;
+    use crate::memory::InMemorySettings;
+    use serde_json::json;
+
+    fn string_column(id: &str, max_length: Option<usize>) -> Column {
+        ColumnBuilder::new(
+            id,
+            id,
+            ColumnType::new_scalar(InnerColumnType::String {
+                min_length: None,
+                max_length,
+                allowed_values: vec![],
+                kind: "normal".to_string(),
+                unchecked_ids: false,
+                channel_types: vec![],
+            }),
+        )
+        .build()
+    }
+
+    fn setting(columns: Vec<Column>) -> Setting<()> {
+        let primary_key = columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| c.id.to_string())
+            .collect();
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns(columns)
+            .operations(SettingOperations::from(InMemorySettings::new(primary_key)))
+            .build()
+            .unwrap()
+    }
+
+    fn id_column() -> Column {
+        let mut column = common_columns::integer("id", "ID", "The ID");
+        column.primary_key = true;
+        column
+    }
+
+    #[test]
+    fn override_raises_max_length() {
+        let setting = setting(vec![id_column(), string_column("name", Some(5))]);
+        let value = json!("premium name");
+
+        let column = setting.column("name").unwrap();
+        assert!(validate_value(value.clone(), &column.column_type, "name", false).is_err());
+
+        let overridden = setting
+            .with_overrides(&indexmap::indexmap! {
+                "name".to_string() => ColumnOverride {
+                    max_length: Some(20),
+                    ..Default::default()
+                },
+            })
+            .unwrap();
+
+        let column = overridden.column("name").unwrap();
+        assert!(validate_value(value, &column.column_type, "name", false).is_ok());
+
+        // The original setting is unchanged
+        let column = setting.column("name").unwrap();
+        assert!(matches!(
+            column.column_type.inner(),
+            InnerColumnType::String {
+                max_length: Some(5),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn override_numeric_constraints() {
+        let mut column = common_columns::integer("count", "Count", "The count");
+        column
+            .apply_override(&ColumnOverride {
+                allowed_values: Some(vec!["1".to_string(), "10".to_string()]),
+                min: Some(1.into()),
+                max: Some(10.into()),
+                nullable: Some(true),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(column.nullable);
+        assert_eq!(
+            column.column_type.inner(),
+            &InnerColumnType::Integer {
+                min: Some(1),
+                max: Some(10),
+                allowed_values: vec![1, 10],
+            }
+        );
+
+        let mut column = common_columns::float("ratio", "Ratio", "The ratio");
+        column
+            .apply_override(&ColumnOverride {
+                max: serde_json::Number::from_f64(0.5),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(matches!(
+            column.column_type.inner(),
+            InnerColumnType::Float { max: Some(max), .. } if *max == 0.5
+        ));
+    }
+
+    #[test]
+    fn override_rejects_constraints_the_type_does_not_have() {
+        let mut column = common_columns::integer("count", "Count", "The count");
+        assert!(column
+            .apply_override(&ColumnOverride {
+                max_length: Some(10),
+                ..Default::default()
+            })
+            .is_err());
+        assert!(column
+            .apply_override(&ColumnOverride {
+                allowed_values: Some(vec!["one".to_string()]),
+                ..Default::default()
+            })
+            .is_err());
+        assert!(column
+            .apply_override(&ColumnOverride {
+                min: serde_json::Number::from_f64(0.5),
+                ..Default::default()
+            })
+            .is_err());
+
+        let mut column = string_column("name", None);
+        assert!(column
+            .apply_override(&ColumnOverride {
+                max: Some(10.into()),
+                ..Default::default()
+            })
+            .is_err());
+
+        let mut column = common_columns::boolean("enabled", "Enabled", "Whether it is enabled");
+        assert!(column
+            .apply_override(&ColumnOverride {
+                allowed_values: Some(vec![]),
+                ..Default::default()
+            })
+            .is_err());
+
+        let setting = setting(vec![id_column()]);
+        assert!(setting
+            .with_overrides(&indexmap::indexmap! {
+                "missing".to_string() => ColumnOverride::default(),
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn mask_secrets_follows_secret_display() {
+        let mut masked = string_column("masked", None);
+        masked.secret = true;
+
+        let mut last_four = string_column("last_four", None);
+        last_four.secret = true;
+        last_four.secret_display = SecretDisplay::LastFour;
+
+        let mut omitted = string_column("omitted", None);
+        omitted.secret = true;
+        omitted.secret_display = SecretDisplay::Omit;
+
+        let setting = setting(vec![id_column(), masked, last_four, omitted]);
+
+        let row = indexmap::indexmap! {
+            "id".to_string() => json!(1),
+            "masked".to_string() => json!("hunter2"),
+            "last_four".to_string() => json!("0123456789abcdef"),
+            "omitted".to_string() => json!("hunter2"),
+        };
+
+        assert_eq!(
+            setting.mask_secrets(&row),
+            indexmap::indexmap! {
+                "id".to_string() => json!(1),
+                "masked".to_string() => json!(SECRET_PLACEHOLDER),
+                "last_four".to_string() => json!(format!("{}cdef", SECRET_PLACEHOLDER)),
+            }
+        );
+    }
+
+    #[test]
+    fn allowed_values_cover_numeric_types() {
+        let string = InnerColumnType::String {
+            min_length: None,
+            max_length: None,
+            allowed_values: vec!["a".to_string(), "b".to_string()],
+            kind: "normal".to_string(),
+            unchecked_ids: false,
+            channel_types: vec![],
+        };
+        assert_eq!(string.allowed_values().as_ref(), ["a", "b"]);
+
+        let integer = InnerColumnType::Integer {
+            min: None,
+            max: None,
+            allowed_values: vec![1, -20],
+        };
+        assert_eq!(integer.allowed_values().as_ref(), ["1", "-20"]);
+
+        let float = InnerColumnType::Float {
+            min: None,
+            max: None,
+            allowed_values: vec![0.5, 2.0],
+        };
+        assert_eq!(float.allowed_values().as_ref(), ["0.5", "2"]);
+
+        assert!(InnerColumnType::Boolean { strict: false }
+            .allowed_values()
+            .is_empty());
+    }
+
+    #[test]
+    fn numeric_allowed_values_pass_validation() {
+        for inner in [
+            InnerColumnType::Integer {
+                min: None,
+                max: None,
+                allowed_values: vec![1, -20],
+            },
+            InnerColumnType::Float {
+                min: None,
+                max: None,
+                allowed_values: vec![0.5, 2.0],
+            },
+        ] {
+            let column_type = ColumnType::new_scalar(inner.clone());
+
+            for value in inner.allowed_values().iter() {
+                assert!(validate_value(json!(value), &column_type, "col", false).is_ok());
+            }
+
+            assert!(validate_value(json!("3"), &column_type, "col", false).is_err());
+        }
+    }
+    /// A setting with a primary key, a required and a nullable column, a column with a default and a
+    /// column filled by the executor
+    fn mixed_setting() -> Setting<()> {
+        let mut note = string_column("note", None);
+        note.nullable = true;
+
+        let mut count = common_columns::integer("count", "Count", "The count");
+        count.default = Some(json!(0));
+
+        let mut created_at = string_column("created_at", None);
+        created_at.ignored_for = vec![OperationType::Create, OperationType::Update];
+
+        setting(vec![
+            id_column(),
+            string_column("name", None),
+            note,
+            count,
+            created_at,
+        ])
+    }
+
+    #[test]
+    fn required_and_optional_fields_for_create() {
+        let setting = mixed_setting();
+
+        assert_eq!(
+            setting.required_fields(OperationType::Create),
+            ["id", "name"]
+        );
+        assert_eq!(
+            setting.optional_fields(OperationType::Create),
+            ["note", "count"]
+        );
+    }
+
+    #[test]
+    fn required_and_optional_fields_for_update() {
+        let setting = mixed_setting();
+
+        // Only the primary key identifies the entry, everything else may be left unchanged
+        assert_eq!(setting.required_fields(OperationType::Update), ["id"]);
+        assert_eq!(
+            setting.optional_fields(OperationType::Update),
+            ["name", "note", "count"]
+        );
+    }
+
+    #[test]
+    fn required_and_optional_fields_for_delete() {
+        let mixed = mixed_setting();
+
+        assert_eq!(mixed.required_fields(OperationType::Delete), ["id"]);
+        assert!(mixed.optional_fields(OperationType::Delete).is_empty());
+
+        // With an alternate key either key may be used
+        let mut name = string_column("name", None);
+        name.alternate_key = true;
+        let alternate_key = setting(vec![id_column(), name]);
+
+        assert!(alternate_key
+            .required_fields(OperationType::Delete)
+            .is_empty());
+        assert_eq!(
+            alternate_key.optional_fields(OperationType::Delete),
+            ["id", "name"]
+        );
+    }
+    #[test]
+    fn confirmation_is_opt_in_except_for_delete() {
+        let ui_options = UiOptions::default();
+
+        assert!(ui_options.requires_confirmation_for(OperationType::Delete));
+        for operation_type in [
+            OperationType::View,
+            OperationType::Create,
+            OperationType::Update,
+        ] {
+            assert!(!ui_options.requires_confirmation_for(operation_type));
+        }
+
+        let ui_options = UiOptions {
+            requires_confirmation: vec![OperationType::Update],
+            skip_delete_confirmation: true,
+            ..Default::default()
+        };
+
+        assert!(ui_options.requires_confirmation_for(OperationType::Update));
+        assert!(!ui_options.requires_confirmation_for(OperationType::Create));
+        assert!(!ui_options.requires_confirmation_for(OperationType::Delete));
+    }
+    #[test]
+    fn has_column_distinguishes_typos() {
+        let setting = setting(vec![id_column(), string_column("channel_id", None)]);
+
+        assert!(setting.has_column("id"));
+        assert!(setting.has_column("channel_id"));
+        assert_eq!(setting.column("channel_id").unwrap().id, "channel_id");
+
+        for typo in ["chanel_id", "channel_ID", "channel_id ", "Id", ""] {
+            assert!(!setting.has_column(typo), "{}", typo);
+            assert!(setting.column(typo).is_none(), "{}", typo);
+        }
+    }
+    #[test]
+    fn field_value_limit_is_capped() {
+        assert_eq!(UiOptions::default().field_value_limit(), FIELD_VALUE_LIMIT);
+
+        for (limit, expected) in [
+            (100, 100),
+            (FIELD_VALUE_LIMIT, FIELD_VALUE_LIMIT),
+            (5000, FIELD_VALUE_LIMIT),
+        ] {
+            let ui_options = UiOptions {
+                field_value_limit: Some(limit),
+                ..Default::default()
+            };
+            assert_eq!(ui_options.field_value_limit(), expected);
+        }
+    }
+    fn build_with_ignored_primary_key(
+        ignored_for: Vec<OperationType>,
+        operations: SettingOperations<()>,
+    ) -> Result<Setting<()>, Error> {
+        let mut id = id_column();
+        id.ignored_for = ignored_for;
+
+        SettingBuilder::new("test", "Test", "A test setting")
+            .columns([id, string_column("name", None)])
+            .operations(operations)
+            .build()
+    }
+
+    #[test]
+    fn validate_schema_rejects_primary_key_ignored_for_delete_or_update() {
+        for operation_type in [OperationType::Delete, OperationType::Update] {
+            let err = build_with_ignored_primary_key(
+                vec![operation_type],
+                SettingOperations::from(InMemorySettings::new(vec!["id".to_string()])),
+            )
+            .unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Setting `test`: primary key column `id` cannot be ignored for {}",
+                    operation_type
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn validate_schema_allows_ignoring_unsupported_operations() {
+        let mut operations = SettingOperations::from(InMemorySettings::new(vec!["id".to_string()]));
+        operations.delete = None;
+
+        assert!(build_with_ignored_primary_key(vec![OperationType::Delete], operations).is_ok());
+    }
+    #[test]
+    fn description_with_deprecation_prefixes_the_note() {
+        let mut column = string_column("old_name", None);
+        column.description = "The old name".to_string();
+        assert_eq!(column.description_with_deprecation(), "The old name");
+
+        column.deprecated = Some("use name instead".to_string());
+        assert_eq!(
+            column.description_with_deprecation(),
+            "(Deprecated: use name instead) The old name"
+        );
+    }
+}